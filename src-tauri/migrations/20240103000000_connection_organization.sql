@@ -0,0 +1,5 @@
+ALTER TABLE connections ADD COLUMN folder VARCHAR(255);
+ALTER TABLE connections ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE connections ADD COLUMN environment VARCHAR(255) NOT NULL DEFAULT 'None';
+ALTER TABLE connections ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE connections ADD COLUMN last_used_at INTEGER;
@@ -41,10 +41,19 @@ fn main() {
             connections::update_connection,
             connections::delete_connection,
             connections::get_connections,
+            connections::reorder_connections,
             connections::init_connection,
             connections::disconnect,
             connections::set_schema,
+            connections::ping_connection,
+            connections::get_connection_info,
+            connections::parse_connection_url,
+            connections::discover_connections,
+            connections::export_connections,
+            connections::inspect_bundle,
+            connections::import_bundle,
             queries::sql_to_statements,
+            queries::geometry_to_geojson,
             queries::execute_query,
             queries::enqueue_query,
             queries::execute_tx,
@@ -55,12 +64,23 @@ fn main() {
             queries::get_procedures,
             queries::get_triggers,
             queries::get_table_structure,
+            queries::get_create_statement,
             queries::get_query_metadata,
             queries::query_results,
             queries::get_schemas,
             queries::get_views,
             queries::download_json,
             queries::download_csv,
+            queries::download_sql,
+            queries::download_xlsx,
+            queries::download_parquet,
+            queries::download_arrow,
+            queries::download_table,
+            queries::import_columns,
+            queries::import_file,
+            queries::transfer_data,
+            queries::dump_schema,
+            queries::restore_script,
             queries::invalidate_query,
             saved_queries::save_query,
             saved_queries::get_saved_queries,
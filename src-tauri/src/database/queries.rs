@@ -1,11 +1,13 @@
 use crate::utils::crypto::{decrypt_data, encrypt_data};
 use anyhow::Result;
 use magic_crypt::MagicCrypt256;
-use sqlx::sqlite::SqlitePool;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
 use sqlx::Row;
 use uuid::Uuid;
 
-use crate::engine::types::config::{ConnectionConfig, Credentials, Dialect, Metadata, Mode};
+use crate::engine::types::config::{
+    ConnectionConfig, ConnectionFilter, Credentials, Dialect, Environment, Metadata, Mode,
+};
 
 pub async fn add_connection(
     pool: &SqlitePool,
@@ -13,14 +15,20 @@ pub async fn add_connection(
     key: MagicCrypt256,
 ) -> Result<()> {
     let credentials = serde_json::to_string(&conn.credentials)?;
+    let metadata = serde_json::to_string(&conn.metadata)?;
     let credentials = encrypt_data(&credentials, &key);
     let id = conn.id.to_string();
     let dialect = conn.dialect.to_string();
     let mode = conn.mode.to_string();
 
+    let tags = serde_json::to_string(&conn.tags)?;
+    let environment = conn.environment.to_string();
+
     sqlx::query(
-        "INSERT INTO connections (id, dialect, mode, credentials, schema, name, color)
-         VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        "INSERT INTO connections
+           (id, dialect, mode, credentials, schema, name, color, metadata, folder, tags, environment, sort_order)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11,
+           (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM connections))",
     )
     .bind(&id)
     .bind(&dialect)
@@ -29,6 +37,10 @@ pub async fn add_connection(
     .bind(&conn.schema)
     .bind(&conn.name)
     .bind(&conn.color)
+    .bind(&metadata)
+    .bind(&conn.folder)
+    .bind(&tags)
+    .bind(&environment)
     .execute(pool)
     .await?;
 
@@ -46,11 +58,14 @@ pub async fn update_connection(
     let credentials = encrypt_data(&credentials, &key);
     let dialect = conn.dialect.to_string();
     let mode = conn.mode.to_string();
+    let tags = serde_json::to_string(&conn.tags)?;
+    let environment = conn.environment.to_string();
 
     sqlx::query(
         "UPDATE connections
-         SET dialect = $1, mode = $2, credentials = $3, schema = $4, name = $5, color = $6, metadata = $7
-         WHERE id = $8",
+         SET dialect = $1, mode = $2, credentials = $3, schema = $4, name = $5, color = $6, metadata = $7,
+             folder = $8, tags = $9, environment = $10
+         WHERE id = $11",
     )
     .bind(&dialect)
     .bind(&mode)
@@ -59,6 +74,9 @@ pub async fn update_connection(
     .bind(&conn.name)
     .bind(&conn.color)
     .bind(&metadata)
+    .bind(&conn.folder)
+    .bind(&tags)
+    .bind(&environment)
     .bind(&id)
     .execute(pool)
     .await?;
@@ -87,41 +105,75 @@ pub async fn update_connection_schema(
     Ok(())
 }
 
+pub async fn touch_connection(pool: &SqlitePool, id: &str) -> Result<()> {
+    sqlx::query("UPDATE connections SET last_used_at = unixepoch() WHERE id = $1")
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+pub async fn reorder_connections(pool: &SqlitePool, ids: &[String]) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    for (idx, id) in ids.iter().enumerate() {
+        sqlx::query("UPDATE connections SET sort_order = $1 WHERE id = $2")
+            .bind(idx as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
 pub async fn get_all_connections(
     pool: &SqlitePool,
     key: MagicCrypt256,
 ) -> Result<Vec<ConnectionConfig>> {
-    let rows = sqlx::query("SELECT * FROM connections")
-        .fetch_all(pool)
-        .await?;
+    get_connections(pool, &ConnectionFilter::default(), &key).await
+}
 
-    let mut items = Vec::new();
-    for row in rows {
-        let credentials: String = row.get("credentials");
-        let data = decrypt_data(&credentials, &key)?;
-        let credentials: Credentials = serde_json::from_str(&data)?;
-        let metadata_str: String = row.get("metadata");
-        let metadata: Metadata = serde_json::from_str(&metadata_str).unwrap_or_default();
-        let dialect_str: String = row.get("dialect");
-        let dialect: Dialect = dialect_str.parse()?;
-        let mode_str: String = row.get("mode");
-        let mode: Mode = mode_str.parse()?;
-        let schema: String = row.get("schema");
-        let id: String = row.get("id");
-
-        items.push(ConnectionConfig {
-            id: Uuid::parse_str(&id)?,
-            name: row.get("name"),
-            color: row.get("color"),
-            dialect,
-            mode,
-            credentials,
-            metadata,
-            schema,
-        });
+pub async fn get_connections(
+    pool: &SqlitePool,
+    filter: &ConnectionFilter,
+    key: &MagicCrypt256,
+) -> Result<Vec<ConnectionConfig>> {
+    let mut conditions = vec![];
+    let mut binds = vec![];
+    if let Some(folder) = &filter.folder {
+        binds.push(folder.clone());
+        conditions.push(format!("folder = ${}", binds.len()));
     }
+    if let Some(tag) = &filter.tag {
+        binds.push(tag.clone());
+        conditions.push(format!(
+            "EXISTS (SELECT 1 FROM json_each(tags) WHERE value = ${})",
+            binds.len()
+        ));
+    }
+    if let Some(environment) = &filter.environment {
+        binds.push(environment.to_string());
+        conditions.push(format!("environment = ${}", binds.len()));
+    }
+    if let Some(search) = filter.search.as_ref().filter(|s| !s.is_empty()) {
+        binds.push(format!("%{}%", search));
+        conditions.push(format!("name LIKE ${}", binds.len()));
+    }
+    let where_clause = match conditions.is_empty() {
+        true => String::new(),
+        false => format!("WHERE {}", conditions.join(" AND ")),
+    };
+    let sql = format!(
+        "SELECT * FROM connections {} ORDER BY sort_order, name COLLATE NOCASE",
+        where_clause
+    );
+    let mut query = sqlx::query(&sql);
+    for bind in &binds {
+        query = query.bind(bind);
+    }
+    let rows = query.fetch_all(pool).await?;
 
-    Ok(items)
+    rows.iter().map(|row| row_to_config(row, key)).collect()
 }
 
 pub async fn get_connection(
@@ -138,7 +190,10 @@ pub async fn get_connection(
         return Err(anyhow::anyhow!("Connection not found"));
     }
 
-    let row = &rows[0];
+    row_to_config(&rows[0], key)
+}
+
+fn row_to_config(row: &SqliteRow, key: &MagicCrypt256) -> Result<ConnectionConfig> {
     let credentials: String = row.get("credentials");
     let data = decrypt_data(&credentials, key)?;
     let credentials: Credentials = serde_json::from_str(&data)?;
@@ -148,6 +203,10 @@ pub async fn get_connection(
     let dialect: Dialect = dialect_str.parse()?;
     let mode_str: String = row.get("mode");
     let mode: Mode = mode_str.parse()?;
+    let tags_str: String = row.get("tags");
+    let tags: Vec<String> = serde_json::from_str(&tags_str).unwrap_or_default();
+    let environment_str: String = row.get("environment");
+    let environment: Environment = environment_str.parse().unwrap_or_default();
     let schema: String = row.get("schema");
     let conn_id: String = row.get("id");
 
@@ -160,5 +219,10 @@ pub async fn get_connection(
         credentials,
         schema,
         metadata,
+        folder: row.get("folder"),
+        tags,
+        environment,
+        sort_order: row.get("sort_order"),
+        last_used_at: row.get("last_used_at"),
     })
 }
@@ -8,6 +8,7 @@ pub struct ClickHouseClient {
     user: String,
     password: String,
     pub database: String,
+    read_only: bool,
 }
 
 impl ClickHouseClient {
@@ -20,29 +21,25 @@ impl ClickHouseClient {
             user: user.to_string(),
             password: password.to_string(),
             database: database.to_string(),
+            read_only: false,
         })
     }
 
-    pub async fn query(&self, sql: &str) -> Result<Value> {
-        let resp = self
-            .client
-            .post(&self.base_url)
-            .query(&[
-                ("database", self.database.as_str()),
-                ("default_format", "JSON"),
-                ("user", self.user.as_str()),
-                ("password", self.password.as_str()),
-            ])
-            .body(sql.to_string())
-            .send()
-            .await?;
+    /// Makes the server reject writes for every request of this client. Level 2
+    /// still lets the per request settings below through.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
 
-        let status = resp.status();
-        let body = resp.text().await?;
+    pub async fn query(&self, sql: &str) -> Result<Value> {
+        self.query_as(sql, "JSON").await
+    }
 
-        if !status.is_success() {
-            return Err(anyhow!("ClickHouse error: {}", body.trim()));
-        }
+    /// Runs a query in one of ClickHouse's JSON output formats, `JSONCompact` returns
+    /// rows as arrays.
+    pub async fn query_as(&self, sql: &str, format: &str) -> Result<Value> {
+        let (body, _) = self.send(sql, format, false).await?;
 
         if body.is_empty() {
             return Ok(serde_json::json!({
@@ -62,8 +59,107 @@ impl ClickHouseClient {
         }))
     }
 
+    /// Runs a statement without a result and returns what it wrote. The response is
+    /// held back until the statement finishes so the summary header is final.
+    pub async fn execute(&self, sql: &str) -> Result<Summary> {
+        let (_, summary) = self.send(sql, "JSON", true).await?;
+        Ok(summary)
+    }
+
+    /// Runs a query and returns the response as soon as it starts, for reading
+    /// its body a chunk at a time while the server still sends it.
+    pub async fn stream(&self, sql: &str, format: &str) -> Result<reqwest::Response> {
+        let resp = self.request(sql, format, false).await?;
+        if !resp.status().is_success() {
+            let body = resp.text().await?;
+            return Err(anyhow!("ClickHouse error: {}", body.trim()));
+        }
+        Ok(resp)
+    }
+
+    async fn send(&self, sql: &str, format: &str, wait: bool) -> Result<(String, Summary)> {
+        let resp = self.request(sql, format, wait).await?;
+        let status = resp.status();
+        let summary = resp
+            .headers()
+            .get("X-ClickHouse-Summary")
+            .and_then(|h| h.to_str().ok())
+            .map(Summary::parse)
+            .unwrap_or_default();
+        let body = resp.text().await?;
+
+        if !status.is_success() {
+            return Err(anyhow!("ClickHouse error: {}", body.trim()));
+        }
+        Ok((body, summary))
+    }
+
+    async fn request(&self, sql: &str, format: &str, wait: bool) -> Result<reqwest::Response> {
+        let mut request = self.client.post(&self.base_url);
+        if self.read_only {
+            request = request.query(&[("readonly", "2")]);
+        }
+        let resp = request
+            .query(&[
+                ("database", self.database.as_str()),
+                ("default_format", format),
+                // 64-bit integers are quoted by default, decimals have to be asked for
+                ("output_format_json_quote_64bit_integers", "1"),
+                ("output_format_json_quote_decimals", "1"),
+                ("wait_end_of_query", if wait { "1" } else { "0" }),
+                ("user", self.user.as_str()),
+                ("password", self.password.as_str()),
+            ])
+            .body(sql.to_string())
+            .send()
+            .await?;
+        Ok(resp)
+    }
+
     pub async fn ping(&self) -> Result<()> {
         self.query("SELECT 1").await?;
         Ok(())
     }
 }
+
+/// The counters of the `X-ClickHouse-Summary` header, sent as a JSON object of
+/// quoted numbers.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub written_rows: u64,
+    pub written_bytes: u64,
+}
+
+impl Summary {
+    fn parse(header: &str) -> Self {
+        let summary: Value = serde_json::from_str(header).unwrap_or_default();
+        let counter = |key: &str| {
+            summary
+                .get(key)
+                .and_then(|v| v.as_str().and_then(|s| s.parse().ok()).or(v.as_u64()))
+                .unwrap_or_default()
+        };
+        Summary {
+            written_rows: counter("written_rows"),
+            written_bytes: counter("written_bytes"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Summary;
+
+    #[test]
+    fn test_parse_summary() {
+        let header = r#"{"read_rows":"0","read_bytes":"0","written_rows":"3","written_bytes":"96","total_rows_to_read":"0"}"#;
+        assert_eq!(
+            Summary::parse(header),
+            Summary {
+                written_rows: 3,
+                written_bytes: 96
+            }
+        );
+        assert_eq!(Summary::parse("garbage"), Summary::default());
+    }
+}
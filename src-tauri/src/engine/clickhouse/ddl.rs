@@ -0,0 +1,105 @@
+use anyhow::{bail, Result};
+
+use crate::engine::types::{
+    config::Dialect,
+    connection::InitiatedConnection,
+    ddl::{text, ObjectType, SchemaObject, TableDdl},
+};
+
+use super::client::ClickHouseClient;
+use super::query::raw_query;
+
+pub async fn get_objects(
+    conn: &InitiatedConnection,
+    client: &ClickHouseClient,
+) -> Result<Vec<SchemaObject>> {
+    let schema = conn.get_schema();
+    // materialized views keep their rows in `.inner` tables they create themselves
+    let query = format!(
+        "SELECT name, engine FROM system.tables \
+         WHERE database = '{}' AND NOT is_temporary AND NOT startsWith(name, '.inner') \
+         ORDER BY name",
+        schema
+    );
+    let mut objects: Vec<SchemaObject> = raw_query(client, &query)
+        .await?
+        .iter()
+        .filter_map(|row| {
+            let object_type = match text(row, "engine")?.as_str() {
+                "View" => ObjectType::View,
+                "MaterializedView" => ObjectType::MaterializedView,
+                _ => ObjectType::Table,
+            };
+            Some(SchemaObject::new(object_type, &text(row, "name")?, None))
+        })
+        .collect();
+    let query = "SELECT name FROM system.functions WHERE origin = 'SQLUserDefined' ORDER BY name";
+    for row in raw_query(client, query).await? {
+        if let Some(name) = text(&row, "name") {
+            objects.push(SchemaObject::new(ObjectType::Function, &name, None));
+        }
+    }
+    Ok(objects)
+}
+
+pub async fn get_create_statement(
+    conn: &InitiatedConnection,
+    client: &ClickHouseClient,
+    object_type: ObjectType,
+    name: &str,
+) -> Result<String> {
+    let (query, column) = match object_type {
+        ObjectType::Table | ObjectType::View | ObjectType::MaterializedView => (
+            format!(
+                "SHOW CREATE TABLE {}.{}",
+                Dialect::ClickHouse.quote_ident(&conn.get_schema()),
+                Dialect::ClickHouse.quote_ident(name)
+            ),
+            "statement",
+        ),
+        ObjectType::Function => (
+            format!(
+                "SELECT create_query FROM system.functions WHERE name = '{}'",
+                name.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+            "create_query",
+        ),
+        // data skipping indices are part of `CREATE TABLE`
+        ObjectType::Index => (
+            format!(
+                "SELECT 'ALTER TABLE ' || backQuote(database) || '.' || backQuote(table) \
+                 || ' ADD INDEX ' || backQuote(name) || ' ' || expr || ' TYPE ' || type_full \
+                 || ' GRANULARITY ' || toString(granularity) AS statement \
+                 FROM system.data_skipping_indices WHERE database = '{}' AND name = '{}' \
+                 ORDER BY table",
+                conn.get_schema(),
+                name.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+            "statement",
+        ),
+        ObjectType::Trigger | ObjectType::Procedure => {
+            bail!("ClickHouse has no triggers or stored procedures")
+        }
+        ObjectType::Extension | ObjectType::Type => bail!("ClickHouse has no extensions or types"),
+    };
+    let statements: Vec<String> = raw_query(client, &query)
+        .await?
+        .iter()
+        .filter_map(|row| text(row, column))
+        .collect();
+    if statements.is_empty() {
+        bail!("{} was not found", name);
+    }
+    // every table's index of that name
+    Ok(statements.join(";\n\n"))
+}
+
+/// `SHOW CREATE TABLE` has the table's data skipping indices.
+pub async fn get_table_ddl(
+    conn: &InitiatedConnection,
+    client: &ClickHouseClient,
+    table: &str,
+) -> Result<TableDdl> {
+    let create = get_create_statement(conn, client, ObjectType::Table, table).await?;
+    Ok(TableDdl::new(create))
+}
@@ -1,3 +1,5 @@
 pub mod client;
+pub mod ddl;
 pub mod query;
+pub mod sql_to_json;
 pub mod tables;
@@ -1,10 +1,13 @@
-use anyhow::{anyhow, Result};
-use serde_json::Value;
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Map, Value};
+use tokio_util::sync::CancellationToken;
 
 use crate::database::QueryType;
-use crate::engine::types::result::ResultSet;
+use crate::engine::types::config::Dialect;
+use crate::engine::types::result::{PageSender, ResultColumn, ResultSet};
 
 use super::client::ClickHouseClient;
+use super::sql_to_json::ChType;
 
 pub async fn raw_query(client: &ClickHouseClient, query: &str) -> Result<Vec<Value>> {
     let resp = client.query(query).await?;
@@ -28,12 +31,26 @@ pub async fn execute_query(
 
     match t {
         QueryType::Select | QueryType::Show => {
-            let resp = client.query(query).await?;
-            let rows = resp
-                .get("data")
-                .and_then(|d| d.as_array())
-                .cloned()
-                .unwrap_or_default();
+            let mut resp = client.query_as(query, "JSONCompact").await?;
+            let result_columns = result_columns(resp.get("meta"));
+            let types: Vec<ChType> = result_columns
+                .iter()
+                .map(|c| ChType::parse(&c.type_name))
+                .collect();
+            let rows = match resp.get_mut("data").map(Value::take) {
+                Some(Value::Array(rows)) => rows
+                    .into_iter()
+                    .map(|row| match row {
+                        Value::Array(values) => types
+                            .iter()
+                            .zip(values)
+                            .map(|(t, v)| t.decode(v))
+                            .collect(),
+                        _ => vec![],
+                    })
+                    .collect(),
+                _ => vec![],
+            };
             let end_time = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .expect("Time went backwards")
@@ -42,12 +59,14 @@ pub async fn execute_query(
                 start_time,
                 end_time,
                 affected_rows: 0,
+                written_bytes: None,
+                result_columns,
                 rows,
                 table: None,
             })
         }
         _ => {
-            client.query(query).await?;
+            let summary = client.execute(query).await?;
             let end_time = std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .expect("Time went backwards")
@@ -55,7 +74,9 @@ pub async fn execute_query(
             Ok(ResultSet {
                 start_time,
                 end_time,
-                affected_rows: 0,
+                affected_rows: summary.written_rows,
+                written_bytes: Some(summary.written_bytes),
+                result_columns: vec![],
                 rows: vec![],
                 table: None,
             })
@@ -63,6 +84,81 @@ pub async fn execute_query(
     }
 }
 
+fn result_columns(meta: Option<&Value>) -> Vec<ResultColumn> {
+    meta.and_then(|m| m.as_array())
+        .into_iter()
+        .flatten()
+        .map(|c| {
+            let name = c.get("name").and_then(Value::as_str).unwrap_or_default();
+            let kind = c.get("type").and_then(Value::as_str).unwrap_or_default();
+            let nullable = kind.starts_with("Nullable(")
+                || kind.starts_with("LowCardinality(Nullable(");
+            ResultColumn::new(name, kind, Some(nullable))
+        })
+        .collect()
+}
+
+/// Reads the rows of a query a line at a time as the server sends them and
+/// sends them in pages, see `exec::stream_query`. The first two lines name the
+/// columns and their types.
+pub async fn stream_query(client: &ClickHouseClient, query: &str, mut pages: PageSender) {
+    let result = async {
+        let mut resp = client
+            .stream(query, "JSONCompactEachRowWithNamesAndTypes")
+            .await?;
+        let mut header = vec![];
+        let mut types = vec![];
+        let mut buffer = vec![];
+        loop {
+            let chunk = resp.chunk().await?;
+            match &chunk {
+                Some(chunk) => buffer.extend_from_slice(chunk),
+                None => buffer.push(b'\n'),
+            }
+            while let Some(end) = buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if line.trim_ascii().is_empty() {
+                    continue;
+                }
+                // errors after the first rows come in the body
+                let values = match serde_json::from_slice(&line) {
+                    Ok(Value::Array(values)) => values,
+                    _ => {
+                        let line = String::from_utf8_lossy(&line);
+                        return Err(anyhow!("ClickHouse error: {}", line.trim()));
+                    }
+                };
+                if header.len() < 2 {
+                    header.push(values);
+                    if let [names, kinds] = &header[..] {
+                        let meta: Vec<Value> = names
+                            .iter()
+                            .zip(kinds)
+                            .map(|(name, kind)| json!({"name": name, "type": kind}))
+                            .collect();
+                        pages.columns = result_columns(Some(&Value::Array(meta)));
+                        types = pages
+                            .columns
+                            .iter()
+                            .map(|c| ChType::parse(&c.type_name))
+                            .collect();
+                    }
+                    continue;
+                }
+                let row = types.iter().zip(values).map(|(t, v)| t.decode(v)).collect();
+                if !pages.push(row).await {
+                    return Ok(());
+                }
+            }
+            if chunk.is_none() {
+                return Ok(());
+            }
+        }
+    }
+    .await;
+    pages.finish(result).await;
+}
+
 pub async fn execute_tx(client: &ClickHouseClient, queries: Vec<&str>) -> Result<()> {
     for q in queries {
         client
@@ -72,3 +168,53 @@ pub async fn execute_tx(client: &ClickHouseClient, queries: Vec<&str>) -> Result
     }
     Ok(())
 }
+
+/// Runs the statements of a script in order, calling `progress` with the
+/// number run so far. ClickHouse has no transactions to run them in.
+pub async fn execute_script(
+    client: &ClickHouseClient,
+    statements: &[String],
+    progress: impl Fn(usize),
+    token: &CancellationToken,
+) -> Result<()> {
+    for (i, statement) in statements.iter().enumerate() {
+        tokio::select! {
+            _ = token.cancelled() => bail!("The script was cancelled"),
+            res = client.execute(statement) => {
+                res.map_err(|e| anyhow!("Statement {} failed: {}", i + 1, e))?;
+            }
+        }
+        progress(i + 1);
+    }
+    Ok(())
+}
+
+/// Inserts rows in one request as `JSONEachRow`, which ClickHouse parses
+/// without building a statement per row.
+pub async fn insert_rows(
+    client: &ClickHouseClient,
+    table: &str,
+    columns: &[ResultColumn],
+    rows: &[Vec<Value>],
+) -> Result<u64> {
+    let names: Vec<String> = columns
+        .iter()
+        .map(|c| Dialect::ClickHouse.quote_ident(&c.name))
+        .collect();
+    let mut sql = format!(
+        "INSERT INTO {} ({}) FORMAT JSONEachRow\n",
+        Dialect::ClickHouse.quote_table(table),
+        names.join(", ")
+    );
+    for row in rows {
+        let object: Map<String, Value> = columns
+            .iter()
+            .map(|c| c.name.clone())
+            .zip(row.iter().cloned())
+            .collect();
+        sql += &Value::Object(object).to_string();
+        sql.push('\n');
+    }
+    let summary = client.execute(&sql).await?;
+    Ok(summary.written_rows)
+}
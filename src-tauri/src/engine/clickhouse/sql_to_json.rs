@@ -0,0 +1,172 @@
+use serde_json::{Map, Value};
+
+use crate::engine::types::value;
+
+/// The parts of a ClickHouse type that change how its JSON output is read.
+/// `Nullable` and `LowCardinality` wrappers are dropped, nulls come through as is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChType {
+    /// 64-bit and wider integers, quoted in the output
+    BigInt,
+    /// `Decimal(P, S)` and its sized aliases, quoted in the output
+    Decimal,
+    Array(Box<ChType>),
+    /// Elements with their names when the tuple is named
+    Tuple(Vec<(Option<String>, ChType)>),
+    Map(Box<ChType>),
+    /// Strings, dates, enums and the numbers JSON can hold as they are
+    Plain,
+}
+
+impl ChType {
+    pub fn parse(name: &str) -> ChType {
+        let name = name.trim();
+        let (base, args) = match name.split_once('(') {
+            Some((base, rest)) if rest.ends_with(')') => {
+                (base.trim(), split_args(&rest[..rest.len() - 1]))
+            }
+            _ => (name, vec![]),
+        };
+        match (base, args.as_slice()) {
+            ("Nullable" | "LowCardinality", [inner]) => ChType::parse(inner),
+            ("Int64" | "UInt64" | "Int128" | "UInt128" | "Int256" | "UInt256", _) => ChType::BigInt,
+            (base, _) if base.starts_with("Decimal") => ChType::Decimal,
+            ("Array", [inner]) => ChType::Array(Box::new(ChType::parse(inner))),
+            ("Map", [_, inner]) => ChType::Map(Box::new(ChType::parse(inner))),
+            ("Tuple", elements) => ChType::Tuple(elements.iter().map(|e| element(e)).collect()),
+            // Nested(a T, b U) is sent as an array of named tuples
+            ("Nested", elements) => ChType::Array(Box::new(ChType::Tuple(
+                elements.iter().map(|e| element(e)).collect(),
+            ))),
+            _ => ChType::Plain,
+        }
+    }
+
+    /// Keeps exact numbers as strings of their digits and turns named tuples into
+    /// objects, recursing into arrays, tuples and maps.
+    pub fn decode(&self, v: Value) -> Value {
+        match (self, v) {
+            (_, Value::Null) => Value::Null,
+            (ChType::BigInt, Value::String(s)) => value::bigint(s),
+            (ChType::BigInt, Value::Number(n)) => value::bigint(n),
+            (ChType::Decimal, Value::String(s)) => value::decimal(s),
+            (ChType::Decimal, Value::Number(n)) => value::decimal(n.to_string()),
+            (ChType::Array(inner), Value::Array(values)) => {
+                Value::Array(values.into_iter().map(|v| inner.decode(v)).collect())
+            }
+            (ChType::Map(inner), Value::Object(entries)) => Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(k, v)| (k, inner.decode(v)))
+                    .collect(),
+            ),
+            (ChType::Tuple(elements), Value::Array(values)) => {
+                let named = elements.iter().all(|(name, _)| name.is_some());
+                let decoded = elements
+                    .iter()
+                    .zip(values)
+                    .map(|((name, t), v)| (name, t.decode(v)));
+                match named {
+                    true => Value::Object(
+                        decoded
+                            .map(|(name, v)| (name.clone().unwrap_or_default(), v))
+                            .collect::<Map<_, _>>(),
+                    ),
+                    false => Value::Array(decoded.map(|(_, v)| v).collect()),
+                }
+            }
+            // named tuples may already be objects, depending on
+            // `output_format_json_named_tuples_as_objects`
+            (ChType::Tuple(elements), Value::Object(mut entries)) => Value::Object(
+                elements
+                    .iter()
+                    .filter_map(|(name, t)| {
+                        let name = name.clone()?;
+                        let v = entries.remove(&name)?;
+                        Some((name, t.decode(v)))
+                    })
+                    .collect(),
+            ),
+            (_, v) => v,
+        }
+    }
+}
+
+/// Reads a tuple element, `name Type` or just `Type`. Only named elements have
+/// whitespace outside of parentheses.
+fn element(arg: &str) -> (Option<String>, ChType) {
+    let mut depth = 0;
+    for (i, c) in arg.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                let name = arg[..i].trim_matches('`').to_string();
+                return (Some(name), ChType::parse(&arg[i..]));
+            }
+            _ => {}
+        }
+    }
+    (None, ChType::parse(arg))
+}
+
+/// Splits type arguments on top level commas, skipping over nested types and
+/// quoted enum labels.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let (mut depth, mut quoted, mut escaped, mut start) = (0, false, false, 0);
+    for (i, c) in args.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '\'' => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth -= 1,
+            ',' if !quoted && depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = args[start..].trim();
+    if !last.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::ChType;
+
+    #[test]
+    fn test_decode() {
+        let t = ChType::parse("Array(Nullable(Decimal(10, 2)))");
+        assert_eq!(
+            t.decode(json!([1.5, "1.10", null])),
+            json!(["1.5", "1.10", null])
+        );
+
+        let t = ChType::parse("Tuple(id UInt64, tags Map(String, Int64), `e` Enum8('a, b' = 1))");
+        assert_eq!(
+            t.decode(json!([18446744073709551615u64, {"x": 7}, "a, b"])),
+            json!({
+                "id": "18446744073709551615",
+                "tags": {"x": "7"},
+                "e": "a, b"
+            })
+        );
+
+        let t = ChType::parse("Nested(n Int64, s LowCardinality(String))");
+        assert_eq!(t.decode(json!([["1", "a"]])), json!([{"n": "1", "s": "a"}]));
+
+        let t = ChType::parse("Tuple(DateTime64(3, 'UTC'), Int32)");
+        assert_eq!(
+            t.decode(json!(["2024-01-01 00:00:00.000", 5])),
+            json!(["2024-01-01 00:00:00.000", 5])
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use serde_json::{json, Value};
 
-use crate::engine::types::connection::InitiatedConnection;
+use crate::engine::types::connection::{ConnectionInfo, InitiatedConnection};
 
 use super::client::ClickHouseClient;
 use super::query::raw_query;
@@ -151,3 +151,18 @@ pub async fn get_views(
     );
     raw_query(client, &query).await
 }
+
+pub async fn get_connection_info(client: &ClickHouseClient) -> Result<ConnectionInfo> {
+    let rows = raw_query(client, "SELECT version() AS version").await?;
+    let server_version = rows
+        .first()
+        .and_then(|r| r.get("version"))
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+        .to_string();
+    // the client talks plain http to the server
+    Ok(ConnectionInfo {
+        server_version,
+        tls: None,
+    })
+}
@@ -0,0 +1,592 @@
+use aes::{
+    cipher::{generic_array::GenericArray, BlockDecrypt, KeyInit},
+    Aes128,
+};
+use anyhow::{anyhow, bail, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::engine::types::config::{ConnectionConfig, Credentials, Dialect, Metadata, Mode};
+
+use super::url::{default_name, normalize_ssl_mode, DEFAULT_COLOR};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiscoveredConnection {
+    pub source: String,
+    pub config: ConnectionConfig,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SshHost {
+    pub alias: String,
+    pub host: String,
+    pub port: Option<String>,
+    pub user: Option<String>,
+    pub identity_file: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Discovery {
+    pub connections: Vec<DiscoveredConnection>,
+    pub ssh_hosts: Vec<SshHost>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgPassEntry {
+    pub host: String,
+    pub port: String,
+    pub database: String,
+    pub user: String,
+    pub password: String,
+}
+
+impl PgPassEntry {
+    fn matches(&self, host: &str, port: &str, database: &str, user: &str) -> bool {
+        let field = |pattern: &str, value: &str| pattern == "*" || pattern == value;
+        field(&self.host, host)
+            && field(&self.port, port)
+            && field(&self.database, database)
+            && field(&self.user, user)
+    }
+}
+
+type IniSection = (String, Vec<(String, String)>);
+
+/// Reads every known client configuration file under `home`. Missing files are
+/// skipped, unreadable ones are logged and skipped.
+pub fn discover(home: &Path) -> Result<Discovery> {
+    let mut discovery = Discovery::default();
+
+    let pgpass_path = std::env::var("PGPASSFILE")
+        .map(PathBuf::from)
+        .unwrap_or(default_pgpass_path(home));
+    let pgpass = read_optional(&pgpass_path)
+        .map(|c| parse_pgpass(&c))
+        .unwrap_or_default();
+    for (idx, entry) in pgpass.iter().enumerate() {
+        match pgpass_to_config(entry) {
+            Ok(Some(config)) => discovery.connections.push(DiscoveredConnection {
+                source: format!("{}:{}", pgpass_path.display(), idx + 1),
+                config,
+            }),
+            Ok(None) => {}
+            Err(e) => warn!("Skipping pgpass entry: {}", e),
+        }
+    }
+
+    let mut service_files = vec![std::env::var("PGSERVICEFILE")
+        .map(PathBuf::from)
+        .unwrap_or(home.join(".pg_service.conf"))];
+    if let Ok(dir) = std::env::var("PGSYSCONFDIR") {
+        service_files.push(PathBuf::from(dir).join("pg_service.conf"));
+    }
+    for path in service_files {
+        let Some(content) = read_optional(&path) else {
+            continue;
+        };
+        for (service, config) in parse_pg_service(&content, &pgpass) {
+            discovery.connections.push(DiscoveredConnection {
+                source: format!("{} [{}]", path.display(), service),
+                config,
+            });
+        }
+    }
+
+    let my_cnf = home.join(".my.cnf");
+    if let Some(content) = read_optional(&my_cnf) {
+        for (group, config) in parse_my_cnf(&content) {
+            discovery.connections.push(DiscoveredConnection {
+                source: format!("{} [{}]", my_cnf.display(), group),
+                config,
+            });
+        }
+    }
+
+    let mylogin = std::env::var("MYSQL_TEST_LOGIN_FILE")
+        .map(PathBuf::from)
+        .unwrap_or(default_mylogin_path(home));
+    if mylogin.exists() {
+        let content = fs::read(&mylogin)
+            .map_err(anyhow::Error::from)
+            .and_then(|data| decrypt_mylogin(&data));
+        match content {
+            Ok(content) => {
+                for (login_path, config) in parse_mylogin(&content) {
+                    discovery.connections.push(DiscoveredConnection {
+                        source: format!("{} [{}]", mylogin.display(), login_path),
+                        config,
+                    });
+                }
+            }
+            Err(e) => warn!("Failed to read {}: {}", mylogin.display(), e),
+        }
+    }
+
+    if let Some(content) = read_optional(&home.join(".ssh").join("config")) {
+        discovery.ssh_hosts = parse_ssh_config(&content);
+    }
+
+    Ok(discovery)
+}
+
+fn default_pgpass_path(home: &Path) -> PathBuf {
+    #[cfg(windows)]
+    if let Ok(appdata) = std::env::var("APPDATA") {
+        return PathBuf::from(appdata)
+            .join("postgresql")
+            .join("pgpass.conf");
+    }
+    home.join(".pgpass")
+}
+
+fn default_mylogin_path(home: &Path) -> PathBuf {
+    #[cfg(windows)]
+    if let Ok(appdata) = std::env::var("APPDATA") {
+        return PathBuf::from(appdata).join("MySQL").join(".mylogin.cnf");
+    }
+    home.join(".mylogin.cnf")
+}
+
+fn read_optional(path: &Path) -> Option<String> {
+    if !path.exists() {
+        return None;
+    }
+    match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// Parses `hostname:port:database:username:password` lines, honouring `\:` and `\\`
+/// escapes.
+pub fn parse_pgpass(content: &str) -> Vec<PgPassEntry> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = vec![String::new()];
+            let mut chars = line.chars();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some(next) = chars.next() {
+                            fields.last_mut()?.push(next);
+                        }
+                    }
+                    ':' if fields.len() < 5 => fields.push(String::new()),
+                    _ => fields.last_mut()?.push(c),
+                }
+            }
+            if fields.len() != 5 {
+                return None;
+            }
+            let mut fields = fields.into_iter();
+            Some(PgPassEntry {
+                host: fields.next()?,
+                port: fields.next()?,
+                database: fields.next()?,
+                user: fields.next()?,
+                password: fields.next()?,
+            })
+        })
+        .collect()
+}
+
+fn pgpass_to_config(entry: &PgPassEntry) -> Result<Option<ConnectionConfig>> {
+    // Without a concrete user there is nothing meaningful to connect as.
+    if entry.user == "*" {
+        return Ok(None);
+    }
+    let wildcard = |value: &str, default: &str| {
+        if value == "*" {
+            default.to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    let mut credentials = Credentials::new();
+    credentials.insert("host".to_string(), wildcard(&entry.host, "localhost"));
+    credentials.insert("port".to_string(), wildcard(&entry.port, "5432"));
+    credentials.insert("db_name".to_string(), wildcard(&entry.database, "postgres"));
+    credentials.insert("user".to_string(), entry.user.clone());
+    credentials.insert("password".to_string(), entry.password.clone());
+    let name = default_name(&credentials);
+    Ok(Some(ConnectionConfig::new(
+        Dialect::Postgresql,
+        Mode::Host,
+        credentials,
+        &name,
+        DEFAULT_COLOR,
+        Metadata::default(),
+    )?))
+}
+
+/// Parses a libpq service file; passwords missing from a service are looked up in
+/// the pgpass entries the same way libpq would.
+pub fn parse_pg_service(content: &str, pgpass: &[PgPassEntry]) -> Vec<(String, ConnectionConfig)> {
+    parse_ini(content)
+        .into_iter()
+        .filter_map(|(service, keys)| {
+            let mut credentials = Credentials::new();
+            for (key, value) in keys {
+                let key = match key.as_str() {
+                    "dbname" => "db_name",
+                    "sslmode" => {
+                        credentials.insert("ssl_mode".to_string(), normalize_ssl_mode(&value));
+                        continue;
+                    }
+                    "sslrootcert" => "ca_cert",
+                    "sslcert" => "client_cert",
+                    "sslkey" => "client_key",
+                    k => k,
+                };
+                credentials.insert(key.to_string(), value);
+            }
+            let host = credentials
+                .get("host")
+                .cloned()
+                .unwrap_or("localhost".to_string());
+            let port = credentials
+                .get("port")
+                .cloned()
+                .unwrap_or("5432".to_string());
+            credentials.insert("host".to_string(), host.clone());
+            credentials.insert("port".to_string(), port.clone());
+            if !credentials.contains_key("password") {
+                let db = credentials.get("db_name").cloned().unwrap_or_default();
+                let user = credentials.get("user").cloned().unwrap_or_default();
+                if let Some(entry) = pgpass.iter().find(|e| e.matches(&host, &port, &db, &user)) {
+                    credentials.insert("password".to_string(), entry.password.clone());
+                }
+            }
+            ConnectionConfig::new(
+                Dialect::Postgresql,
+                Mode::Host,
+                credentials,
+                &service,
+                DEFAULT_COLOR,
+                Metadata::default(),
+            )
+            .map_err(|e| warn!("Skipping service {}: {}", service, e))
+            .ok()
+            .map(|config| (service, config))
+        })
+        .collect()
+}
+
+/// Parses `.my.cnf` option groups. `[client]` and `[mysql]` form the default
+/// connection, and every `[client_<suffix>]`/`[mysql_<suffix>]` group is layered on
+/// top of it the way `--defaults-group-suffix` does.
+pub fn parse_my_cnf(content: &str) -> Vec<(String, ConnectionConfig)> {
+    let sections = parse_ini(content);
+    let base: Vec<(String, String)> = sections
+        .iter()
+        .filter(|(name, _)| name == "client" || name == "mysql")
+        .flat_map(|(_, keys)| keys.clone())
+        .collect();
+
+    let mut groups: Vec<(String, Vec<(String, String)>)> = vec![];
+    for (name, keys) in &sections {
+        let suffix = name
+            .strip_prefix("client")
+            .or(name.strip_prefix("mysql"))
+            .and_then(|s| s.strip_prefix(['_', '-']))
+            .filter(|s| !s.is_empty());
+        let Some(suffix) = suffix else {
+            continue;
+        };
+        let group = suffix.to_string();
+        match groups.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, existing)) => existing.extend(keys.clone()),
+            None => groups.push((group, keys.clone())),
+        }
+    }
+
+    let mut result = vec![];
+    if !base.is_empty() {
+        if let Some(config) = my_cnf_to_config("client", &base) {
+            result.push(("client".to_string(), config));
+        }
+    }
+    for (group, keys) in groups {
+        let merged: Vec<(String, String)> = base.iter().cloned().chain(keys).collect();
+        if let Some(config) = my_cnf_to_config(&group, &merged) {
+            result.push((group, config));
+        }
+    }
+    result
+}
+
+fn my_cnf_to_config(group: &str, keys: &[(String, String)]) -> Option<ConnectionConfig> {
+    let mut credentials = Credentials::new();
+    for (key, value) in keys {
+        let key = match key.replace('_', "-").as_str() {
+            "host" => "host",
+            "port" => "port",
+            "user" => "user",
+            "password" => "password",
+            "database" => "db_name",
+            "socket" => "socket",
+            "ssl-ca" => "ca_cert",
+            "ssl-cert" => "client_cert",
+            "ssl-key" => "client_key",
+            "ssl-mode" => {
+                credentials.insert("ssl_mode".to_string(), normalize_ssl_mode(value));
+                continue;
+            }
+            _ => continue,
+        };
+        credentials.insert(key.to_string(), value.clone());
+    }
+    if !credentials.contains_key("user") && !credentials.contains_key("host") {
+        return None;
+    }
+    let mode = if credentials.contains_key("socket") && !credentials.contains_key("host") {
+        Mode::Socket
+    } else {
+        Mode::Host
+    };
+    let name = match group {
+        "client" => default_name(&credentials),
+        g => g.to_string(),
+    };
+    ConnectionConfig::new(
+        Dialect::Mysql,
+        mode,
+        credentials,
+        &name,
+        DEFAULT_COLOR,
+        Metadata::default(),
+    )
+    .map_err(|e| warn!("Skipping my.cnf group {}: {}", group, e))
+    .ok()
+}
+
+/// Decrypts the login path file `mysql_config_editor` writes: 4 unused bytes,
+/// a 20 byte key folded into an AES-128 key, then every line as a length
+/// prefixed AES-128-ECB ciphertext with PKCS#7 padding.
+pub fn decrypt_mylogin(data: &[u8]) -> Result<String> {
+    let key_bytes = data
+        .get(4..24)
+        .ok_or_else(|| anyhow!("The login path file is truncated"))?;
+    let mut key = [0u8; 16];
+    for (i, b) in key_bytes.iter().enumerate() {
+        key[i % 16] ^= b;
+    }
+    let cipher = Aes128::new(&key.into());
+    let mut content = vec![];
+    let mut rest = &data[24..];
+    while rest.len() >= 4 {
+        let len = u32::from_le_bytes([rest[0], rest[1], rest[2], rest[3]]) as usize;
+        let mut line = rest
+            .get(4..4 + len)
+            .filter(|line| !line.is_empty() && line.len() % 16 == 0)
+            .ok_or_else(|| anyhow!("The login path file is corrupted"))?
+            .to_vec();
+        for block in line.chunks_exact_mut(16) {
+            cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+        let padding = line[line.len() - 1] as usize;
+        if padding == 0 || padding > 16 {
+            bail!("The login path file is corrupted");
+        }
+        line.truncate(line.len() - padding);
+        content.extend(line);
+        rest = &rest[4 + len..];
+    }
+    Ok(String::from_utf8(content)?)
+}
+
+/// Parses decrypted login paths. `[client]` is the default one and the other
+/// paths are layered on top of it as `mysql --login-path` does.
+pub fn parse_mylogin(content: &str) -> Vec<(String, ConnectionConfig)> {
+    let sections = parse_ini(content);
+    let base: Vec<(String, String)> = sections
+        .iter()
+        .filter(|(name, _)| name == "client")
+        .flat_map(|(_, keys)| keys.clone())
+        .collect();
+    sections
+        .iter()
+        .filter_map(|(login_path, keys)| {
+            let keys: Vec<(String, String)> = match login_path.as_str() {
+                "client" => keys.clone(),
+                _ => base.iter().chain(keys).cloned().collect(),
+            };
+            my_cnf_to_config(login_path, &keys).map(|config| (login_path.clone(), config))
+        })
+        .collect()
+}
+
+/// Parses `Host` blocks of an OpenSSH client config. Wildcard patterns and `Match`
+/// blocks are skipped since they don't name a concrete host.
+pub fn parse_ssh_config(content: &str) -> Vec<SshHost> {
+    let mut hosts: Vec<SshHost> = vec![];
+    let mut current: Vec<usize> = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((k, v)) => (
+                k.to_lowercase(),
+                v.trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+                    .trim()
+                    .trim_matches('"')
+                    .to_string(),
+            ),
+            None => continue,
+        };
+        match key.as_str() {
+            "host" => {
+                current = vec![];
+                for alias in value.split_whitespace() {
+                    if alias.contains(['*', '?', '!']) {
+                        continue;
+                    }
+                    current.push(hosts.len());
+                    hosts.push(SshHost {
+                        alias: alias.to_string(),
+                        host: alias.to_string(),
+                        ..Default::default()
+                    });
+                }
+            }
+            "match" => current = vec![],
+            "hostname" => current.iter().for_each(|i| hosts[*i].host = value.clone()),
+            "port" => current
+                .iter()
+                .for_each(|i| hosts[*i].port = Some(value.clone())),
+            "user" => current
+                .iter()
+                .for_each(|i| hosts[*i].user = Some(value.clone())),
+            "identityfile" => current
+                .iter()
+                .for_each(|i| hosts[*i].identity_file = Some(value.clone())),
+            _ => {}
+        }
+    }
+    hosts
+}
+
+fn parse_ini(content: &str) -> Vec<IniSection> {
+    let mut sections: Vec<IniSection> = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';', '!']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), vec![]));
+            continue;
+        }
+        let Some((_, keys)) = sections.last_mut() else {
+            continue;
+        };
+        let (key, value) = line.split_once('=').unwrap_or((line, ""));
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or(value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        keys.push((key.trim().to_lowercase(), value.to_string()));
+    }
+    sections
+}
+
+#[cfg(test)]
+mod test {
+    use aes::{
+        cipher::{generic_array::GenericArray, BlockEncrypt, KeyInit},
+        Aes128,
+    };
+
+    use super::{
+        decrypt_mylogin, parse_my_cnf, parse_mylogin, parse_pg_service, parse_pgpass,
+        parse_ssh_config,
+    };
+    use crate::engine::types::config::Mode;
+
+    fn encrypt_mylogin(content: &str) -> Vec<u8> {
+        let key_bytes: Vec<u8> = (1..=20).collect();
+        let mut key = [0u8; 16];
+        for (i, b) in key_bytes.iter().enumerate() {
+            key[i % 16] ^= b;
+        }
+        let cipher = Aes128::new(&key.into());
+        let mut data = vec![0u8; 4];
+        data.extend(&key_bytes);
+        for line in content.split_inclusive('\n') {
+            let mut line = line.as_bytes().to_vec();
+            let padding = 16 - line.len() % 16;
+            line.extend(std::iter::repeat_n(padding as u8, padding));
+            for block in line.chunks_exact_mut(16) {
+                cipher.encrypt_block(GenericArray::from_mut_slice(block));
+            }
+            data.extend((line.len() as u32).to_le_bytes());
+            data.extend(line);
+        }
+        data
+    }
+
+    #[test]
+    fn test_parse_client_files() {
+        let pgpass = parse_pgpass("# comment\ndb.local:*:*:app:se\\:cret\n*:5432:*:*:ignored\n");
+        assert_eq!(pgpass.len(), 2);
+        assert_eq!(pgpass[0].password, "se:cret");
+
+        let services = parse_pg_service(
+            "[billing]\nhost=db.local\ndbname=billing\nuser=app\nsslmode=verify-full\n",
+            &pgpass,
+        );
+        assert_eq!(services.len(), 1);
+        let (name, cfg) = &services[0];
+        assert_eq!(name, "billing");
+        assert_eq!(cfg.credentials["password"], "se:cret");
+        assert_eq!(cfg.credentials["ssl_mode"], "verify-full");
+
+        let groups = parse_my_cnf(
+            "[client]\nuser=root\npassword=\"pw\"\n\n[client_staging]\nhost=staging.db\ndatabase=world\n\n[mysqld]\nport=3307\n",
+        );
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[1].0, "staging");
+        assert_eq!(groups[1].1.credentials["password"], "pw");
+        assert_eq!(groups[1].1.credentials["host"], "staging.db");
+        assert_eq!(groups[1].1.mode, Mode::Host);
+
+        let hosts = parse_ssh_config(
+            "Host *\n  ServerAliveInterval 60\nHost bastion jump\n  HostName 10.0.0.1\n  User ops\n  Port=2222\n",
+        );
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].host, "10.0.0.1");
+        assert_eq!(hosts[1].user.as_deref(), Some("ops"));
+        assert_eq!(hosts[1].port.as_deref(), Some("2222"));
+    }
+
+    #[test]
+    fn test_parse_mylogin() {
+        let content = "[client]\nuser = \"root\"\npassword = \"p#w\"\n\
+            [staging]\nhost = \"staging.db\"\nport = 3307\n";
+        let decrypted = decrypt_mylogin(&encrypt_mylogin(content)).unwrap();
+        assert_eq!(decrypted, content);
+        assert!(decrypt_mylogin(&[0; 10]).is_err());
+
+        let paths = parse_mylogin(&decrypted);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[1].0, "staging");
+        assert_eq!(paths[1].1.name, "staging");
+        assert_eq!(paths[1].1.credentials["user"], "root");
+        assert_eq!(paths[1].1.credentials["password"], "p#w");
+        assert_eq!(paths[1].1.credentials["port"], "3307");
+    }
+}
@@ -1,10 +1,17 @@
 use anyhow::Result;
 use serde_json::{json, Value};
+use tokio::sync::mpsc::{self, Receiver};
+use tokio_util::sync::CancellationToken;
 
 use crate::database::QueryType;
 
-use super::types::result::ResultSet;
-use super::types::{config::ConnectionPool::*, connection::InitiatedConnection};
+use super::types::ddl::{ObjectType, SchemaObject, TableDdl};
+use super::types::inserts::Inserts;
+use super::types::result::{stream_rows, PageSender, ResultColumn, ResultSet};
+use super::types::{
+    config::ConnectionPool::*,
+    connection::{ConnectionInfo, InitiatedConnection},
+};
 use super::{clickhouse, mysql, postgresql, sqlite};
 
 pub async fn get_table_structure(conn: &InitiatedConnection, table: String) -> Result<Value> {
@@ -99,6 +106,68 @@ pub async fn get_views(conn: &InitiatedConnection) -> Result<Vec<Value>> {
     }
 }
 
+pub async fn get_objects(conn: &InitiatedConnection) -> Result<Vec<SchemaObject>> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::ddl::get_objects(conn, pool).await,
+        Postgresql(pool) => postgresql::ddl::get_objects(conn, pool).await,
+        Sqlite(pool) => sqlite::ddl::get_objects(pool).await,
+        ClickHouse(client) => clickhouse::ddl::get_objects(conn, client).await,
+    }
+}
+
+pub async fn get_create_statement(
+    conn: &InitiatedConnection,
+    object_type: ObjectType,
+    name: &str,
+) -> Result<String> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => {
+            mysql::ddl::get_create_statement(conn, pool, object_type, name).await
+        }
+        Postgresql(pool) => {
+            postgresql::ddl::get_create_statement(conn, pool, object_type, name).await
+        }
+        Sqlite(pool) => sqlite::ddl::get_create_statement(pool, object_type, name).await,
+        ClickHouse(client) => {
+            clickhouse::ddl::get_create_statement(conn, client, object_type, name).await
+        }
+    }
+}
+
+pub async fn get_table_ddl(conn: &InitiatedConnection, table: &str) -> Result<TableDdl> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::ddl::get_table_ddl(conn, pool, table).await,
+        Postgresql(pool) => postgresql::ddl::get_table_ddl(conn, pool, table).await,
+        Sqlite(pool) => sqlite::ddl::get_table_ddl(pool, table).await,
+        ClickHouse(client) => clickhouse::ddl::get_table_ddl(conn, client, table).await,
+    }
+}
+
+pub async fn get_connection_info(conn: &InitiatedConnection) -> Result<ConnectionInfo> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => mysql::tables::get_connection_info(pool).await,
+        Postgresql(pool) => postgresql::tables::get_connection_info(pool).await,
+        Sqlite(pool) => sqlite::tables::get_connection_info(pool).await,
+        ClickHouse(client) => clickhouse::tables::get_connection_info(client).await,
+    }
+}
+
+pub async fn ping(conn: &InitiatedConnection) -> Result<()> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => {
+            sqlx::query("SELECT 1").execute(pool).await?;
+        }
+        Postgresql(pool) => {
+            sqlx::query("SELECT 1").execute(pool).await?;
+        }
+        Sqlite(pool) => {
+            sqlx::query("SELECT 1").execute(pool).await?;
+        }
+        ClickHouse(client) => client.ping().await?,
+    }
+    Ok(())
+}
+
 pub async fn execute_query(conn: &InitiatedConnection, q: &str, t: QueryType) -> Result<ResultSet> {
     match &conn.pool {
         Mysql(pool) | MariaDB(pool) => mysql::query::execute_query(pool, q, t).await,
@@ -108,6 +177,45 @@ pub async fn execute_query(conn: &InitiatedConnection, q: &str, t: QueryType) ->
     }
 }
 
+/// Runs a `SELECT` in the background and receives its rows in result sets of
+/// `page_size` rows as the database streams them, so results larger than
+/// memory can be copied. There is always a page, empty with the columns when
+/// there are no rows. Reading stops once the receiver is dropped.
+pub fn stream_query(
+    conn: &InitiatedConnection,
+    query: String,
+    page_size: usize,
+) -> Receiver<Result<ResultSet>> {
+    let (tx, rx) = mpsc::channel(1);
+    let pages = PageSender::new(tx, page_size);
+    match conn.pool.clone() {
+        Mysql(pool) | MariaDB(pool) => tokio::spawn(stream_rows(
+            pool,
+            query,
+            mysql::sql_to_json::row_to_array,
+            pages,
+        )),
+        Postgresql(pool) => tokio::spawn(stream_rows(
+            pool,
+            query,
+            postgresql::sql_to_json::row_to_array,
+            pages,
+        )),
+        Sqlite(pool) => tokio::spawn(stream_rows(
+            pool,
+            query,
+            sqlite::sql_to_json::row_to_array,
+            pages,
+        )),
+        ClickHouse(client) => {
+            tokio::spawn(
+                async move { clickhouse::query::stream_query(&client, &query, pages).await },
+            )
+        }
+    };
+    rx
+}
+
 pub async fn execute_tx(conn: &InitiatedConnection, queries: Vec<&str>) -> Result<()> {
     match &conn.pool {
         Mysql(pool) | MariaDB(pool) => mysql::query::execute_tx(pool, queries).await,
@@ -116,3 +224,55 @@ pub async fn execute_tx(conn: &InitiatedConnection, queries: Vec<&str>) -> Resul
         ClickHouse(client) => clickhouse::query::execute_tx(client, queries).await,
     }
 }
+
+pub async fn execute_script(
+    conn: &InitiatedConnection,
+    statements: &[String],
+    progress: impl Fn(usize),
+    token: &CancellationToken,
+) -> Result<()> {
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => {
+            mysql::query::execute_script(pool, statements, progress, token).await
+        }
+        Postgresql(pool) => {
+            postgresql::query::execute_script(pool, statements, progress, token).await
+        }
+        Sqlite(pool) => sqlite::query::execute_script(pool, statements, progress, token).await,
+        ClickHouse(client) => {
+            clickhouse::query::execute_script(client, statements, progress, token).await
+        }
+    }
+}
+
+/// Rows per statement when loading through `INSERT`.
+const INSERT_BATCH: usize = 100;
+
+/// Loads rows into a table with the dialect's bulk path, `COPY` for Postgres
+/// and `JSONEachRow` for ClickHouse, multi-row inserts otherwise. Returns the
+/// number of rows written, every row is written or none except on ClickHouse.
+pub async fn load(
+    conn: &InitiatedConnection,
+    table: &str,
+    columns: &[ResultColumn],
+    rows: &[Vec<Value>],
+) -> Result<u64> {
+    let statements = || -> Result<Vec<String>> {
+        let inserts = Inserts::new(&conn.config.dialect, table, columns.to_vec(), vec![], false)?;
+        Ok(rows.chunks(INSERT_BATCH).map(|c| inserts.statement(c)).collect())
+    };
+    match &conn.pool {
+        Mysql(pool) | MariaDB(pool) => {
+            let statements = statements()?;
+            mysql::query::execute_tx(pool, statements.iter().map(String::as_str).collect()).await?;
+            Ok(rows.len() as u64)
+        }
+        Postgresql(pool) => postgresql::load::copy_in(pool, table, columns, rows).await,
+        Sqlite(pool) => {
+            let statements = statements()?;
+            sqlite::query::execute_tx(pool, statements.iter().map(String::as_str).collect()).await?;
+            Ok(rows.len() as u64)
+        }
+        ClickHouse(client) => clickhouse::query::insert_rows(client, table, columns, rows).await,
+    }
+}
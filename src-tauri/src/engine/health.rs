@@ -0,0 +1,233 @@
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    engine::{init::init_conn, types::config::Dialect},
+    query::Events,
+    state::{AppState, ServiceAccess},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+const DEGRADED_LATENCY: Duration = Duration::from_millis(1000);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ConnectionStatus {
+    Healthy,
+    Degraded,
+    Lost,
+    Reconnected,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionStatusEvent {
+    pub conn_id: String,
+    pub status: ConnectionStatus,
+    pub latency_ms: Option<u64>,
+    pub attempt: u32,
+    pub error: Option<String>,
+}
+
+/// Turns health check results into statuses. A single failed check only degrades
+/// the connection, the second one in a row loses it and the monitor reconnects.
+#[derive(Debug, Default)]
+struct Checks {
+    failures: u32,
+}
+
+impl Checks {
+    fn record(&mut self, result: &Result<Duration, String>) -> ConnectionStatus {
+        match result {
+            Ok(latency) => {
+                self.failures = 0;
+                match *latency > DEGRADED_LATENCY {
+                    true => ConnectionStatus::Degraded,
+                    false => ConnectionStatus::Healthy,
+                }
+            }
+            Err(_) => {
+                self.failures += 1;
+                match self.failures < 2 {
+                    true => ConnectionStatus::Degraded,
+                    false => ConnectionStatus::Lost,
+                }
+            }
+        }
+    }
+}
+
+/// The delay before the next reconnect attempt, doubling up to `MAX_BACKOFF`.
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1)
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_BACKOFF)
+}
+
+/// The monitor has its own cancel token so reconnecting, which cancels the
+/// connection's token to tear down tunnels, doesn't stop the monitor itself.
+pub fn health_token_id(conn_id: &str) -> String {
+    format!("{}-health", conn_id)
+}
+
+/// Starts a background health check for an initiated connection, replacing any
+/// monitor already running for it. The monitor stops when its token is cancelled
+/// or the connection is no longer in the app state.
+pub async fn spawn_monitor(handle: AppHandle, conn_id: String) {
+    if let Some(conn) = handle.find_connection(&conn_id) {
+        if conn.config.dialect == Dialect::Sqlite {
+            return;
+        }
+    }
+    let token = CancellationToken::new();
+    {
+        let state: State<'_, AppState> = handle.state();
+        let mut binding = state.cancel_tokens.lock().await;
+        if let Some(previous) = binding.insert(health_token_id(&conn_id), token.clone()) {
+            previous.cancel();
+        }
+    }
+    tokio::spawn(monitor(handle, conn_id, token));
+}
+
+async fn monitor(handle: AppHandle, conn_id: String, token: CancellationToken) {
+    let mut checks = Checks::default();
+    loop {
+        tokio::select! {
+            _ = token.cancelled() => break,
+            _ = tokio::time::sleep(CHECK_INTERVAL) => {}
+        }
+        let Some(conn) = handle.find_connection(&conn_id) else {
+            break;
+        };
+        let result = match tokio::time::timeout(PING_TIMEOUT, conn.ping()).await {
+            Ok(Ok(latency)) => Ok(latency),
+            Ok(Err(e)) => Err(e.to_string()),
+            Err(_) => Err("Health check timed out".to_string()),
+        };
+        let status = checks.record(&result);
+        match result {
+            Ok(latency) => emit(&handle, &conn_id, status, Some(latency), 0, None),
+            Err(e) => {
+                warn!("Health check failed for {conn_id}: {e}");
+                emit(&handle, &conn_id, status, None, 0, Some(e));
+                if status != ConnectionStatus::Lost {
+                    continue;
+                }
+                if !reconnect(&handle, &conn_id, &token).await {
+                    break;
+                }
+                checks = Checks::default();
+            }
+        }
+    }
+    info!("Stopped health monitor for {conn_id}");
+}
+
+/// Re-initiates the connection with exponential backoff until it succeeds. Returns
+/// false if the monitor was cancelled or the connection went away meanwhile.
+async fn reconnect(handle: &AppHandle, conn_id: &str, token: &CancellationToken) -> bool {
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let Some(conn) = handle.find_connection(conn_id) else {
+            return false;
+        };
+        // tears down the previous ssh tunnel and secret refresh before rebuilding
+        if let Err(e) = handle.cancel_token(conn_id.to_string()).await {
+            warn!("Failed to cancel tasks for {conn_id}: {e}");
+        }
+        // the config carries the selected schema, so the new pool keeps working in it
+        match init_conn(conn.config.clone(), handle.clone()).await {
+            Ok(new_conn) => {
+                if handle.find_connection(conn_id).is_none() {
+                    let _ = handle.cancel_token(conn_id.to_string()).await;
+                    return false;
+                }
+                let latency = new_conn.ping().await.ok();
+                if let Err(e) = handle.update_connection(new_conn) {
+                    error!("Failed to store reconnected connection {conn_id}: {e}");
+                }
+                info!("Reconnected {conn_id} after {attempt} attempts");
+                emit(
+                    handle,
+                    conn_id,
+                    ConnectionStatus::Reconnected,
+                    latency,
+                    attempt,
+                    None,
+                );
+                return true;
+            }
+            Err(e) => {
+                warn!("Reconnect attempt {attempt} for {conn_id} failed: {e}");
+                emit(
+                    handle,
+                    conn_id,
+                    ConnectionStatus::Lost,
+                    None,
+                    attempt,
+                    Some(e.to_string()),
+                );
+            }
+        }
+        tokio::select! {
+            _ = token.cancelled() => return false,
+            _ = tokio::time::sleep(backoff(attempt)) => {}
+        }
+    }
+}
+
+fn emit(
+    handle: &AppHandle,
+    conn_id: &str,
+    status: ConnectionStatus,
+    latency: Option<Duration>,
+    attempt: u32,
+    error: Option<String>,
+) {
+    let event = ConnectionStatusEvent {
+        conn_id: conn_id.to_string(),
+        status,
+        latency_ms: latency.map(|l| l.as_millis() as u64),
+        attempt,
+        error,
+    };
+    if let Err(e) = handle.emit(Events::ConnectionStatus.as_str(), event) {
+        error!("Failed to emit connection_status event: {e}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{backoff, Checks, ConnectionStatus, MAX_BACKOFF};
+
+    #[test]
+    fn test_status_transitions() {
+        let mut checks = Checks::default();
+        let ok = |ms| Ok(Duration::from_millis(ms));
+        let failed = || Err("timed out".to_string());
+        assert_eq!(checks.record(&ok(20)), ConnectionStatus::Healthy);
+        assert_eq!(checks.record(&ok(1500)), ConnectionStatus::Degraded);
+        // one failure degrades, a second in a row loses the connection
+        assert_eq!(checks.record(&failed()), ConnectionStatus::Degraded);
+        assert_eq!(checks.record(&ok(20)), ConnectionStatus::Healthy);
+        assert_eq!(checks.record(&failed()), ConnectionStatus::Degraded);
+        assert_eq!(checks.record(&failed()), ConnectionStatus::Lost);
+        assert_eq!(checks.record(&failed()), ConnectionStatus::Lost);
+    }
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(7), MAX_BACKOFF);
+        assert_eq!(backoff(100), MAX_BACKOFF);
+    }
+}
@@ -1,43 +1,73 @@
 use anyhow::{anyhow, Result};
-use std::time::Duration;
+use futures::future::BoxFuture;
+use log::warn;
+use std::{sync::Arc, time::Duration};
 
 use sqlx::{
     mysql::{MySqlConnectOptions, MySqlPoolOptions, MySqlSslMode},
-    pool::PoolOptions,
-    postgres::{PgConnectOptions, PgPoolOptions, PgSslMode},
-    Executor,
+    pool::{PoolConnectionMetadata, PoolOptions},
+    postgres::{PgConnectOptions, PgConnection, PgPoolOptions, PgSslMode},
+    Connection, Database, Executor, Pool,
 };
 use tauri::AppHandle;
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     engine::{
         clickhouse::client::ClickHouseClient,
+        postgresql::{
+            hosts::{self, TargetSessionAttrs},
+            search_path,
+        },
         types::{
-            config::{ConnectionConfig, ConnectionPool, Dialect, Mode},
+            config::{ConnectionConfig, ConnectionPool, Credentials, Dialect, Mode},
             connection::InitiatedConnection,
         },
     },
     state::ServiceAccess,
     utils::{
         error::Error,
-        general::{get_available_port, request_port_forward},
+        general::{get_available_port, get_connection_token, request_port_forward},
+        secrets::{has_references, resolve_credentials},
     },
 };
 
+/// How often secret references are re-resolved for connections opened later by a pool,
+/// short-lived database tokens usually expire after 15 minutes.
+const SECRET_REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+const DEFAULT_MAX_CONNECTIONS: u32 = 10;
+const DEFAULT_IDLE_TIMEOUT: u64 = 30 * 60;
+const DEFAULT_MAX_LIFETIME: u64 = 60 * 60;
+
 pub async fn init_conn(
     cfg: ConnectionConfig,
     app_handle: AppHandle,
 ) -> Result<InitiatedConnection, Error> {
+    // Secret references are resolved into a copy used for connecting only, the
+    // resolved values never end up in the stored config.
+    let resolved = ConnectionConfig {
+        credentials: resolve_credentials(&cfg.credentials, cfg.metadata.trust_commands).await?,
+        ..cfg.clone()
+    };
+    let refresh_secrets = has_references(&cfg.credentials);
     match &cfg.dialect {
         Dialect::Mysql | Dialect::MariaDB => {
             let (pool_opts, options) =
-                build_mysql_pool_opts(cfg.clone(), app_handle.clone()).await?;
+                build_mysql_pool_opts(resolved, app_handle.clone()).await?;
             let schema = options.get_database().unwrap_or("").to_string();
             let pool = pool_opts.connect_with(options).await?;
             if pool.execute("SELECT 1").await.is_err() {
                 app_handle.cancel_token(cfg.id.to_string()).await?;
                 return Err(Error::from(anyhow::anyhow!("Could not connect")));
             }
+            if refresh_secrets {
+                let token = get_connection_token(&app_handle, &cfg.id.to_string()).await;
+                spawn_secret_refresh(pool.clone(), cfg.clone(), token, |o, c| {
+                    o.username(c.get("user").map_or("", |v| v))
+                        .password(c.get("password").map_or("", |v| v))
+                });
+            }
             Ok(InitiatedConnection {
                 config: cfg.clone(),
                 pool: ConnectionPool::Mysql(pool),
@@ -45,20 +75,26 @@ pub async fn init_conn(
             })
         }
         Dialect::Postgresql => {
-            let _cfg = cfg.clone();
-            let pool = build_psql_pool(cfg, app_handle).await?;
+            let pool = build_psql_pool(resolved, &cfg.credentials, app_handle.clone()).await?;
             sqlx::query("SELECT 1").execute(&pool).await?;
+            if refresh_secrets {
+                let token = get_connection_token(&app_handle, &cfg.id.to_string()).await;
+                spawn_secret_refresh(pool.clone(), cfg.clone(), token, |o, c| {
+                    o.username(c.get("user").map_or("", |v| v))
+                        .password(c.get("password").map_or("", |v| v))
+                });
+            }
             Ok(InitiatedConnection {
-                config: _cfg,
+                schema: search_path::primary(&cfg.schema),
+                config: cfg,
                 pool: ConnectionPool::Postgresql(pool),
-                schema: "public".to_string(),
             })
         }
         Dialect::Sqlite => {
             if cfg.mode != Mode::File {
                 return Err(anyhow::anyhow!("Only file mode is supported for Sqlite").into());
             }
-            let path = cfg
+            let path = resolved
                 .credentials
                 .get("path")
                 .cloned()
@@ -67,7 +103,13 @@ pub async fn init_conn(
                 .filename(&path)
                 .read_only(false)
                 .create_if_missing(false);
-            let pool = sqlx::SqlitePool::connect_with(options).await?;
+            let options = match cfg.metadata.read_only {
+                true => options.pragma("query_only", "ON"),
+                false => options,
+            };
+            let pool = build_pool_opts::<sqlx::Sqlite>(&resolved.credentials, 30, None)?
+                .connect_with(options)
+                .await?;
             sqlx::query("SELECT 1").execute(&pool).await?;
             Ok(InitiatedConnection {
                 config: cfg.clone(),
@@ -82,9 +124,9 @@ pub async fn init_conn(
                 );
             }
             let empty = String::new();
-            let user = cfg.credentials.get("user").unwrap_or(&empty);
-            let password = cfg.credentials.get("password").unwrap_or(&empty);
-            let database = cfg
+            let user = resolved.credentials.get("user").unwrap_or(&empty);
+            let password = resolved.credentials.get("password").unwrap_or(&empty);
+            let database = resolved
                 .credentials
                 .get("db_name")
                 .cloned()
@@ -93,11 +135,15 @@ pub async fn init_conn(
             let (host, port) = match cfg.mode {
                 Mode::Ssh => {
                     let ssh_keys = ["ssh_host", "ssh_port", "ssh_user", "ssh_key"];
-                    let mut ssh_cfg = cfg.credentials.clone();
+                    let mut ssh_cfg = resolved.credentials.clone();
                     ssh_cfg.retain(|k, _| ssh_keys.contains(&k.as_str()));
                     let available_port = get_available_port();
-                    let remote_host = cfg.credentials.get("host").unwrap_or(&empty).to_string();
-                    let remote_port = cfg
+                    let remote_host = resolved
+                        .credentials
+                        .get("host")
+                        .unwrap_or(&empty)
+                        .to_string();
+                    let remote_port = resolved
                         .credentials
                         .get("port")
                         .cloned()
@@ -115,12 +161,12 @@ pub async fn init_conn(
                     ("127.0.0.1".to_string(), available_port)
                 }
                 _ => {
-                    let host = cfg
+                    let host = resolved
                         .credentials
                         .get("host")
                         .cloned()
                         .unwrap_or("localhost".to_string());
-                    let port = cfg
+                    let port = resolved
                         .credentials
                         .get("port")
                         .cloned()
@@ -130,7 +176,8 @@ pub async fn init_conn(
                 }
             };
 
-            let client = ClickHouseClient::new(&host, port, user, password, &database)?;
+            let client = ClickHouseClient::new(&host, port, user, password, &database)?
+                .read_only(cfg.metadata.read_only);
             if client.ping().await.is_err() {
                 app_handle.cancel_token(cfg.id.to_string()).await?;
                 return Err(Error::from(anyhow::anyhow!("Could not connect to ClickHouse")));
@@ -144,52 +191,159 @@ pub async fn init_conn(
     }
 }
 
+type ConnectOptions<DB> = <<DB as Database>::Connection as Connection>::Options;
+
+/// Re-resolves secret references periodically and applies them to the pool, so
+/// connections the pool opens later authenticate with fresh values.
+fn spawn_secret_refresh<DB: Database>(
+    pool: Pool<DB>,
+    cfg: ConnectionConfig,
+    token: CancellationToken,
+    apply: fn(ConnectOptions<DB>, &Credentials) -> ConnectOptions<DB>,
+) {
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = tokio::time::sleep(SECRET_REFRESH_INTERVAL) => {
+                    match resolve_credentials(&cfg.credentials, cfg.metadata.trust_commands).await {
+                        Ok(resolved) => {
+                            let options = apply((*pool.connect_options()).clone(), &resolved);
+                            pool.set_connect_options(options);
+                        }
+                        Err(e) => warn!("Failed to refresh connection secrets: {}", e),
+                    }
+                }
+            }
+        }
+    });
+}
+
+fn parse_pool_key<T: std::str::FromStr>(
+    credentials: &Credentials,
+    key: &str,
+) -> Result<Option<T>> {
+    match credentials.get(key).map(|v| v.trim()) {
+        None | Some("") => Ok(None),
+        Some(v) => v
+            .parse::<T>()
+            .map(Some)
+            .map_err(|_| anyhow!("{} should be a valid number, got '{}'", key, v)),
+    }
+}
+
+/// Builds pool options from the connection's pool settings, falling back to the
+/// defaults noir always used. A timeout of 0 disables it. The `init_sql` statements
+/// run on every new connection the pool opens, e.g. `SET time_zone = '+00:00'`,
+/// followed by `session_sql` so the user's statements can't undo it.
+fn build_pool_opts<DB: Database>(
+    credentials: &Credentials,
+    default_acquire_timeout: u64,
+    session_sql: Option<&'static str>,
+) -> Result<PoolOptions<DB>>
+where
+    for<'c> &'c mut DB::Connection: Executor<'c, Database = DB>,
+{
+    let max = parse_pool_key(credentials, "pool_max")?.unwrap_or(DEFAULT_MAX_CONNECTIONS);
+    let min = parse_pool_key(credentials, "pool_min")?.unwrap_or(0);
+    if max == 0 {
+        return Err(anyhow!("pool_max must be at least 1"));
+    }
+    if min > max {
+        return Err(anyhow!("pool_min can not be greater than pool_max"));
+    }
+    let timeout = |secs: u64| (secs > 0).then(|| Duration::from_secs(secs));
+    let idle =
+        parse_pool_key(credentials, "pool_idle_timeout_secs")?.unwrap_or(DEFAULT_IDLE_TIMEOUT);
+    let lifetime =
+        parse_pool_key(credentials, "pool_max_lifetime_secs")?.unwrap_or(DEFAULT_MAX_LIFETIME);
+    let acquire = parse_pool_key(credentials, "pool_acquire_timeout_secs")?
+        .filter(|secs| *secs > 0)
+        .unwrap_or(default_acquire_timeout);
+    let pool_opts = PoolOptions::<DB>::new()
+        .max_connections(max)
+        .min_connections(min)
+        .idle_timeout(timeout(idle))
+        .max_lifetime(timeout(lifetime))
+        .acquire_timeout(Duration::from_secs(acquire));
+
+    let statements = session_statements(credentials, session_sql);
+    Ok(match statements.is_empty() {
+        true => pool_opts,
+        false => pool_opts.after_connect(move |conn, _meta| {
+            let statements = statements.clone();
+            Box::pin(async move {
+                for sql in statements {
+                    conn.execute(sqlx::raw_sql(&sql)).await?;
+                }
+                Ok(())
+            })
+        }),
+    })
+}
+
+fn session_statements(credentials: &Credentials, session_sql: Option<&'static str>) -> Vec<String> {
+    credentials
+        .get("init_sql")
+        .map(|s| s.trim().to_string())
+        .into_iter()
+        .chain(session_sql.map(String::from))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn parse_port(credentials: &Credentials, default: u16) -> Result<u16> {
+    Ok(parse_pool_key(credentials, "port")?.unwrap_or(default))
+}
+
 async fn build_psql_opts(
     cfg: &ConnectionConfig,
     host: &str,
     port: u16,
 ) -> Result<PgConnectOptions> {
-    let empty = String::new();
-    let mut options = PgConnectOptions::new()
-        .host(host)
-        .port(port)
-        .username(cfg.credentials.get("user").unwrap_or(&empty))
-        .password(cfg.credentials.get("password").unwrap_or(&empty))
-        .database(cfg.credentials.get("db_name").unwrap_or(&empty));
-
-    let ssl_mode = cfg.credentials.get("ssl_mode");
-    options = match ssl_mode.map(|s| s.as_str()) {
+    let mut options = PgConnectOptions::new().host(host).port(port);
+    // left unset, sqlx falls back to the OS user which peer auth over sockets relies on
+    let non_empty = |key: &str| cfg.credentials.get(key).filter(|v| !v.is_empty());
+    if let Some(user) = non_empty("user") {
+        options = options.username(user);
+    }
+    if let Some(password) = non_empty("password") {
+        options = options.password(password);
+    }
+    if let Some(db_name) = non_empty("db_name") {
+        options = options.database(db_name);
+    }
+
+    let schemas = search_path::parse(&cfg.schema);
+    if !schemas.is_empty() {
+        options = options.options([("search_path", search_path::to_option(&schemas))]);
+    }
+
+    options = match cfg.credentials.get("ssl_mode").map(|s| s.as_str()) {
+        None | Some("") | Some("disable") => options.ssl_mode(PgSslMode::Disable),
+        Some("allow") => options.ssl_mode(PgSslMode::Allow),
         Some("prefer") => options.ssl_mode(PgSslMode::Prefer),
         Some("require") => options.ssl_mode(PgSslMode::Require),
-        _ => options.ssl_mode(PgSslMode::Disable),
+        Some("verify-ca") => options.ssl_mode(PgSslMode::VerifyCa),
+        Some("verify-full") => options.ssl_mode(PgSslMode::VerifyFull),
+        Some(mode) => return Err(anyhow!("Invalid ssl_mode: {}", mode)),
     };
 
-    let ca_cert = cfg.credentials.get("ca_cert").cloned().unwrap_or_default();
-    let client_cert = cfg
-        .credentials
-        .get("client_cert")
-        .cloned()
-        .unwrap_or_default();
-    let client_key = cfg
-        .credentials
-        .get("client_key")
-        .cloned()
-        .unwrap_or_default();
-
-    if (!client_cert.is_empty() && client_key.is_empty())
-        || (client_cert.is_empty() && !client_key.is_empty())
-    {
-        return Err(anyhow::anyhow!(
-            "client_cert and client_key must be set together"
-        ));
-    }
-
-    if !ca_cert.is_empty() {
-        options = options.ssl_root_cert(ca_cert);
-    }
-    if !client_cert.is_empty() {
-        options = options.ssl_client_cert(client_cert);
-        options = options.ssl_client_key(client_key);
+    let (ca_cert, client) = tls_certificates(&cfg.credentials)?;
+    options = match ca_cert {
+        Some(Certificate::Pem(pem)) => options.ssl_root_cert_from_pem(pem),
+        Some(Certificate::Path(path)) => options.ssl_root_cert(path),
+        None => options,
+    };
+    if let Some((cert, key)) = client {
+        options = match cert {
+            Certificate::Pem(pem) => options.ssl_client_cert_from_pem(pem),
+            Certificate::Path(path) => options.ssl_client_cert(path),
+        };
+        options = match key {
+            Certificate::Pem(pem) => options.ssl_client_key_from_pem(pem),
+            Certificate::Path(path) => options.ssl_client_key(path),
+        };
     }
 
     Ok(options)
@@ -197,17 +351,20 @@ async fn build_psql_opts(
 
 async fn build_psql_pool(
     cfg: ConnectionConfig,
+    stored: &Credentials,
     app_handle: AppHandle,
 ) -> Result<sqlx::PgPool> {
     if cfg.mode == Mode::File {
         return Err(anyhow::anyhow!("File mode is not supported for Postgresql"));
     }
 
-    let pool_opts = PgPoolOptions::new()
-        .max_connections(10)
-        .idle_timeout(Duration::from_secs(30 * 60))
-        .max_lifetime(Duration::from_secs(60 * 60))
-        .acquire_timeout(Duration::from_secs(15));
+    // set after connecting rather than as a startup option, so checking the host
+    // against target_session_attrs still sees the server's own read only state
+    let session_sql = cfg
+        .metadata
+        .read_only
+        .then_some("SET default_transaction_read_only = on");
+    let pool_opts: PgPoolOptions = build_pool_opts(&cfg.credentials, 15, session_sql)?;
 
     match cfg.mode {
         Mode::Ssh => {
@@ -217,12 +374,11 @@ async fn build_psql_pool(
             let empty_str = String::default();
             let available_port = get_available_port();
             let host = cfg.credentials.get("host").unwrap_or(&empty_str);
-            let port = cfg
-                .credentials
-                .get("port")
-                .cloned()
-                .map(|p| p.parse::<u16>().expect("Port should be a valid number"))
-                .unwrap_or(5432);
+            if host.contains(',') {
+                return Err(anyhow!("Multiple hosts are not supported over ssh"));
+            }
+            check_tunnel_tls(&cfg.credentials)?;
+            let port = parse_port(&cfg.credentials, 5432)?;
             request_port_forward(
                 app_handle.clone(),
                 cfg.id.to_string(),
@@ -236,24 +392,226 @@ async fn build_psql_pool(
             Ok(pool_opts.connect_with(options).await?)
         }
         Mode::File => Err(anyhow!("Should never reach here")),
-        _ => {
+        Mode::Socket => {
+            let socket = cfg
+                .credentials
+                .get("socket")
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| anyhow!("Socket directory is required"))?;
+            let port = parse_port(&cfg.credentials, 5432)?;
+            // sqlx connects to `<socket>/.s.PGSQL.<port>`
+            let options = build_psql_opts(&cfg, "localhost", port)
+                .await?
+                .socket(socket);
+            Ok(pool_opts.connect_with(options).await?)
+        }
+        Mode::Host => {
             let empty_str = String::default();
             let host = cfg
                 .credentials
                 .get("host")
                 .unwrap_or(&empty_str);
-            let port = cfg
+            let port = parse_port(&cfg.credentials, 5432)?;
+            let target = cfg
                 .credentials
-                .get("port")
-                .cloned()
-                .map(|p| p.parse::<u16>().expect("Port should be a valid number"))
-                .unwrap_or(5432);
-            let options = build_psql_opts(&cfg, host, port).await?;
+                .get("target_session_attrs")
+                .map_or(Ok(TargetSessionAttrs::Any), |s| s.parse())?;
+            let hosts = hosts::parse(host, port)?;
+            let options = select_psql_host(&cfg, &hosts, target).await?;
+            let pool_opts = match target.is_strict() {
+                true => pool_opts.after_connect(reselect_psql_host(
+                    Arc::new(ConnectionConfig {
+                        credentials: stored.clone(),
+                        ..cfg.clone()
+                    }),
+                    hosts,
+                    target,
+                    session_statements(&cfg.credentials, session_sql),
+                )),
+                false => pool_opts,
+            };
             Ok(pool_opts.connect_with(options).await?)
         }
     }
 }
 
+type PgAfterConnect = Box<
+    dyn Fn(&mut PgConnection, PoolConnectionMetadata) -> BoxFuture<'_, Result<(), sqlx::Error>>
+        + Send
+        + Sync,
+>;
+
+/// Checks every connection the pool opens against `target`. A pool keeps connecting
+/// to the host picked first, so after a failover the host list is walked again and
+/// the connection replaced by one to the host that satisfies `target` now. `cfg`
+/// holds the stored credentials, secret references are resolved again so the
+/// replacement doesn't sign in with expired tokens.
+fn reselect_psql_host(
+    cfg: Arc<ConnectionConfig>,
+    hosts: Vec<(String, u16)>,
+    target: TargetSessionAttrs,
+    statements: Vec<String>,
+) -> PgAfterConnect {
+    Box::new(move |conn, _meta| {
+        let (cfg, hosts, statements) = (cfg.clone(), hosts.clone(), statements.clone());
+        Box::pin(async move {
+            if !target.accepts(conn).await.map_err(config_error)? {
+                warn!("Host is no longer {target:?}, selecting another one");
+                let resolved = ConnectionConfig {
+                    credentials: resolve_credentials(&cfg.credentials, cfg.metadata.trust_commands)
+                        .await
+                        .map_err(config_error)?,
+                    ..(*cfg).clone()
+                };
+                let options = select_psql_host(&resolved, &hosts, target)
+                    .await
+                    .map_err(config_error)?;
+                let replacement = PgConnection::connect_with(&options).await?;
+                let previous = std::mem::replace(conn, replacement);
+                let _ = previous.close().await;
+            }
+            for sql in statements {
+                conn.execute(sqlx::raw_sql(&sql)).await?;
+            }
+            Ok(())
+        })
+    })
+}
+
+fn config_error(e: anyhow::Error) -> sqlx::Error {
+    sqlx::Error::Configuration(e.into())
+}
+
+/// Walks the host list in order like libpq does and returns the options of the
+/// first host satisfying `target`.
+async fn select_psql_host(
+    cfg: &ConnectionConfig,
+    hosts: &[(String, u16)],
+    target: TargetSessionAttrs,
+) -> Result<PgConnectOptions> {
+    if let ([(host, port)], TargetSessionAttrs::Any) = (hosts, target) {
+        return build_psql_opts(cfg, host, *port).await;
+    }
+    let mut fallback = None;
+    let mut errors = vec![];
+    for (host, port) in hosts {
+        let options = build_psql_opts(cfg, host, *port).await?;
+        let mut conn = match PgConnection::connect_with(&options).await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Could not connect to {host}:{port}: {e}");
+                errors.push(format!("{host}:{port}: {e}"));
+                continue;
+            }
+        };
+        let accepted = target.accepts(&mut conn).await;
+        let _ = conn.close().await;
+        match accepted {
+            Ok(true) => return Ok(options),
+            Ok(false) => {
+                errors.push(format!("{host}:{port}: server is not {target:?}"));
+                fallback.get_or_insert(options);
+            }
+            Err(e) => errors.push(format!("{host}:{port}: {e}")),
+        }
+    }
+    match (target, fallback) {
+        (TargetSessionAttrs::PreferStandby, Some(options)) => Ok(options),
+        _ => Err(anyhow!(
+            "No suitable host found for target_session_attrs {:?}: {}",
+            target,
+            errors.join("; ")
+        )),
+    }
+}
+
+/// A certificate credential holds either a file path or inline PEM content. Inline
+/// PEM is stored with the rest of the credentials, encrypted with the app key.
+enum Certificate {
+    Path(String),
+    Pem(Vec<u8>),
+}
+
+impl Certificate {
+    fn from_credential(value: &str) -> Self {
+        match value.trim_start().starts_with("-----BEGIN") {
+            true => Certificate::Pem(value.trim().as_bytes().to_vec()),
+            false => Certificate::Path(value.to_string()),
+        }
+    }
+}
+
+type ClientCertificate = (Certificate, Certificate);
+
+/// Reads `ca_cert` and the `client_cert`/`client_key` pair from the credentials.
+fn tls_certificates(
+    credentials: &Credentials,
+) -> Result<(Option<Certificate>, Option<ClientCertificate>)> {
+    let get = |key: &str| {
+        credentials
+            .get(key)
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| Certificate::from_credential(v))
+    };
+    let client = match (get("client_cert"), get("client_key")) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        (None, None) => None,
+        _ => {
+            return Err(anyhow::anyhow!(
+                "client_cert and client_key must be set together"
+            ))
+        }
+    };
+    Ok((get("ca_cert"), client))
+}
+
+/// Hostname verification can't pass over an ssh tunnel as the server is reached
+/// through 127.0.0.1.
+fn check_tunnel_tls(credentials: &Credentials) -> Result<()> {
+    match credentials.get("ssl_mode").map(|s| s.as_str()) {
+        Some("verify-full") => Err(anyhow!(
+            "verify-full can not be used over ssh, the tunnel endpoint does not match the certificate, use verify-ca"
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn apply_mysql_tls(
+    options: MySqlConnectOptions,
+    credentials: &Credentials,
+    tunneled: bool,
+) -> Result<MySqlConnectOptions> {
+    if tunneled {
+        check_tunnel_tls(credentials)?;
+    }
+    let mut options = match credentials.get("ssl_mode").map(|s| s.as_str()) {
+        None | Some("") => options,
+        Some("disable") => options.ssl_mode(MySqlSslMode::Disabled),
+        Some("prefer") => options.ssl_mode(MySqlSslMode::Preferred),
+        Some("require") => options.ssl_mode(MySqlSslMode::Required),
+        Some("verify-ca") => options.ssl_mode(MySqlSslMode::VerifyCa),
+        Some("verify-full") => options.ssl_mode(MySqlSslMode::VerifyIdentity),
+        Some(mode) => return Err(anyhow!("Invalid ssl_mode: {}", mode)),
+    };
+    let (ca_cert, client) = tls_certificates(credentials)?;
+    options = match ca_cert {
+        Some(Certificate::Pem(pem)) => options.ssl_ca_from_pem(pem),
+        Some(Certificate::Path(path)) => options.ssl_ca(path),
+        None => options,
+    };
+    if let Some((cert, key)) = client {
+        options = match cert {
+            Certificate::Pem(pem) => options.ssl_client_cert_from_pem(pem),
+            Certificate::Path(path) => options.ssl_client_cert(path),
+        };
+        options = match key {
+            Certificate::Pem(pem) => options.ssl_client_key_from_pem(pem),
+            Certificate::Path(path) => options.ssl_client_key(path),
+        };
+    }
+    Ok(options)
+}
+
 async fn build_mysql_pool_opts(
     cfg: ConnectionConfig,
     app_handle: AppHandle,
@@ -263,32 +621,12 @@ async fn build_mysql_pool_opts(
     }
     let mut credentials = cfg.credentials.clone();
     let ssl_keys = vec!["ssl_mode", "ca_cert", "client_key", "client_cert"];
-    let mut ssl_cfg = credentials.clone();
-    ssl_cfg.retain(|k, _| ssl_keys.contains(&k.as_str()));
     let ssh_keys = ["ssh_host", "ssh_port", "ssh_user", "ssh_key"];
     let mut ssh_cfg = credentials.clone();
     ssh_cfg.retain(|k, _| ssh_keys.contains(&k.as_str()));
     for key in ssl_keys {
         credentials.remove(key);
     }
-    let ca_cert = ssl_cfg.get("ca_cert").cloned().unwrap_or("".to_string());
-    let client_cert = cfg
-        .credentials
-        .get("client_cert")
-        .cloned()
-        .unwrap_or("".to_string());
-    let client_key = cfg
-        .credentials
-        .get("client_key")
-        .cloned()
-        .unwrap_or("".to_string());
-    if (!client_cert.is_empty() && client_key.is_empty())
-        || (client_cert.is_empty() && !client_key.is_empty())
-    {
-        return Err(anyhow::anyhow!(
-            "client_cert and client_key must be set together"
-        ));
-    }
     let options = match cfg.mode {
         Mode::Host => {
             let port = credentials
@@ -296,28 +634,13 @@ async fn build_mysql_pool_opts(
                 .cloned()
                 .map(|p| p.parse::<u16>().expect("Port should be a valid number"))
                 .unwrap_or(3306);
-            let mut options = MySqlConnectOptions::new()
+            let options = MySqlConnectOptions::new()
                 .host(credentials.get("host").unwrap_or(&"".to_string()))
                 .username(credentials.get("user").unwrap_or(&"".to_string()))
                 .password(credentials.get("password").unwrap_or(&"".to_string()))
                 .database(credentials.get("db_name").unwrap_or(&"".to_string()))
                 .port(port);
-            let ssl_mode = cfg.credentials.get("ssl_mode");
-            if let Some(ssl_mode) = ssl_mode {
-                options = match ssl_mode.as_str() {
-                    "prefer" => options.ssl_mode(MySqlSslMode::Preferred),
-                    "require" => options.ssl_mode(MySqlSslMode::Required),
-                    _ => options.ssl_mode(MySqlSslMode::Disabled),
-                };
-            }
-            if !ca_cert.is_empty() {
-                options = options.ssl_ca(ca_cert);
-            }
-            if !client_cert.is_empty() {
-                options = options.ssl_client_cert(client_cert);
-                options = options.ssl_client_key(client_key);
-            }
-            options
+            apply_mysql_tls(options, &cfg.credentials, false)?
         }
         Mode::Socket => MySqlConnectOptions::new()
             .socket(credentials.get("socket").unwrap_or(&"".to_string()))
@@ -333,27 +656,13 @@ async fn build_mysql_pool_opts(
                 .cloned()
                 .map(|p| p.parse::<u16>().expect("Port should be a valid number"))
                 .unwrap_or(3306);
-            let mut options = MySqlConnectOptions::new()
+            let options = MySqlConnectOptions::new()
                 .host("127.0.0.1")
                 .username(credentials.get("user").unwrap_or(&"".to_string()))
                 .password(credentials.get("password").unwrap_or(&"".to_string()))
                 .database(credentials.get("db_name").unwrap_or(&"".to_string()))
                 .port(available_port);
-            let ssl_mode = cfg.credentials.get("ssl_mode");
-            if let Some(ssl_mode) = ssl_mode {
-                options = match ssl_mode.as_str() {
-                    "prefer" => options.ssl_mode(MySqlSslMode::Preferred),
-                    "require" => options.ssl_mode(MySqlSslMode::Required),
-                    _ => options.ssl_mode(MySqlSslMode::Disabled),
-                };
-            }
-            if !ca_cert.is_empty() {
-                options = options.ssl_ca(ca_cert);
-            }
-            if !client_cert.is_empty() {
-                options = options.ssl_client_cert(client_cert);
-                options = options.ssl_client_key(client_key);
-            }
+            let options = apply_mysql_tls(options, &cfg.credentials, true)?;
             request_port_forward(
                 app_handle.clone(),
                 cfg.id.to_string(),
@@ -367,10 +676,109 @@ async fn build_mysql_pool_opts(
         }
         _ => MySqlConnectOptions::new(),
     };
-    let pool_opts = MySqlPoolOptions::new()
-        .max_connections(10)
-        .idle_timeout(Duration::from_secs(30 * 60))
-        .max_lifetime(Duration::from_secs(60 * 60))
-        .acquire_timeout(Duration::from_secs(10));
+    let session_sql = cfg
+        .metadata
+        .read_only
+        .then_some("SET SESSION TRANSACTION READ ONLY");
+    let pool_opts: MySqlPoolOptions = build_pool_opts(&cfg.credentials, 10, session_sql)?;
     Ok((pool_opts, options))
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use sqlx::{mysql::MySqlConnectOptions, Row, Sqlite};
+
+    use super::{
+        apply_mysql_tls, build_pool_opts, parse_pool_key, DEFAULT_IDLE_TIMEOUT,
+        DEFAULT_MAX_CONNECTIONS,
+    };
+    use crate::engine::types::config::Credentials;
+
+    fn credentials(pairs: &[(&str, &str)]) -> Credentials {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_pool_key() -> Result<()> {
+        let creds = credentials(&[
+            ("pool_max", " 20 "),
+            ("pool_min", ""),
+            ("pool_idle_timeout_secs", "x"),
+        ]);
+        assert_eq!(parse_pool_key::<u32>(&creds, "pool_max")?, Some(20));
+        assert_eq!(parse_pool_key::<u32>(&creds, "pool_min")?, None);
+        assert_eq!(
+            parse_pool_key::<u32>(&creds, "pool_max_lifetime_secs")?,
+            None
+        );
+        assert!(parse_pool_key::<u64>(&creds, "pool_idle_timeout_secs").is_err());
+        assert!(parse_pool_key::<u32>(&credentials(&[("pool_max", "-1")]), "pool_max").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_pool_bounds() -> Result<()> {
+        let opts = build_pool_opts::<Sqlite>(&Credentials::new(), 30, None)?;
+        assert_eq!(opts.get_max_connections(), DEFAULT_MAX_CONNECTIONS);
+        assert_eq!(opts.get_min_connections(), 0);
+        assert_eq!(
+            opts.get_idle_timeout(),
+            Some(Duration::from_secs(DEFAULT_IDLE_TIMEOUT))
+        );
+        assert_eq!(opts.get_acquire_timeout(), Duration::from_secs(30));
+
+        let creds = credentials(&[
+            ("pool_min", "2"),
+            ("pool_max", "4"),
+            ("pool_max_lifetime_secs", "0"),
+            ("pool_acquire_timeout_secs", "0"),
+        ]);
+        let opts = build_pool_opts::<Sqlite>(&creds, 30, None)?;
+        assert_eq!(
+            (opts.get_min_connections(), opts.get_max_connections()),
+            (2, 4)
+        );
+        // a timeout of 0 disables it, the acquire timeout falls back to the default
+        assert_eq!(opts.get_max_lifetime(), None);
+        assert_eq!(opts.get_acquire_timeout(), Duration::from_secs(30));
+
+        assert!(build_pool_opts::<Sqlite>(&credentials(&[("pool_max", "0")]), 30, None).is_err());
+        let creds = credentials(&[("pool_min", "5"), ("pool_max", "4")]);
+        assert!(build_pool_opts::<Sqlite>(&creds, 30, None).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_init_sql() -> Result<()> {
+        let creds = credentials(&[("init_sql", "PRAGMA user_version = 7;")]);
+        let pool = build_pool_opts::<Sqlite>(&creds, 30, Some("PRAGMA query_only = ON"))?
+            .connect("sqlite::memory:")
+            .await?;
+        let row = sqlx::query("PRAGMA user_version").fetch_one(&pool).await?;
+        assert_eq!(row.try_get::<i64, _>(0)?, 7);
+        // the session statement runs last, so the connection stays read only
+        assert!(sqlx::query("CREATE TABLE t (a INT)")
+            .execute(&pool)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ssl_mode() {
+        let tls = |mode: &str, tunneled: bool| {
+            let creds = credentials(&[("ssl_mode", mode)]);
+            apply_mysql_tls(MySqlConnectOptions::new(), &creds, tunneled)
+        };
+        assert!(tls("disable", false).is_ok());
+        assert!(tls("verify_ca", false).is_err());
+        // hostname verification can't pass through the tunnel
+        assert!(tls("verify-full", true).is_err());
+    }
+}
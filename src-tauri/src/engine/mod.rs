@@ -1,7 +1,11 @@
 pub mod clickhouse;
+pub mod discovery;
 pub mod exec;
+pub mod health;
 pub mod init;
 pub mod mysql;
 pub mod postgresql;
 pub mod sqlite;
 pub mod types;
+pub mod wkb;
+pub mod wkt;
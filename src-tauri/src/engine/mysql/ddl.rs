@@ -0,0 +1,220 @@
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use sqlx::MySqlPool;
+
+use crate::engine::types::{
+    connection::InitiatedConnection,
+    ddl::{text, ObjectType, SchemaObject, TableDdl},
+};
+
+use super::sql_to_json::row_to_json;
+
+async fn rows(pool: &MySqlPool, query: &str) -> Result<Vec<Value>> {
+    Ok(sqlx::query(query).map(row_to_json).fetch_all(pool).await?)
+}
+
+pub async fn get_objects(
+    conn: &InitiatedConnection,
+    pool: &MySqlPool,
+) -> Result<Vec<SchemaObject>> {
+    let schema = conn.get_schema();
+    let tables = |table_type: &str| {
+        format!(
+            "SELECT TABLE_NAME AS name FROM information_schema.tables
+            WHERE TABLE_SCHEMA = '{}' AND TABLE_TYPE = '{}' ORDER BY TABLE_NAME;",
+            schema, table_type
+        )
+    };
+    let routines = |routine_type: &str| {
+        format!(
+            "SELECT ROUTINE_NAME AS name FROM information_schema.routines
+            WHERE ROUTINE_SCHEMA = '{}' AND ROUTINE_TYPE = '{}' ORDER BY ROUTINE_NAME;",
+            schema, routine_type
+        )
+    };
+    let queries = [
+        (ObjectType::Table, tables("BASE TABLE")),
+        (
+            ObjectType::View,
+            format!(
+                "SELECT TABLE_NAME AS name, VIEW_DEFINITION AS definition
+                FROM information_schema.views WHERE TABLE_SCHEMA = '{}' ORDER BY TABLE_NAME;",
+                schema
+            ),
+        ),
+        (ObjectType::Function, routines("FUNCTION")),
+        (ObjectType::Procedure, routines("PROCEDURE")),
+        (
+            ObjectType::Trigger,
+            format!(
+                "SELECT TRIGGER_NAME AS name, EVENT_OBJECT_TABLE AS table_name
+                FROM information_schema.triggers WHERE TRIGGER_SCHEMA = '{}'
+                ORDER BY EVENT_OBJECT_TABLE, ACTION_ORDER;",
+                schema
+            ),
+        ),
+    ];
+    let mut objects = vec![];
+    for (object_type, query) in queries {
+        let mut views = vec![];
+        for row in rows(pool, &query).await? {
+            if let Some(name) = text(&row, "name") {
+                if object_type == ObjectType::View {
+                    views.push((name, text(&row, "definition").unwrap_or_default()));
+                    continue;
+                }
+                let table = text(&row, "table_name");
+                objects.push(SchemaObject::new(object_type, &name, table.as_deref()));
+            }
+        }
+        for name in by_dependency(&schema, views) {
+            objects.push(SchemaObject::new(object_type, &name, None));
+        }
+    }
+    Ok(objects)
+}
+
+/// Orders views so that each comes after the views it selects from, otherwise
+/// by name. MySQL stores view definitions with schema-qualified, backquoted
+/// names; views in a cycle keep their order.
+fn by_dependency(schema: &str, mut views: Vec<(String, String)>) -> Vec<String> {
+    let mut ordered = vec![];
+    while !views.is_empty() {
+        let ready = views.iter().position(|(_, definition)| {
+            !views
+                .iter()
+                .any(|(other, _)| definition.contains(&format!("`{}`.`{}`", schema, other)))
+        });
+        let (name, _) = views.remove(ready.unwrap_or(0));
+        ordered.push(name);
+    }
+    ordered
+}
+
+pub async fn get_create_statement(
+    conn: &InitiatedConnection,
+    pool: &MySqlPool,
+    object_type: ObjectType,
+    name: &str,
+) -> Result<String> {
+    let (keyword, column) = match object_type {
+        ObjectType::Table => ("TABLE", "Create Table"),
+        ObjectType::View => ("VIEW", "Create View"),
+        ObjectType::Trigger => ("TRIGGER", "SQL Original Statement"),
+        ObjectType::Function => ("FUNCTION", "Create Function"),
+        ObjectType::Procedure => ("PROCEDURE", "Create Procedure"),
+        ObjectType::Index => return get_index_statement(conn, pool, name).await,
+        ObjectType::MaterializedView => bail!("MySQL has no materialized views"),
+        ObjectType::Extension | ObjectType::Type => bail!("MySQL has no extensions or types"),
+    };
+    show_create(conn, pool, keyword, column, name).await
+}
+
+async fn show_create(
+    conn: &InitiatedConnection,
+    pool: &MySqlPool,
+    keyword: &str,
+    column: &str,
+    name: &str,
+) -> Result<String> {
+    let dialect = &conn.config.dialect;
+    let query = format!(
+        "SHOW CREATE {} {}.{};",
+        keyword,
+        dialect.quote_ident(&conn.get_schema()),
+        dialect.quote_ident(name)
+    );
+    let row = rows(pool, &query)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("{} was not found", name))?;
+    // routines show a null definition to users without the privileges to read it
+    text(&row, column).ok_or_else(|| anyhow!("Could not read the definition of {}", name))
+}
+
+/// MySQL has no statement showing an index, its definition is taken from the
+/// `SHOW CREATE TABLE` of every table with an index of that name.
+async fn get_index_statement(
+    conn: &InitiatedConnection,
+    pool: &MySqlPool,
+    name: &str,
+) -> Result<String> {
+    let dialect = &conn.config.dialect;
+    let query = format!(
+        "SELECT DISTINCT TABLE_NAME AS name FROM information_schema.statistics
+        WHERE TABLE_SCHEMA = '{}' AND INDEX_NAME = '{}' ORDER BY TABLE_NAME;",
+        conn.get_schema(),
+        name.replace('\'', "''")
+    );
+    let key = match name {
+        "PRIMARY" => "PRIMARY KEY ".to_string(),
+        _ => format!("KEY {} ", dialect.quote_ident(name)),
+    };
+    let mut statements = vec![];
+    for row in rows(pool, &query).await? {
+        let Some(table) = text(&row, "name") else {
+            continue;
+        };
+        let create = show_create(conn, pool, "TABLE", "Create Table", &table).await?;
+        // `KEY`, `UNIQUE KEY`, `FULLTEXT KEY` or `SPATIAL KEY` lines
+        let line = create
+            .lines()
+            .map(|line| line.trim().trim_end_matches(','))
+            .find(|line| line.starts_with(&key) || line.contains(&format!(" {}", key)))
+            .ok_or_else(|| anyhow!("Could not read the definition of {}", name))?;
+        statements.push(format!(
+            "ALTER TABLE {}.{} ADD {}",
+            dialect.quote_ident(&conn.get_schema()),
+            dialect.quote_ident(&table),
+            line
+        ));
+    }
+    if statements.is_empty() {
+        bail!("{} was not found", name);
+    }
+    Ok(statements.join(";\n\n"))
+}
+
+/// `SHOW CREATE TABLE` has the table's indices and foreign keys.
+pub async fn get_table_ddl(
+    conn: &InitiatedConnection,
+    pool: &MySqlPool,
+    table: &str,
+) -> Result<TableDdl> {
+    let create = get_create_statement(conn, pool, ObjectType::Table, table).await?;
+    let query = format!(
+        "SELECT COLUMN_NAME AS name FROM information_schema.columns
+        WHERE TABLE_SCHEMA = '{}' AND TABLE_NAME = '{}' AND GENERATION_EXPRESSION <> ''
+        ORDER BY ORDINAL_POSITION;",
+        conn.get_schema(),
+        table.replace('\'', "''")
+    );
+    let generated = rows(pool, &query)
+        .await?
+        .iter()
+        .filter_map(|row| text(row, "name"))
+        .collect();
+    Ok(TableDdl {
+        create,
+        generated,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_by_dependency() {
+        let view = |name: &str, definition: &str| (name.to_string(), definition.to_string());
+        let views = vec![
+            view("a", "select `x`.`c`.`id` from `x`.`c`"),
+            view("b", "select 1"),
+            view("c", "select `x`.`b`.`1` from `x`.`b`"),
+            view("d", "select * from `y`.`a`"),
+        ];
+        assert_eq!(by_dependency("x", views), ["b", "c", "a", "d"]);
+    }
+}
@@ -1,9 +1,10 @@
 use crate::database::QueryType;
-use crate::engine::types::result::ResultSet;
+use crate::engine::types::result::{describe_columns, ResultSet};
 use anyhow::{anyhow, Result};
-use sqlx::MySqlPool;
+use sqlx::{Connection, MySqlPool};
+use tokio_util::sync::CancellationToken;
 
-use super::sql_to_json::row_to_json;
+use super::sql_to_json::row_to_array;
 
 pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Result<ResultSet> {
     let start_time = std::time::SystemTime::now()
@@ -12,14 +13,19 @@ pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Resul
         .as_millis() as u64;
     match t {
         QueryType::Select | QueryType::Show => {
-            let rows = sqlx::query(query).map(row_to_json).fetch_all(pool).await?;
+            let rows = sqlx::query(query).fetch_all(pool).await?;
+            let end_time = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("Time went backwards")
+                .as_millis() as u64;
+            let result_columns = describe_columns(pool, query, rows.first()).await;
+            let rows = rows.iter().map(row_to_array).collect();
             Ok(ResultSet {
                 start_time,
-                end_time: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("Time went backwards")
-                    .as_millis() as u64,
+                end_time,
                 affected_rows: 0,
+                written_bytes: None,
+                result_columns,
                 rows,
                 table: None,
             })
@@ -38,6 +44,8 @@ pub async fn execute_query(pool: &MySqlPool, query: &str, t: QueryType) -> Resul
                 start_time,
                 end_time,
                 affected_rows,
+                written_bytes: None,
+                result_columns: vec![],
                 rows: vec![],
                 table: None,
             })
@@ -61,3 +69,38 @@ pub async fn execute_tx(pool: &MySqlPool, queries: Vec<&str>) -> Result<()> {
     transaction.commit().await?;
     Ok(())
 }
+
+/// Runs the statements of a script in order on one connection, calling
+/// `progress` with the number run so far. DDL commits implicitly in MySQL, so
+/// there is no transaction to roll back. Scripts change session settings such
+/// as `FOREIGN_KEY_CHECKS`, the connection is closed afterwards rather than
+/// returned to the pool with them, even when a statement fails or the script
+/// is cancelled.
+pub async fn execute_script(
+    pool: &MySqlPool,
+    statements: &[String],
+    progress: impl Fn(usize),
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut conn = pool.acquire().await?.detach();
+    let mut result = Ok(());
+    for (i, statement) in statements.iter().enumerate() {
+        tokio::select! {
+            _ = token.cancelled() => {
+                result = Err(anyhow!("The script was cancelled"));
+                break;
+            }
+            res = sqlx::raw_sql(statement).execute(&mut conn) => {
+                if let Err(e) = res {
+                    result = Err(anyhow!("Statement {} failed: {}", i + 1, e));
+                    break;
+                }
+            }
+        }
+        progress(i + 1);
+    }
+    if let Err(e) = conn.close().await {
+        log::warn!("Failed to close the script connection: {}", e);
+    }
+    result
+}
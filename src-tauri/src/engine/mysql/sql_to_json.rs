@@ -1,9 +1,12 @@
-use chrono::{DateTime, Utc};
+use chrono::NaiveDateTime;
 use serde_json::{self, json, Value};
 use sqlx::mysql::MySqlRow;
 use sqlx::Decode;
 use sqlx::{Column, Row, TypeInfo, ValueRef};
 
+use crate::engine::types::value;
+use crate::engine::wkb;
+
 pub fn row_to_json(row: MySqlRow) -> Value {
     let mut object = json!({});
     for column in row.columns().iter() {
@@ -14,6 +17,13 @@ pub fn row_to_json(row: MySqlRow) -> Value {
     object
 }
 
+pub fn row_to_array(row: &MySqlRow) -> Vec<Value> {
+    row.columns()
+        .iter()
+        .map(|column| sql_to_json(row, column))
+        .collect()
+}
+
 pub fn sql_to_json(row: &MySqlRow, col: &sqlx::mysql::MySqlColumn) -> Value {
     let raw_value_result = row.try_get_raw(col.ordinal());
     match raw_value_result {
@@ -46,17 +56,14 @@ pub fn sql_nonnull_to_json<'r>(
                 .unwrap_or(f64::NAN)
                 .into()
         }
-        "NUMERIC" | "DECIMAL" | "FIXED" => {
-            <String as Decode<sqlx::MySql>>::decode(raw_value)
-                .ok()
-                .and_then(|s| s.parse::<f64>().ok())
-                .unwrap_or(f64::NAN)
-                .into()
-        }
+        // MySQL sends decimals as text in both protocols
+        "NUMERIC" | "DECIMAL" | "FIXED" => <String as Decode<sqlx::MySql>>::decode(raw_value)
+            .map(value::decimal)
+            .unwrap_or(Value::Null),
         "BIGINT UNSIGNED" => <u64 as Decode<sqlx::MySql>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
-        "INT UNSIGNED" => <u32 as Decode<sqlx::MySql>>::decode(raw_value)
+            .map(value::bigint)
+            .unwrap_or(Value::Null),
+        "INT UNSIGNED" | "MEDIUMINT UNSIGNED" => <u32 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
         "SMALLINT UNSIGNED" => <u16 as Decode<sqlx::MySql>>::decode(raw_value)
@@ -66,9 +73,9 @@ pub fn sql_nonnull_to_json<'r>(
             .unwrap_or_default()
             .into(),
         "BIGINT" => <i64 as Decode<sqlx::MySql>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
-        "INT" | "INTEGER" => <i32 as Decode<sqlx::MySql>>::decode(raw_value)
+            .map(value::bigint)
+            .unwrap_or(Value::Null),
+        "INT" | "INTEGER" | "MEDIUMINT" => <i32 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
         "SMALLINT" => <i16 as Decode<sqlx::MySql>>::decode(raw_value)
@@ -77,35 +84,153 @@ pub fn sql_nonnull_to_json<'r>(
         "BOOL" | "BOOLEAN" | "TINYINT" => <i8 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
-        "DATE" => <chrono::NaiveDate as Decode<sqlx::MySql>>::decode(raw_value)
-            .as_ref()
-            .map_or_else(std::string::ToString::to_string, ToString::to_string)
-            .into(),
-        "TIME" => <chrono::NaiveTime as Decode<sqlx::MySql>>::decode(raw_value)
-            .as_ref()
-            .map_or_else(ToString::to_string, ToString::to_string)
-            .into(),
+        "DATE" => bytes(raw_value).map_or(Value::Null, |b| Value::String(date(b))),
+        "TIME" => bytes(raw_value).map_or(Value::Null, |b| Value::String(time(b))),
         "YEAR" => <i16 as Decode<sqlx::MySql>>::decode(raw_value)
             .unwrap_or_default()
             .into(),
-        "DATETIME" | "DATETIME2" | "DATETIMEOFFSET" | "TIMESTAMP" | "TIMESTAMPTZ" => {
-            let mut date_time = <DateTime<Utc> as Decode<sqlx::MySql>>::decode(get_ref());
-            if date_time.is_err() {
-                date_time = <chrono::NaiveDateTime as Decode<sqlx::MySql>>::decode(raw_value)
-                    .map(|d| d.and_utc());
-            }
-            Value::String(
-                date_time
-                    .as_ref()
-                    .map_or_else(ToString::to_string, DateTime::to_rfc3339),
-            )
+        "DATETIME" | "TIMESTAMP" => {
+            bytes(raw_value).map_or(Value::Null, |b| Value::String(datetime(b)))
         }
-        // "JSON" | "JSON[]" | "JSONB" | "JSONB[]" => {
-        //     <Value as Decode<sqlx::MySql>>::decode(raw_value).unwrap_or_default()
-        // }
-        // Deserialize as a string by default
-        _ => <String as Decode<sqlx::MySql>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
+        "JSON" => bytes(raw_value).map_or(Value::Null, |b| {
+            serde_json::from_slice(b)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(b).to_string()))
+        }),
+        "BIT" => bytes(raw_value).map_or(Value::Null, bit),
+        "BINARY" | "VARBINARY" | "TINYBLOB" | "BLOB" | "MEDIUMBLOB" | "LONGBLOB" => {
+            bytes(raw_value).map_or(Value::Null, value::binary)
+        }
+        "GEOMETRY" => bytes(raw_value).map_or(Value::Null, geometry),
+        // ENUM and SET come as their labels, SET members joined by commas. MariaDB's
+        // UUID and INET6 are sent in their text form and land here too.
+        _ => match bytes(raw_value) {
+            Some(b) => match std::str::from_utf8(b) {
+                Ok(s) => Value::String(s.to_string()),
+                Err(_) => value::binary(b),
+            },
+            None => Value::Null,
+        },
+    }
+}
+
+fn bytes<'r>(raw_value: sqlx::mysql::MySqlValueRef<'r>) -> Option<&'r [u8]> {
+    <&[u8] as Decode<sqlx::MySql>>::decode(raw_value).ok()
+}
+
+/// Temporal values are length prefixed in the binary protocol and plain text in
+/// the text protocol. sqlx keeps the format private, but the length byte is always
+/// below any printable character.
+fn binary_temporal(buf: &[u8]) -> Option<&[u8]> {
+    match buf.first() {
+        Some(len) if *len < b' ' => buf.get(1..1 + *len as usize),
+        _ => None,
+    }
+}
+
+/// Zero dates (`0000-00-00`) are sent without any bytes and kept as such.
+fn date(buf: &[u8]) -> String {
+    match binary_temporal(buf) {
+        Some(b) => binary_date(b),
+        None => String::from_utf8_lossy(buf).to_string(),
+    }
+}
+
+fn binary_date(b: &[u8]) -> String {
+    match b.len() >= 4 {
+        true => format!(
+            "{:04}-{:02}-{:02}",
+            u16::from_le_bytes([b[0], b[1]]),
+            b[2],
+            b[3]
+        ),
+        false => "0000-00-00".to_string(),
+    }
+}
+
+fn clock(hours: u32, minutes: u8, seconds: u8, micros: Option<&[u8]>) -> String {
+    let micros = micros
+        .and_then(|m| m.try_into().ok())
+        .map_or(0, u32::from_le_bytes);
+    match micros {
+        0 => format!("{:02}:{:02}:{:02}", hours, minutes, seconds),
+        micros => format!("{:02}:{:02}:{:02}.{:06}", hours, minutes, seconds, micros),
+    }
+}
+
+/// Valid date times are returned as RFC 3339, zero dates as MySQL prints them.
+fn datetime(buf: &[u8]) -> String {
+    let text = match binary_temporal(buf) {
+        Some(b) => {
+            let time = match b.len() >= 7 {
+                true => clock(b[4] as u32, b[5], b[6], b.get(7..11)),
+                false => clock(0, 0, 0, None),
+            };
+            format!("{} {}", binary_date(b), time)
+        }
+        None => String::from_utf8_lossy(buf).to_string(),
+    };
+    NaiveDateTime::parse_from_str(&text, "%Y-%m-%d %H:%M:%S%.f")
+        .map_or(text, |d| d.and_utc().to_rfc3339())
+}
+
+/// TIME is a duration that can be negative and exceed 24 hours, e.g. `-838:59:59`.
+fn time(buf: &[u8]) -> String {
+    match binary_temporal(buf) {
+        Some(b) if b.len() >= 8 => {
+            let days = u32::from_le_bytes([b[1], b[2], b[3], b[4]]);
+            let sign = if b[0] == 1 { "-" } else { "" };
+            format!("{}{}", sign, clock(days * 24 + b[5] as u32, b[6], b[7], b.get(8..12)))
+        }
+        Some(_) => clock(0, 0, 0, None),
+        None => String::from_utf8_lossy(buf).to_string(),
+    }
+}
+
+/// BIT(M) is a big endian bit field of up to 64 bits.
+fn bit(buf: &[u8]) -> Value {
+    let v = buf.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+    match v > (1 << 53) {
+        true => value::bigint(v),
+        false => v.into(),
+    }
+}
+
+/// MySQL stores geometries as a little endian SRID followed by WKB.
+fn geometry(buf: &[u8]) -> Value {
+    let wkt = buf.get(4..).map(wkb::to_wkt);
+    match (buf.get(..4), wkt) {
+        (Some(srid), Some(Ok(wkt))) => match u32::from_le_bytes(srid.try_into().unwrap()) {
+            0 => Value::String(wkt),
+            srid => Value::String(format!("SRID={};{}", srid, wkt)),
+        },
+        _ => value::binary(buf),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{bit, date, datetime, geometry, time};
+
+    #[test]
+    fn test_decode_raw_values() {
+        assert_eq!(date(&[0]), "0000-00-00");
+        assert_eq!(date(b"0000-00-00"), "0000-00-00");
+        assert_eq!(date(&[4, 0xe8, 0x07, 2, 29]), "2024-02-29");
+        assert_eq!(datetime(&[0]), "0000-00-00 00:00:00");
+        assert_eq!(
+            datetime(&[7, 0xe8, 0x07, 2, 29, 13, 5, 9]),
+            "2024-02-29T13:05:09+00:00"
+        );
+        assert_eq!(time(&[8, 1, 34, 0, 0, 0, 22, 59, 59]), "-838:59:59");
+        assert_eq!(time(b"12:00:00"), "12:00:00");
+        assert_eq!(bit(&[0x01, 0x02]), json!(258));
+        // ST_GeomFromText('POINT(1 2)', 4326)
+        let mut point = vec![0xe6, 0x10, 0, 0, 1, 1, 0, 0, 0];
+        point.extend(1f64.to_le_bytes());
+        point.extend(2f64.to_le_bytes());
+        assert_eq!(geometry(&point), json!("SRID=4326;POINT(1 2)"));
+        assert_eq!(geometry(&[1, 2]), json!("0x0102"));
     }
 }
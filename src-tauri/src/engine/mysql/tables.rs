@@ -1,9 +1,9 @@
 use anyhow::Result;
 use futures::try_join;
 use serde_json::{json, Value};
-use sqlx::MySqlPool;
+use sqlx::{MySqlPool, Row};
 
-use crate::engine::types::connection::InitiatedConnection;
+use crate::engine::types::connection::{ConnectionInfo, InitiatedConnection, TlsInfo};
 
 use super::sql_to_json::row_to_json;
 
@@ -167,3 +167,35 @@ pub async fn get_views(conn: &InitiatedConnection, pool: &MySqlPool) -> Result<V
     );
     Ok(sqlx::query(&query).map(row_to_json).fetch_all(pool).await?)
 }
+
+pub async fn get_connection_info(pool: &MySqlPool) -> Result<ConnectionInfo> {
+    // session status is per connection, so both statements run on the same one
+    let mut conn = pool.acquire().await?;
+    let server_version: String = sqlx::query("SELECT VERSION()")
+        .fetch_one(&mut *conn)
+        .await?
+        .try_get(0)?;
+    let rows =
+        sqlx::query("SHOW SESSION STATUS WHERE Variable_name IN ('Ssl_version', 'Ssl_cipher')")
+            .fetch_all(&mut *conn)
+            .await?;
+    let mut version = String::new();
+    let mut cipher = String::new();
+    for row in rows {
+        let name: String = row.try_get(0)?;
+        let value: String = row.try_get(1)?;
+        match name.as_str() {
+            "Ssl_version" => version = value,
+            "Ssl_cipher" => cipher = value,
+            _ => {}
+        }
+    }
+    let tls = match version.is_empty() {
+        true => None,
+        false => Some(TlsInfo { version, cipher }),
+    };
+    Ok(ConnectionInfo {
+        server_version,
+        tls,
+    })
+}
@@ -0,0 +1,692 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use serde_json::{Map, Value};
+use sqlx::postgres::{PgTypeInfo, PgTypeKind, PgValueFormat, PgValueRef};
+use sqlx::TypeInfo;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::engine::types::value;
+use crate::engine::wkb;
+
+/// Decodes a value from its wire representation without going through sqlx's
+/// `Decode`, which only knows the types it has Rust counterparts for. Enums, domains,
+/// composites, arrays and ranges are resolved through the type's kind, everything
+/// else by name so extension types whose oids vary per database are covered too.
+pub fn decode(type_info: &PgTypeInfo, raw_value: PgValueRef) -> Value {
+    if raw_value.format() == PgValueFormat::Text {
+        return raw_value
+            .as_str()
+            .map(|s| Value::String(s.to_string()))
+            .unwrap_or(Value::Null);
+    }
+    match raw_value.as_bytes() {
+        Ok(buf) => decode_typed(type_info, buf),
+        Err(_) => Value::Null,
+    }
+}
+
+fn decode_typed(type_info: &PgTypeInfo, buf: &[u8]) -> Value {
+    match type_info.kind() {
+        PgTypeKind::Enum(_) => text(buf),
+        PgTypeKind::Domain(base) => decode_typed(base, buf),
+        PgTypeKind::Composite(fields) => record(buf, Some(fields)),
+        PgTypeKind::Array(element) => array(buf, Some(element)),
+        PgTypeKind::Range(element) => range(buf, |b| decode_typed(element, b)),
+        PgTypeKind::Simple | PgTypeKind::Pseudo => decode_named(type_info.name(), buf),
+    }
+}
+
+/// Fields of anonymous records only carry their oid, so they are limited to the
+/// built-in types.
+fn decode_oid(oid: u32, buf: &[u8]) -> Value {
+    if let Some(element) = range_element(oid) {
+        return range(buf, |b| decode_oid(element, b));
+    }
+    match builtin_name(oid) {
+        Some(name) if name.ends_with("[]") => array(buf, None),
+        Some(name) => decode_named(name, buf),
+        None => fallback(buf),
+    }
+}
+
+fn decode_named(name: &str, buf: &[u8]) -> Value {
+    let decoded = match name {
+        "BOOL" => buf.first().map(|b| Value::Bool(*b != 0)),
+        "INT2" => be::<2>(buf).map(|b| i16::from_be_bytes(b).into()),
+        "INT4" => be::<4>(buf).map(|b| i32::from_be_bytes(b).into()),
+        "OID" => be::<4>(buf).map(|b| u32::from_be_bytes(b).into()),
+        "INT8" => be::<8>(buf).map(|b| value::bigint(i64::from_be_bytes(b))),
+        "FLOAT4" => be::<4>(buf).map(|b| float(f32::from_be_bytes(b) as f64)),
+        "FLOAT8" => be::<8>(buf).map(|b| float(f64::from_be_bytes(b))),
+        "NUMERIC" => numeric(buf).map(value::decimal),
+        "MONEY" => be::<8>(buf).map(|b| value::decimal(money(i64::from_be_bytes(b)))),
+        "TEXT" | "VARCHAR" | "CHAR" | "NAME" | "UNKNOWN" | "XML" | "\"CHAR\"" | "citext" => {
+            Some(text(buf))
+        }
+        "JSON" => serde_json::from_slice(buf).ok(),
+        // jsonb and jsonpath are prefixed with a format version
+        "JSONB" => buf.get(1..).and_then(|b| serde_json::from_slice(b).ok()),
+        "JSONPATH" | "ltree" | "lquery" | "ltxtquery" => buf.get(1..).map(text),
+        "UUID" => uuid::Uuid::from_slice(buf)
+            .ok()
+            .map(|u| Value::String(u.to_string())),
+        "BYTEA" => Some(value::binary(buf)),
+        "DATE" => be::<4>(buf).map(|b| Value::String(date(i32::from_be_bytes(b)))),
+        "TIME" => be::<8>(buf).map(|b| Value::String(time(i64::from_be_bytes(b)))),
+        "TIMETZ" => timetz(buf).map(Value::String),
+        "TIMESTAMP" => be::<8>(buf).map(|b| Value::String(timestamp(i64::from_be_bytes(b)))),
+        "TIMESTAMPTZ" => be::<8>(buf).map(|b| Value::String(timestamptz(i64::from_be_bytes(b)))),
+        "INTERVAL" => interval(buf).map(Value::String),
+        "INET" | "CIDR" => inet(buf, name == "CIDR").map(Value::String),
+        "MACADDR" | "MACADDR8" => Some(Value::String(
+            buf.iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":"),
+        )),
+        "BIT" | "VARBIT" => bits(buf).map(Value::String),
+        "POINT" | "LSEG" | "BOX" | "LINE" | "CIRCLE" | "PATH" | "POLYGON" => {
+            geometric(name, buf).map(Value::String)
+        }
+        "RECORD" => Some(record(buf, None)),
+        "hstore" => hstore(buf),
+        "geometry" | "geography" => wkb::to_wkt(buf).ok().map(Value::String),
+        "vector" | "halfvec" => vector(buf, name == "halfvec"),
+        "sparsevec" => sparsevec(buf).map(Value::String),
+        _ => None,
+    };
+    decoded.unwrap_or_else(|| fallback(buf))
+}
+
+/// Unknown types are shown as text when they are valid UTF-8, as binary otherwise.
+fn fallback(buf: &[u8]) -> Value {
+    match std::str::from_utf8(buf) {
+        Ok(s) => Value::String(s.to_string()),
+        Err(_) => value::binary(buf),
+    }
+}
+
+fn be<const N: usize>(buf: &[u8]) -> Option<[u8; N]> {
+    buf.get(..N)?.try_into().ok()
+}
+
+fn i32_at(buf: &[u8], pos: usize) -> Option<i32> {
+    be::<4>(buf.get(pos..)?).map(i32::from_be_bytes)
+}
+
+fn f64_at(buf: &[u8], pos: usize) -> Option<f64> {
+    be::<8>(buf.get(pos..)?).map(f64::from_be_bytes)
+}
+
+fn text(buf: &[u8]) -> Value {
+    Value::String(String::from_utf8_lossy(buf).to_string())
+}
+
+fn float(v: f64) -> Value {
+    serde_json::Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or_else(|| Value::String(v.to_string()))
+}
+
+/// Reads the binary NUMERIC form: digit count, weight, sign and display scale
+/// followed by base 10000 digits.
+pub fn numeric(buf: &[u8]) -> Option<String> {
+    let read = |i: usize| buf.get(i..i + 2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+    let ndigits = read(0)? as usize;
+    let weight = read(2)? as i16 as i64;
+    let sign = read(4)?;
+    let dscale = read(6)? as usize;
+    match sign {
+        0xC000 => return Some("NaN".to_string()),
+        0xD000 => return Some("Infinity".to_string()),
+        0xF000 => return Some("-Infinity".to_string()),
+        _ => {}
+    }
+    let digits = (0..ndigits)
+        .map(|i| read(8 + i * 2))
+        .collect::<Option<Vec<u16>>>()?;
+    let digit = |i: i64| match i >= 0 {
+        true => digits.get(i as usize).copied().unwrap_or(0),
+        false => 0,
+    };
+
+    let mut out = String::new();
+    if sign == 0x4000 && digits.iter().any(|d| *d != 0) {
+        out.push('-');
+    }
+    if weight < 0 {
+        out.push('0');
+    } else {
+        out.push_str(&digit(0).to_string());
+        for i in 1..=weight {
+            out.push_str(&format!("{:04}", digit(i)));
+        }
+    }
+    if dscale > 0 {
+        let mut fraction = String::new();
+        let mut i = weight + 1;
+        while fraction.len() < dscale {
+            fraction.push_str(&format!("{:04}", digit(i)));
+            i += 1;
+        }
+        fraction.truncate(dscale);
+        out.push('.');
+        out.push_str(&fraction);
+    }
+    Some(out)
+}
+
+/// MONEY is sent as an integer amount of cents, assuming a two digit `lc_monetary`.
+fn money(cents: i64) -> String {
+    let sign = if cents < 0 { "-" } else { "" };
+    let cents = cents.unsigned_abs();
+    format!("{}{}.{:02}", sign, cents / 100, cents % 100)
+}
+
+const DAY_MICROS: i64 = 86_400_000_000;
+
+fn epoch() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2000, 1, 1)
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .expect("Postgres epoch is a valid date")
+}
+
+/// Dates past chrono's range of about 262000 years, Postgres dates reach the
+/// year 5874897. Uses the days to civil date algorithm of Howard Hinnant.
+fn far_date(days: i64) -> String {
+    // days from 0000-03-01 to the Postgres epoch
+    let z = days + 730_425;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// A timestamp as a date and a time of day, for the ones chrono can't hold.
+fn far_timestamp(micros: i64) -> (String, String) {
+    (
+        far_date(micros.div_euclid(DAY_MICROS)),
+        time(micros.rem_euclid(DAY_MICROS)),
+    )
+}
+
+fn date(days: i32) -> String {
+    match days {
+        i32::MAX => "infinity".to_string(),
+        i32::MIN => "-infinity".to_string(),
+        days => epoch()
+            .date()
+            .checked_add_signed(Duration::days(days as i64))
+            .map_or_else(|| far_date(days as i64), |d| d.to_string()),
+    }
+}
+
+/// Postgres allows `24:00:00`, which chrono would wrap to midnight.
+fn time(micros: i64) -> String {
+    match micros {
+        DAY_MICROS => "24:00:00".to_string(),
+        micros => (NaiveTime::MIN + Duration::microseconds(micros)).to_string(),
+    }
+}
+
+fn timetz(buf: &[u8]) -> Option<String> {
+    let micros = i64::from_be_bytes(be::<8>(buf)?);
+    // the offset is sent in seconds west of UTC
+    let offset = -i32_at(buf, 8)?;
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    Some(format!(
+        "{}{}{:02}:{:02}",
+        time(micros),
+        sign,
+        offset / 3600,
+        offset % 3600 / 60
+    ))
+}
+
+fn timestamp(micros: i64) -> String {
+    match micros {
+        i64::MAX => "infinity".to_string(),
+        i64::MIN => "-infinity".to_string(),
+        micros => match epoch().checked_add_signed(Duration::microseconds(micros)) {
+            Some(t) => t.to_string(),
+            None => {
+                let (date, time) = far_timestamp(micros);
+                format!("{} {}", date, time)
+            }
+        },
+    }
+}
+
+fn timestamptz(micros: i64) -> String {
+    match micros {
+        i64::MAX => "infinity".to_string(),
+        i64::MIN => "-infinity".to_string(),
+        micros => match epoch().checked_add_signed(Duration::microseconds(micros)) {
+            Some(t) => DateTime::<Utc>::from_naive_utc_and_offset(t, Utc).to_rfc3339(),
+            None => {
+                let (date, time) = far_timestamp(micros);
+                format!("{}T{}+00:00", date, time)
+            }
+        },
+    }
+}
+
+/// Formats like Postgres' default `IntervalStyle`, e.g. `1 year 2 mons 3 days 04:05:06`.
+fn interval(buf: &[u8]) -> Option<String> {
+    let micros = i64::from_be_bytes(be::<8>(buf)?);
+    let days = i32_at(buf, 8)?;
+    let months = i32_at(buf, 12)?;
+    let plural = |n: i32, unit: &str, units: &str| match n.abs() {
+        1 => format!("{} {}", n, unit),
+        _ => format!("{} {}", n, units),
+    };
+    let mut parts = vec![];
+    if months / 12 != 0 {
+        parts.push(plural(months / 12, "year", "years"));
+    }
+    if months % 12 != 0 {
+        parts.push(plural(months % 12, "mon", "mons"));
+    }
+    if days != 0 {
+        parts.push(plural(days, "day", "days"));
+    }
+    if micros != 0 || parts.is_empty() {
+        let sign = if micros < 0 { "-" } else { "" };
+        let micros = micros.unsigned_abs();
+        let seconds = micros / 1_000_000;
+        let mut time = format!(
+            "{}{:02}:{:02}:{:02}",
+            sign,
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        );
+        if micros % 1_000_000 != 0 {
+            let fraction = format!("{:06}", micros % 1_000_000);
+            time.push('.');
+            time.push_str(fraction.trim_end_matches('0'));
+        }
+        parts.push(time);
+    }
+    Some(parts.join(" "))
+}
+
+fn inet(buf: &[u8], cidr: bool) -> Option<String> {
+    let (family, bits, address) = (*buf.first()?, *buf.get(1)?, buf.get(4..)?);
+    let (address, max_bits) = match family {
+        2 => (
+            Ipv4Addr::from(<[u8; 4]>::try_from(address).ok()?).to_string(),
+            32,
+        ),
+        3 => (
+            Ipv6Addr::from(<[u8; 16]>::try_from(address).ok()?).to_string(),
+            128,
+        ),
+        _ => return None,
+    };
+    Some(match cidr || bits != max_bits {
+        true => format!("{}/{}", address, bits),
+        false => address,
+    })
+}
+
+fn bits(buf: &[u8]) -> Option<String> {
+    let len = i32_at(buf, 0)? as usize;
+    let bytes = buf.get(4..)?;
+    (0..len)
+        .map(|i| match bytes.get(i / 8)? & (0x80 >> (i % 8)) {
+            0 => Some('0'),
+            _ => Some('1'),
+        })
+        .collect()
+}
+
+fn geometric(name: &str, buf: &[u8]) -> Option<String> {
+    let point = |pos: usize| Some(format!("({},{})", f64_at(buf, pos)?, f64_at(buf, pos + 8)?));
+    let points = |count: usize, start: usize| {
+        (0..count)
+            .map(|i| point(start + i * 16))
+            .collect::<Option<Vec<_>>>()
+            .map(|p| p.join(","))
+    };
+    Some(match name {
+        "POINT" => point(0)?,
+        "LSEG" => format!("[{},{}]", point(0)?, point(16)?),
+        "BOX" => format!("{},{}", point(0)?, point(16)?),
+        "LINE" => format!(
+            "{{{},{},{}}}",
+            f64_at(buf, 0)?,
+            f64_at(buf, 8)?,
+            f64_at(buf, 16)?
+        ),
+        "CIRCLE" => format!("<{},{}>", point(0)?, f64_at(buf, 16)?),
+        "PATH" => {
+            let closed = *buf.first()? != 0;
+            let path = points(i32_at(buf, 1)? as usize, 5)?;
+            match closed {
+                true => format!("({})", path),
+                false => format!("[{}]", path),
+            }
+        }
+        "POLYGON" => format!("({})", points(i32_at(buf, 0)? as usize, 4)?),
+        _ => return None,
+    })
+}
+
+/// Splits a run of length prefixed values, a length of -1 marks a NULL.
+fn read_value<'a>(buf: &'a [u8], pos: &mut usize) -> Option<Option<&'a [u8]>> {
+    let len = i32_at(buf, *pos)?;
+    *pos += 4;
+    if len < 0 {
+        return Some(None);
+    }
+    let value = buf.get(*pos..*pos + len as usize)?;
+    *pos += len as usize;
+    Some(Some(value))
+}
+
+fn array(buf: &[u8], element: Option<&PgTypeInfo>) -> Value {
+    let decode_element = |b: &[u8], oid: u32| match element {
+        Some(element) => decode_typed(element, b),
+        None => decode_oid(oid, b),
+    };
+    let parse = || -> Option<Value> {
+        let ndim = i32_at(buf, 0)? as usize;
+        let oid = i32_at(buf, 8)? as u32;
+        if ndim == 0 {
+            return Some(Value::Array(vec![]));
+        }
+        let dims = (0..ndim)
+            .map(|d| i32_at(buf, 12 + d * 8).map(|n| n as usize))
+            .collect::<Option<Vec<_>>>()?;
+        let mut pos = 12 + ndim * 8;
+        let mut values = vec![];
+        for _ in 0..dims.iter().product::<usize>() {
+            values.push(match read_value(buf, &mut pos)? {
+                Some(b) => decode_element(b, oid),
+                None => Value::Null,
+            });
+        }
+        // nest the flat element list by dimension, innermost first
+        for dim in dims[1..].iter().rev() {
+            values = values
+                .chunks(*dim)
+                .map(|chunk| Value::Array(chunk.to_vec()))
+                .collect();
+        }
+        Some(Value::Array(values))
+    };
+    parse().unwrap_or(Value::Null)
+}
+
+fn record(buf: &[u8], fields: Option<&[(String, PgTypeInfo)]>) -> Value {
+    let parse = || -> Option<Value> {
+        let count = i32_at(buf, 0)? as usize;
+        let mut pos = 4;
+        let mut values = vec![];
+        for i in 0..count {
+            let oid = i32_at(buf, pos)? as u32;
+            pos += 4;
+            let field = fields.and_then(|f| f.get(i));
+            let value = match read_value(buf, &mut pos)? {
+                Some(b) => match field {
+                    Some((_, type_info)) => decode_typed(type_info, b),
+                    None => decode_oid(oid, b),
+                },
+                None => Value::Null,
+            };
+            values.push((field.map(|(name, _)| name.clone()), value));
+        }
+        Some(match fields {
+            Some(_) => Value::Object(
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (name, v))| (name.unwrap_or(format!("f{}", i + 1)), v))
+                    .collect::<Map<_, _>>(),
+            ),
+            None => Value::Array(values.into_iter().map(|(_, v)| v).collect()),
+        })
+    };
+    parse().unwrap_or(Value::Null)
+}
+
+/// Formats a range like Postgres does, e.g. `[1,10)` or `empty`.
+fn range(buf: &[u8], decode_bound: impl Fn(&[u8]) -> Value) -> Value {
+    const EMPTY: u8 = 0x01;
+    const LB_INC: u8 = 0x02;
+    const UB_INC: u8 = 0x04;
+    const LB_INF: u8 = 0x08;
+    const UB_INF: u8 = 0x10;
+    let parse = || -> Option<String> {
+        let flags = *buf.first()?;
+        if flags & EMPTY != 0 {
+            return Some("empty".to_string());
+        }
+        let mut pos = 1;
+        let mut bound = |infinite: bool| -> Option<String> {
+            if infinite {
+                return Some(String::new());
+            }
+            let value = decode_bound(read_value(buf, &mut pos)??);
+            Some(match value {
+                Value::String(s) if s.contains([' ', ',', '"', '(', ')', '[', ']']) => {
+                    format!("\"{}\"", s.replace('"', "\\\""))
+                }
+                Value::String(s) => s,
+                v => v.to_string(),
+            })
+        };
+        let lower = bound(flags & LB_INF != 0)?;
+        let upper = bound(flags & UB_INF != 0)?;
+        Some(format!(
+            "{}{},{}{}",
+            if flags & LB_INC != 0 { '[' } else { '(' },
+            lower,
+            upper,
+            if flags & UB_INC != 0 { ']' } else { ')' },
+        ))
+    };
+    parse().map(Value::String).unwrap_or(Value::Null)
+}
+
+fn hstore(buf: &[u8]) -> Option<Value> {
+    let count = i32_at(buf, 0)? as usize;
+    let mut pos = 4;
+    let mut object = Map::new();
+    for _ in 0..count {
+        let key = read_value(buf, &mut pos)??;
+        let value = read_value(buf, &mut pos)?.map(text).unwrap_or(Value::Null);
+        object.insert(String::from_utf8_lossy(key).to_string(), value);
+    }
+    Some(Value::Object(object))
+}
+
+/// pgvector's `vector` and `halfvec`: dimension count, an unused word, then the
+/// 4 or 2 byte floats.
+fn vector(buf: &[u8], half: bool) -> Option<Value> {
+    let dim = u16::from_be_bytes(be::<2>(buf)?) as usize;
+    let size = if half { 2 } else { 4 };
+    (0..dim)
+        .map(|i| {
+            let pos = 4 + i * size;
+            let v = match half {
+                true => f16_to_f32(u16::from_be_bytes(be::<2>(buf.get(pos..)?)?)),
+                false => f32::from_be_bytes(be::<4>(buf.get(pos..)?)?),
+            };
+            Some(float(v as f64))
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(Value::Array)
+}
+
+/// pgvector's `sparsevec` in its text form, `{1:0.5,3:2}/5` with 1-based indices.
+fn sparsevec(buf: &[u8]) -> Option<String> {
+    let dim = i32_at(buf, 0)?;
+    let nnz = i32_at(buf, 4)? as usize;
+    let values = 12 + nnz * 4;
+    let entries = (0..nnz)
+        .map(|i| {
+            let index = i32_at(buf, 12 + i * 4)?;
+            let value = f32::from_be_bytes(be::<4>(buf.get(values + i * 4..)?)?);
+            Some(format!("{}:{}", index + 1, value))
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(format!("{{{}}}/{}", entries.join(","), dim))
+}
+
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = (bits >> 10) & 0x1f;
+    let fraction = (bits & 0x3ff) as f32;
+    sign * match exponent {
+        0 => fraction * 2f32.powi(-24),
+        0x1f if fraction == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        e => (1.0 + fraction / 1024.0) * 2f32.powi(e as i32 - 15),
+    }
+}
+
+fn range_element(oid: u32) -> Option<u32> {
+    Some(match oid {
+        3904 => 23,
+        3906 => 1700,
+        3908 => 1114,
+        3910 => 1184,
+        3912 => 1082,
+        3926 => 20,
+        _ => return None,
+    })
+}
+
+fn builtin_name(oid: u32) -> Option<&'static str> {
+    Some(match oid {
+        16 => "BOOL",
+        17 => "BYTEA",
+        18 => "\"CHAR\"",
+        19 => "NAME",
+        20 => "INT8",
+        21 => "INT2",
+        23 => "INT4",
+        25 => "TEXT",
+        26 => "OID",
+        114 => "JSON",
+        142 => "XML",
+        600 => "POINT",
+        601 => "LSEG",
+        602 => "PATH",
+        603 => "BOX",
+        604 => "POLYGON",
+        628 => "LINE",
+        650 => "CIDR",
+        700 => "FLOAT4",
+        701 => "FLOAT8",
+        718 => "CIRCLE",
+        774 => "MACADDR8",
+        790 => "MONEY",
+        829 => "MACADDR",
+        869 => "INET",
+        1042 => "CHAR",
+        1043 => "VARCHAR",
+        1082 => "DATE",
+        1083 => "TIME",
+        1114 => "TIMESTAMP",
+        1184 => "TIMESTAMPTZ",
+        1186 => "INTERVAL",
+        1266 => "TIMETZ",
+        1560 => "BIT",
+        1562 => "VARBIT",
+        1700 => "NUMERIC",
+        2249 => "RECORD",
+        2950 => "UUID",
+        3802 => "JSONB",
+        4072 => "JSONPATH",
+        199 | 1000 | 1001 | 1005 | 1007 | 1009 | 1014 | 1015 | 1016 | 1021 | 1022 | 1115 | 1182
+        | 1183 | 1185 | 1187 | 1231 | 2951 | 3807 => "[]",
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{array, date, decode_named, far_date, numeric, time, timestamp};
+    use serde_json::json;
+
+    fn numeric_bytes(weight: i16, sign: u16, dscale: u16, digits: &[u16]) -> Vec<u8> {
+        let mut buf = vec![];
+        for v in [digits.len() as u16, weight as u16, sign, dscale] {
+            buf.extend(v.to_be_bytes());
+        }
+        for d in digits {
+            buf.extend(d.to_be_bytes());
+        }
+        buf
+    }
+
+    #[test]
+    fn test_numeric() {
+        let s = |b: Vec<u8>| numeric(&b).unwrap();
+        assert_eq!(s(numeric_bytes(0, 0, 2, &[123, 4500])), "123.45");
+        assert_eq!(s(numeric_bytes(-1, 0, 3, &[10])), "0.001");
+        assert_eq!(s(numeric_bytes(1, 0, 0, &[1])), "10000");
+        assert_eq!(
+            s(numeric_bytes(1, 0x4000, 2, &[12, 3456, 7800])),
+            "-123456.78"
+        );
+        assert_eq!(
+            s(numeric_bytes(4, 0, 0, &[9223, 3720, 3685, 4775, 8070])),
+            "92233720368547758070"
+        );
+        assert_eq!(s(numeric_bytes(0, 0, 2, &[])), "0.00");
+        assert_eq!(s(numeric_bytes(0, 0xC000, 0, &[])), "NaN");
+    }
+
+    #[test]
+    fn test_decode_named() {
+        let mut interval = 3_723_500_000i64.to_be_bytes().to_vec();
+        interval.extend(3i32.to_be_bytes());
+        interval.extend(14i32.to_be_bytes());
+        assert_eq!(
+            decode_named("INTERVAL", &interval),
+            json!("1 year 2 mons 3 days 01:02:03.5")
+        );
+        assert_eq!(
+            decode_named("INET", &[2, 24, 1, 4, 10, 0, 0, 0]),
+            json!("10.0.0.0/24")
+        );
+        assert_eq!(
+            decode_named("MONEY", &(-1234i64).to_be_bytes()),
+            json!("-12.34")
+        );
+        assert_eq!(
+            decode_named("DATE", &31i32.to_be_bytes()),
+            json!("2000-02-01")
+        );
+        // '5874897-12-31'::date and '294276-12-31 23:59:59'::timestamp
+        assert_eq!(date(2_145_031_948), "5874897-12-31");
+        assert_eq!(far_date(-2_451_545), date(-2_451_545));
+        assert_eq!(
+            timestamp(9_223_371_331_199_000_000),
+            "294276-12-31 23:59:59"
+        );
+        assert_eq!(time(86_400_000_000), "24:00:00");
+        assert_eq!(decode_named("BYTEA", &[0xde, 0xad]), json!("0xdead"));
+
+        // '{{1,2},{3,NULL}}'::int4[]
+        let mut buf = vec![];
+        for v in [2i32, 1, 23, 2, 1, 2, 1] {
+            buf.extend(v.to_be_bytes());
+        }
+        for v in [1i32, 2, 3] {
+            buf.extend(4i32.to_be_bytes());
+            buf.extend(v.to_be_bytes());
+        }
+        buf.extend((-1i32).to_be_bytes());
+        assert_eq!(array(&buf, None), json!([[1, 2], [3, null]]));
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::HashSet;
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+use sqlx::PgPool;
+
+use crate::engine::types::{
+    config::Dialect,
+    connection::InitiatedConnection,
+    ddl::{text, ObjectType, SchemaObject, TableDdl},
+};
+
+use super::query::raw_query;
+
+fn literal(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+fn qualified(conn: &InitiatedConnection, name: &str) -> String {
+    format!(
+        "{}.{}",
+        Dialect::Postgresql.quote_ident(&conn.get_schema()),
+        Dialect::Postgresql.quote_ident(name)
+    )
+}
+
+/// The `regclass` of a relation in the connection's schema, for catalog queries.
+fn regclass(conn: &InitiatedConnection, name: &str) -> String {
+    format!("{}::regclass", literal(&qualified(conn, name)))
+}
+
+fn definitions(rows: &[Value]) -> Vec<String> {
+    rows.iter()
+        .filter_map(|row| text(row, "definition"))
+        .collect()
+}
+
+pub async fn get_objects(conn: &InitiatedConnection, pool: &PgPool) -> Result<Vec<SchemaObject>> {
+    let schema = literal(&conn.get_schema());
+    // objects that belong to extensions are created with the extension. Types
+    // are enums, domains and composite types, not the row types of tables.
+    // Routines get a table whose row type, or array of it, an overload of
+    // theirs takes or returns, they can only be created after it
+    let query = format!(
+        "SELECT name, kind, table_name FROM (
+            SELECT e.oid, e.extname::text AS name, 'e' AS kind, NULL AS table_name
+            FROM pg_extension e JOIN pg_namespace n ON n.oid = e.extnamespace
+            WHERE n.nspname = {schema}
+            UNION ALL
+            SELECT t.oid, t.typname::text, 'y', NULL
+            FROM pg_type t JOIN pg_namespace n ON n.oid = t.typnamespace
+            LEFT JOIN pg_class c ON c.oid = t.typrelid
+            WHERE n.nspname = {schema}
+                AND (t.typtype IN ('e', 'd') OR (t.typtype = 'c' AND c.relkind = 'c'))
+            UNION ALL
+            SELECT c.oid, c.relname::text,
+                translate(c.relkind::text, 'p', 'r'), NULL
+            FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = {schema} AND c.relkind IN ('r', 'p', 'v', 'm')
+            UNION ALL
+            SELECT p.oid, p.proname::text, p.prokind::text, (
+                SELECT min(c.relname::text) FROM pg_proc o
+                JOIN pg_depend d ON d.classid = 'pg_proc'::regclass AND d.objid = o.oid
+                    AND d.refclassid = 'pg_type'::regclass
+                JOIN pg_type t ON t.oid = d.refobjid
+                JOIN pg_class c ON c.reltype IN (t.oid, t.typelem)
+                WHERE o.pronamespace = p.pronamespace AND o.proname = p.proname
+                    AND c.relkind IN ('r', 'p', 'f')
+            )
+            FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = {schema} AND p.prokind IN ('f', 'p')
+            UNION ALL
+            SELECT t.oid, t.tgname::text, 't', c.relname::text
+            FROM pg_trigger t JOIN pg_class c ON c.oid = t.tgrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = {schema} AND NOT t.tgisinternal
+        ) objects
+        WHERE NOT EXISTS (
+            SELECT 1 FROM pg_depend d WHERE d.objid = objects.oid AND d.deptype = 'e'
+        )
+        ORDER BY objects.oid;"
+    );
+    let mut seen = HashSet::new();
+    let objects = raw_query(pool, &query)
+        .await?
+        .iter()
+        .filter_map(|row| {
+            let object_type = match text(row, "kind")?.as_str() {
+                "r" => ObjectType::Table,
+                "v" => ObjectType::View,
+                "m" => ObjectType::MaterializedView,
+                "f" => ObjectType::Function,
+                "p" => ObjectType::Procedure,
+                "e" => ObjectType::Extension,
+                "y" => ObjectType::Type,
+                _ => ObjectType::Trigger,
+            };
+            let name = text(row, "name")?;
+            let table = text(row, "table_name");
+            // overloads of a routine are defined together
+            seen.insert((object_type, name.clone(), table.clone()))
+                .then(|| SchemaObject::new(object_type, &name, table.as_deref()))
+        })
+        .collect();
+    Ok(objects)
+}
+
+pub async fn get_create_statement(
+    conn: &InitiatedConnection,
+    pool: &PgPool,
+    object_type: ObjectType,
+    name: &str,
+) -> Result<String> {
+    let schema = literal(&conn.get_schema());
+    let query = match object_type {
+        ObjectType::Table => return Ok(get_table_ddl(conn, pool, name).await?.script()),
+        ObjectType::View => format!(
+            "SELECT 'CREATE OR REPLACE VIEW ' || {} || ' AS' || chr(10)
+                || rtrim(pg_get_viewdef({}, true), ';') AS definition;",
+            literal(&qualified(conn, name)),
+            regclass(conn, name)
+        ),
+        // with the indices refreshing it concurrently needs
+        ObjectType::MaterializedView => format!(
+            "SELECT 'CREATE MATERIALIZED VIEW ' || {} || ' AS' || chr(10)
+                || rtrim(pg_get_viewdef(c.oid, true), ';')
+                || CASE WHEN c.relispopulated THEN ' WITH DATA' ELSE ' WITH NO DATA' END
+                AS definition
+            FROM pg_class c WHERE c.oid = {}
+            UNION ALL
+            SELECT * FROM (
+                SELECT pg_get_indexdef(indexrelid) FROM pg_index
+                WHERE indrelid = {} ORDER BY indexrelid
+            ) indices;",
+            literal(&qualified(conn, name)),
+            regclass(conn, name),
+            regclass(conn, name)
+        ),
+        ObjectType::Index => format!(
+            "SELECT pg_get_indexdef(c.oid) AS definition
+            FROM pg_class c JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = {} AND c.relname = {} AND c.relkind IN ('i', 'I');",
+            schema,
+            literal(name)
+        ),
+        ObjectType::Trigger => format!(
+            "SELECT pg_get_triggerdef(t.oid, true) AS definition
+            FROM pg_trigger t JOIN pg_class c ON c.oid = t.tgrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = {} AND t.tgname = {} AND NOT t.tgisinternal ORDER BY t.oid;",
+            schema,
+            literal(name)
+        ),
+        ObjectType::Extension => format!(
+            "SELECT 'CREATE EXTENSION IF NOT EXISTS ' || quote_ident(e.extname)
+                || ' WITH SCHEMA ' || quote_ident(n.nspname) AS definition
+            FROM pg_extension e JOIN pg_namespace n ON n.oid = e.extnamespace
+            WHERE e.extname = {};",
+            literal(name)
+        ),
+        ObjectType::Type => format!(
+            "SELECT CASE t.typtype
+                WHEN 'e' THEN 'CREATE TYPE ' || {qualified} || ' AS ENUM ('
+                    || coalesce((SELECT string_agg(quote_literal(e.enumlabel), ', '
+                        ORDER BY e.enumsortorder) FROM pg_enum e WHERE e.enumtypid = t.oid), '')
+                    || ')'
+                WHEN 'd' THEN 'CREATE DOMAIN ' || {qualified} || ' AS '
+                    || format_type(t.typbasetype, t.typtypmod)
+                    || coalesce(' DEFAULT ' || t.typdefault, '')
+                    || CASE WHEN t.typnotnull THEN ' NOT NULL' ELSE '' END
+                    || coalesce((SELECT string_agg(' CONSTRAINT ' || quote_ident(c.conname)
+                        || ' ' || pg_get_constraintdef(c.oid, true), '' ORDER BY c.conname)
+                        FROM pg_constraint c WHERE c.contypid = t.oid), '')
+                ELSE 'CREATE TYPE ' || {qualified} || ' AS ('
+                    || coalesce((SELECT string_agg(quote_ident(a.attname) || ' '
+                        || format_type(a.atttypid, a.atttypmod), ', ' ORDER BY a.attnum)
+                        FROM pg_attribute a
+                        WHERE a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped), '')
+                    || ')'
+                END AS definition
+            FROM pg_type t JOIN pg_namespace n ON n.oid = t.typnamespace
+            WHERE n.nspname = {schema} AND t.typname = {name};",
+            qualified = literal(&qualified(conn, name)),
+            name = literal(name)
+        ),
+        ObjectType::Function | ObjectType::Procedure => format!(
+            "SELECT pg_get_functiondef(p.oid) AS definition
+            FROM pg_proc p JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE n.nspname = {} AND p.proname = {} AND p.prokind = '{}' ORDER BY p.oid;",
+            schema,
+            literal(name),
+            match object_type {
+                ObjectType::Function => 'f',
+                _ => 'p',
+            }
+        ),
+    };
+    let statements: Vec<String> = definitions(&raw_query(pool, &query).await?)
+        .iter()
+        .map(|s| s.trim_end().to_string())
+        .collect();
+    if statements.is_empty() {
+        bail!("{} was not found", name);
+    }
+    // every overload of a function, or every table's trigger of that name
+    Ok(statements.join(";\n\n"))
+}
+
+/// Rebuilds `CREATE TABLE` from the catalogs, Postgres having no statement
+/// that shows it. Sequences the columns' defaults draw from are created
+/// first and owned by their columns after, and foreign keys are added by
+/// `ALTER TABLE`. Partitions are created
+/// `PARTITION OF` their parent, whose columns and constraints they inherit.
+pub async fn get_table_ddl(
+    conn: &InitiatedConnection,
+    pool: &PgPool,
+    table: &str,
+) -> Result<TableDdl> {
+    let relation = regclass(conn, table);
+    let query = format!(
+        "SELECT c.relkind::text AS kind, pg_get_partkeydef(c.oid) AS partition_key,
+            pg_get_expr(c.relpartbound, c.oid) AS partition_bound,
+            obj_description(c.oid, 'pg_class') AS comment,
+            pn.nspname::text AS parent_schema, p.relname::text AS parent
+        FROM pg_class c
+        LEFT JOIN pg_inherits i ON i.inhrelid = c.oid AND c.relispartition
+        LEFT JOIN pg_class p ON p.oid = i.inhparent
+        LEFT JOIN pg_namespace pn ON pn.oid = p.relnamespace
+        WHERE c.oid = {};",
+        relation
+    );
+    let rows = raw_query(pool, &query).await?;
+    let relation_row = rows
+        .first()
+        .ok_or_else(|| anyhow!("{} was not found", table))?;
+    let parent = match (
+        text(relation_row, "parent_schema"),
+        text(relation_row, "parent"),
+    ) {
+        (Some(schema), Some(parent)) => Some(format!(
+            "{}.{}",
+            Dialect::Postgresql.quote_ident(&schema),
+            Dialect::Postgresql.quote_ident(&parent)
+        )),
+        _ => None,
+    };
+    let partitioned = text(relation_row, "kind").as_deref() == Some("p");
+    let mut comments = vec![];
+    if let Some(comment) = text(relation_row, "comment") {
+        comments.push(format!(
+            "COMMENT ON TABLE {} IS {}",
+            qualified(conn, table),
+            literal(&comment)
+        ));
+    }
+
+    let query = format!(
+        "SELECT a.attname::text AS name, format_type(a.atttypid, a.atttypmod) AS type,
+            a.attnotnull AS not_null, pg_get_expr(d.adbin, d.adrelid) AS default_value,
+            a.attidentity::text AS identity, a.attgenerated::text AS generated,
+            col_description(a.attrelid, a.attnum) AS comment
+        FROM pg_attribute a
+        LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+        WHERE a.attrelid = {} AND a.attnum > 0 AND NOT a.attisdropped
+        ORDER BY a.attnum;",
+        relation
+    );
+    let columns = raw_query(pool, &query).await?;
+    let mut generated = vec![];
+    let mut lines: Vec<String> = columns
+        .iter()
+        .filter_map(|column| {
+            let name = text(column, "name")?;
+            if let Some(comment) = text(column, "comment") {
+                comments.push(format!(
+                    "COMMENT ON COLUMN {}.{} IS {}",
+                    qualified(conn, table),
+                    Dialect::Postgresql.quote_ident(&name),
+                    literal(&comment)
+                ));
+            }
+            if text(column, "generated").as_deref() == Some("s") {
+                generated.push(name.clone());
+            }
+            // partitions have the columns of their parent
+            if parent.is_some() {
+                return None;
+            }
+            let mut line = format!(
+                "    {} {}",
+                Dialect::Postgresql.quote_ident(&name),
+                text(column, "type")?
+            );
+            let default = text(column, "default_value");
+            match (
+                text(column, "identity").as_deref(),
+                text(column, "generated").as_deref(),
+            ) {
+                (Some("a"), _) => line += " GENERATED ALWAYS AS IDENTITY",
+                (Some("d"), _) => line += " GENERATED BY DEFAULT AS IDENTITY",
+                (_, Some("s")) => {
+                    line += &format!(
+                        " GENERATED ALWAYS AS ({}) STORED",
+                        default.unwrap_or_default()
+                    );
+                }
+                _ => {
+                    if let Some(default) = default {
+                        line += &format!(" DEFAULT {}", default);
+                    }
+                }
+            }
+            if column["not_null"] == Value::Bool(true) {
+                line += " NOT NULL";
+            }
+            Some(line)
+        })
+        .collect();
+
+    // partitions inherit the constraints of their parent
+    let inherited = match parent {
+        Some(_) => "AND conislocal",
+        None => "",
+    };
+    let query = format!(
+        "SELECT conname::text AS name, contype::text AS type,
+            pg_get_constraintdef(oid, true) AS definition
+        FROM pg_constraint
+        WHERE conrelid = {} AND contype IN ('p', 'u', 'c', 'f', 'x') {}
+        ORDER BY contype, conname;",
+        relation, inherited
+    );
+    let mut foreign_keys = vec![];
+    for constraint in raw_query(pool, &query).await? {
+        let (Some(name), Some(definition)) =
+            (text(&constraint, "name"), text(&constraint, "definition"))
+        else {
+            continue;
+        };
+        let constraint_type = text(&constraint, "type").unwrap_or_default();
+        let name = Dialect::Postgresql.quote_ident(&name);
+        match constraint_type.as_str() {
+            "f" => foreign_keys.push(format!(
+                "ALTER TABLE {} ADD CONSTRAINT {} {}",
+                qualified(conn, table),
+                name,
+                definition
+            )),
+            _ => lines.push(format!("    CONSTRAINT {} {}", name, definition)),
+        }
+    }
+    let body = if parent.is_some() && lines.is_empty() {
+        String::new()
+    } else {
+        format!(" (\n{}\n)", lines.join(",\n"))
+    };
+    let mut create = match &parent {
+        Some(parent) => format!(
+            "CREATE TABLE {} PARTITION OF {}{} {}",
+            qualified(conn, table),
+            parent,
+            body,
+            text(relation_row, "partition_bound").unwrap_or_default()
+        ),
+        None => format!("CREATE TABLE {}{}", qualified(conn, table), body),
+    };
+    if let Some(key) = text(relation_row, "partition_key") {
+        create += &format!(" PARTITION BY {}", key);
+    }
+
+    // indices of keys and constraints come with them, those of partitions
+    // with the index of their parent
+    let query = format!(
+        "SELECT pg_get_indexdef(i.indexrelid) AS definition FROM pg_index i
+        WHERE i.indrelid = {} AND NOT EXISTS (
+            SELECT 1 FROM pg_constraint c
+            WHERE c.conindid = i.indexrelid AND c.conrelid = i.indrelid
+                AND c.contype IN ('p', 'u', 'x')
+        ) AND NOT EXISTS (SELECT 1 FROM pg_inherits h WHERE h.inhrelid = i.indexrelid)
+        ORDER BY i.indexrelid;",
+        relation
+    );
+    let indices = definitions(&raw_query(pool, &query).await?);
+
+    // serial columns own their sequence, identity columns create theirs
+    let query = format!(
+        "SELECT s.relname::text AS name, d.deptype::text AS dependency,
+            a.attname::text AS column_name
+        FROM pg_depend d JOIN pg_class s ON s.oid = d.objid
+        LEFT JOIN pg_attribute a ON a.attrelid = d.refobjid AND a.attnum = d.refobjsubid
+        WHERE d.refobjid = {} AND d.classid = 'pg_class'::regclass
+            AND s.relkind = 'S' AND d.deptype IN ('a', 'i')
+        ORDER BY s.oid;",
+        relation
+    );
+    let mut sequences = vec![];
+    let mut owned = vec![];
+    let mut positions = vec![];
+    for sequence in raw_query(pool, &query).await? {
+        let Some(name) = text(&sequence, "name") else {
+            continue;
+        };
+        let query = format!(
+            "SELECT last_value::text AS last_value, is_called FROM {};",
+            qualified(conn, &name)
+        );
+        let state = raw_query(pool, &query).await?;
+        let Some(state) = state.first() else {
+            continue;
+        };
+        if text(&sequence, "dependency").as_deref() == Some("a") {
+            sequences.push(format!(
+                "CREATE SEQUENCE IF NOT EXISTS {}",
+                qualified(conn, &name)
+            ));
+            if let Some(column) = text(&sequence, "column_name") {
+                owned.push(format!(
+                    "ALTER SEQUENCE {} OWNED BY {}.{}",
+                    qualified(conn, &name),
+                    qualified(conn, table),
+                    Dialect::Postgresql.quote_ident(&column)
+                ));
+            }
+        }
+        positions.push(format!(
+            "SELECT setval({}, {}, {})",
+            literal(&qualified(conn, &name)),
+            text(state, "last_value").unwrap_or_else(|| "1".to_string()),
+            state["is_called"] == Value::Bool(true)
+        ));
+    }
+
+    Ok(TableDdl {
+        sequences,
+        create,
+        owned,
+        comments,
+        indices,
+        foreign_keys,
+        positions,
+        generated,
+        partitioned,
+    })
+}
@@ -0,0 +1,142 @@
+use anyhow::{anyhow, Result};
+use sqlx::{PgConnection, Row};
+use std::str::FromStr;
+
+/// libpq's `target_session_attrs`, deciding which host of a multi-host
+/// connection is used.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TargetSessionAttrs {
+    #[default]
+    Any,
+    ReadWrite,
+    ReadOnly,
+    Primary,
+    Standby,
+    PreferStandby,
+}
+
+impl FromStr for TargetSessionAttrs {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "" | "any" => Ok(TargetSessionAttrs::Any),
+            "read-write" => Ok(TargetSessionAttrs::ReadWrite),
+            "read-only" => Ok(TargetSessionAttrs::ReadOnly),
+            "primary" => Ok(TargetSessionAttrs::Primary),
+            "standby" => Ok(TargetSessionAttrs::Standby),
+            "prefer-standby" => Ok(TargetSessionAttrs::PreferStandby),
+            s => Err(anyhow!("Invalid target_session_attrs: {}", s)),
+        }
+    }
+}
+
+impl TargetSessionAttrs {
+    /// Whether a host can stop satisfying the attribute, e.g. when a primary is
+    /// demoted. `PreferStandby` falls back to any host so it never rules one out.
+    pub fn is_strict(&self) -> bool {
+        !matches!(
+            self,
+            TargetSessionAttrs::Any | TargetSessionAttrs::PreferStandby
+        )
+    }
+
+    /// Checks whether the server behind an open connection satisfies the attribute.
+    /// `PreferStandby` only accepts standbys, callers fall back to `Any`.
+    pub async fn accepts(&self, conn: &mut PgConnection) -> Result<bool> {
+        Ok(match self {
+            TargetSessionAttrs::Any => true,
+            TargetSessionAttrs::ReadWrite => !read_only(conn).await?,
+            TargetSessionAttrs::ReadOnly => read_only(conn).await?,
+            TargetSessionAttrs::Primary => !in_recovery(conn).await?,
+            TargetSessionAttrs::Standby | TargetSessionAttrs::PreferStandby => {
+                in_recovery(conn).await?
+            }
+        })
+    }
+}
+
+async fn read_only(conn: &mut PgConnection) -> Result<bool> {
+    let row = sqlx::query("SHOW transaction_read_only")
+        .fetch_one(conn)
+        .await?;
+    Ok(row.try_get::<String, _>(0)? == "on")
+}
+
+async fn in_recovery(conn: &mut PgConnection) -> Result<bool> {
+    let row = sqlx::query("SELECT pg_is_in_recovery()")
+        .fetch_one(conn)
+        .await?;
+    Ok(row.try_get(0)?)
+}
+
+/// Parses a libpq style host list, e.g. `pg1:5432,pg2,[::1]:5433`. Hosts without a
+/// port use `default_port`, an IPv6 address only takes a port in brackets.
+pub fn parse(hosts: &str, default_port: u16) -> Result<Vec<(String, u16)>> {
+    let hosts = hosts
+        .split(',')
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(|entry| {
+            let (host, port) = match entry.strip_prefix('[') {
+                Some(ipv6) => {
+                    let (host, port) = ipv6
+                        .split_once(']')
+                        .ok_or_else(|| anyhow!("Unterminated IPv6 host: {}", entry))?;
+                    (host, port.strip_prefix(':'))
+                }
+                None if entry.matches(':').count() > 1 => (entry, None),
+                None => match entry.split_once(':') {
+                    Some((host, port)) => (host, Some(port)),
+                    None => (entry, None),
+                },
+            };
+            let port = match port {
+                Some(port) => port
+                    .parse::<u16>()
+                    .map_err(|_| anyhow!("Port should be a valid number, got '{}'", port))?,
+                None => default_port,
+            };
+            Ok((host.to_string(), port))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if hosts.is_empty() {
+        return Err(anyhow!("No host given"));
+    }
+    Ok(hosts)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::{parse, TargetSessionAttrs};
+
+    #[test]
+    fn test_parse_hosts() -> Result<()> {
+        assert_eq!(parse("db", 5432)?, vec![("db".to_string(), 5432)]);
+        assert_eq!(
+            parse("pg1:5433, pg2,[::1]:6432", 5432)?,
+            vec![
+                ("pg1".to_string(), 5433),
+                ("pg2".to_string(), 5432),
+                ("::1".to_string(), 6432)
+            ]
+        );
+        assert_eq!(
+            parse("::1,fe80::1", 5432)?,
+            vec![("::1".to_string(), 5432), ("fe80::1".to_string(), 5432)]
+        );
+        assert!(parse("pg1:port", 5432).is_err());
+        assert!(parse("[::1:5432", 5432).is_err());
+        assert!(parse(" , ", 5432).is_err());
+        assert_eq!(
+            "read-write".parse::<TargetSessionAttrs>()?,
+            TargetSessionAttrs::ReadWrite
+        );
+        assert!("master".parse::<TargetSessionAttrs>().is_err());
+        assert!(TargetSessionAttrs::Primary.is_strict());
+        assert!(!TargetSessionAttrs::PreferStandby.is_strict());
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::Result;
+use serde_json::Value;
+use sqlx::PgPool;
+
+use crate::engine::types::{config::Dialect, result::ResultColumn, value};
+
+/// Escapes text for the text format of `COPY`, where backslash starts an
+/// escape and tabs and new lines separate fields and rows.
+fn escape(s: &str, out: &mut String) {
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\t' => out.push_str("\\t"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// An array literal such as `{1,NULL,"a b"}` from a JSON array.
+fn array_literal(values: &[Value]) -> String {
+    let elements: Vec<String> = values
+        .iter()
+        .map(|v| match v {
+            Value::Null => "NULL".to_string(),
+            Value::Array(values) => array_literal(values),
+            Value::Bool(b) => if *b { "t" } else { "f" }.to_string(),
+            Value::Number(n) => n.to_string(),
+            v => {
+                let text = match v {
+                    Value::String(s) => s.clone(),
+                    v => v.to_string(),
+                };
+                format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+        })
+        .collect();
+    format!("{{{}}}", elements.join(","))
+}
+
+/// One row in the text format of `COPY`, ending with a new line.
+pub fn copy_row(columns: &[ResultColumn], row: &[Value]) -> String {
+    let mut out = String::new();
+    for (i, (column, v)) in columns.iter().zip(row).enumerate() {
+        if i > 0 {
+            out.push('\t');
+        }
+        if let Some(hex) = value::binary_hex(column.kind, v) {
+            out.push_str("\\\\x");
+            out.push_str(hex);
+            continue;
+        }
+        let is_array = column.type_name.starts_with('_') || column.type_name.ends_with("[]");
+        match v {
+            Value::Null => out.push_str("\\N"),
+            Value::Bool(b) => out.push(if *b { 't' } else { 'f' }),
+            Value::Number(n) => out.push_str(&n.to_string()),
+            Value::String(s) => escape(s, &mut out),
+            Value::Array(values) if is_array => escape(&array_literal(values), &mut out),
+            v => escape(&v.to_string(), &mut out),
+        }
+    }
+    out.push('\n');
+    out
+}
+
+/// Loads rows with `COPY FROM STDIN`, all of them or none.
+pub async fn copy_in(
+    pool: &PgPool,
+    table: &str,
+    columns: &[ResultColumn],
+    rows: &[Vec<Value>],
+) -> Result<u64> {
+    let names: Vec<String> = columns
+        .iter()
+        .map(|c| Dialect::Postgresql.quote_ident(&c.name))
+        .collect();
+    let statement = format!(
+        "COPY {} ({}) FROM STDIN",
+        Dialect::Postgresql.quote_table(table),
+        names.join(", ")
+    );
+    let data: String = rows.iter().map(|row| copy_row(columns, row)).collect();
+    // the pool's copy methods are not exported by sqlx 0.7, a connection's are
+    let mut conn = pool.acquire().await?;
+    let mut copy = conn.copy_in_raw(&statement).await?;
+    if let Err(e) = copy.send(data.into_bytes()).await {
+        copy.abort(e.to_string()).await?;
+        return Err(e.into());
+    }
+    Ok(copy.finish().await?)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::copy_row;
+    use crate::engine::types::result::ResultColumn;
+
+    #[test]
+    fn test_copy_row() {
+        let columns = vec![
+            ResultColumn::new("id", "int8", None),
+            ResultColumn::new("note", "text", None),
+            ResultColumn::new("tags", "_text", None),
+            ResultColumn::new("data", "bytea", None),
+            ResultColumn::new("doc", "jsonb", None),
+        ];
+        let row = vec![
+            json!("1"),
+            json!("a\tb\\c\nd"),
+            json!(["x", null, "say \"hi\""]),
+            json!("0x00ff"),
+            json!({"k": [1]}),
+        ];
+        assert_eq!(
+            copy_row(&columns, &row),
+            "1\ta\\tb\\\\c\\nd\t{\"x\",NULL,\"say \\\\\"hi\\\\\"\"}\t\\\\x00ff\t{\"k\":[1]}\n"
+        );
+        assert_eq!(
+            copy_row(&columns[..2], &[json!(null), json!(true)]),
+            "\\N\tt\n"
+        );
+    }
+}
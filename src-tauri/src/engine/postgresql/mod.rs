@@ -1,4 +1,8 @@
+pub mod binary;
+pub mod ddl;
+pub mod hosts;
+pub mod load;
 pub mod query;
+pub mod search_path;
 pub mod tables;
 pub mod sql_to_json;
-
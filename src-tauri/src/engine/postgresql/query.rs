@@ -1,11 +1,13 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use serde_json::Value;
-use sqlx::PgPool;
+use sqlx::postgres::PgConnection;
+use sqlx::{Connection, PgPool};
+use tokio_util::sync::CancellationToken;
 
 use crate::database::QueryType;
-use crate::engine::types::result::ResultSet;
+use crate::engine::types::result::{describe_columns, ResultSet};
 
-use super::sql_to_json::row_to_json;
+use super::sql_to_json::{row_to_array, row_to_json};
 
 pub async fn raw_query(pool: &PgPool, query: &str) -> Result<Vec<Value>> {
     let rows = sqlx::query(query)
@@ -22,17 +24,19 @@ pub async fn execute_query(pool: &PgPool, query: &str, t: QueryType) -> Result<R
         .as_millis() as u64;
     match t {
         QueryType::Select | QueryType::Show => {
-            let rows = sqlx::query(query)
-                .map(row_to_json)
-                .fetch_all(pool)
-                .await?;
+            let rows = sqlx::query(query).fetch_all(pool).await?;
+            let end_time = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("Time went backwards")
+                .as_millis() as u64;
+            let result_columns = describe_columns(pool, query, rows.first()).await;
+            let rows = rows.iter().map(row_to_array).collect();
             Ok(ResultSet {
                 start_time,
-                end_time: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("Time went backwards")
-                    .as_millis() as u64,
+                end_time,
                 affected_rows: 0,
+                written_bytes: None,
+                result_columns,
                 rows,
                 table: None,
             })
@@ -48,6 +52,8 @@ pub async fn execute_query(pool: &PgPool, query: &str, t: QueryType) -> Result<R
                 start_time,
                 end_time,
                 affected_rows,
+                written_bytes: None,
+                result_columns: vec![],
                 rows: vec![],
                 table: None,
             })
@@ -69,3 +75,42 @@ pub async fn execute_tx(pool: &PgPool, queries: Vec<&str>) -> Result<()> {
     transaction.commit().await?;
     Ok(())
 }
+
+/// Runs the statements of a script in order and in one transaction, calling
+/// `progress` with the number run so far. Settings the script makes, such as
+/// `check_function_bodies`, outlive the transaction, so the connection is
+/// closed afterwards rather than returned to the pool with them. Cancelling
+/// rolls the transaction back and closes the connection too.
+pub async fn execute_script(
+    pool: &PgPool,
+    statements: &[String],
+    progress: impl Fn(usize),
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut conn = pool.acquire().await?.detach();
+    let result = run_script(&mut conn, statements, progress, token).await;
+    if let Err(e) = conn.close().await {
+        log::warn!("Failed to close the script connection: {}", e);
+    }
+    result
+}
+
+async fn run_script(
+    conn: &mut PgConnection,
+    statements: &[String],
+    progress: impl Fn(usize),
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut transaction = conn.begin().await?;
+    for (i, statement) in statements.iter().enumerate() {
+        tokio::select! {
+            _ = token.cancelled() => bail!("The script was cancelled"),
+            res = sqlx::raw_sql(statement).execute(&mut *transaction) => {
+                res.map_err(|e| anyhow!("Statement {} failed: {}", i + 1, e))?;
+            }
+        }
+        progress(i + 1);
+    }
+    transaction.commit().await?;
+    Ok(())
+}
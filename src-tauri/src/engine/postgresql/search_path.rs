@@ -0,0 +1,70 @@
+/// Splits a search path like `sales, "Reporting", public` into schema names,
+/// honouring double quoted names that may contain commas.
+pub fn parse(search_path: &str) -> Vec<String> {
+    let mut schemas = vec![];
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut chars = search_path.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                schemas.push(current.trim().to_string());
+                current.clear();
+            }
+            c => current.push(c),
+        }
+    }
+    schemas.push(current.trim().to_string());
+    schemas.retain(|s| !s.is_empty());
+    schemas
+}
+
+/// The first schema of the search path, tables and routines are listed from it.
+/// `$user` is skipped, as the schema named after the user rarely exists.
+pub fn primary(search_path: &str) -> String {
+    parse(search_path)
+        .into_iter()
+        .find(|s| s != "$user")
+        .unwrap_or("public".to_string())
+}
+
+/// Formats the schemas as a `search_path` value for the startup `options` parameter,
+/// where spaces and backslashes have to be escaped. `$user` stays unquoted so it
+/// still stands for the schema named after the session user.
+pub fn to_option(schemas: &[String]) -> String {
+    schemas
+        .iter()
+        .map(|s| match s.as_str() {
+            "$user" => s.clone(),
+            s => format!("\"{}\"", s.replace('"', "\"\"")),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+        .replace('\\', "\\\\")
+        .replace(' ', "\\ ")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, primary, to_option};
+
+    #[test]
+    fn test_search_path() {
+        assert_eq!(parse("sales, public"), vec!["sales", "public"]);
+        assert_eq!(parse("\"a, \"\"b\"\"\",c"), vec!["a, \"b\"", "c"]);
+        assert_eq!(primary(" "), "public");
+        assert_eq!(primary("sales,public"), "sales");
+        assert_eq!(primary("\"$user\", public"), "public");
+        assert_eq!(primary("$user"), "public");
+        assert_eq!(
+            to_option(&parse("My Schema, public")),
+            "\"My\\ Schema\",\"public\""
+        );
+        assert_eq!(to_option(&parse("$user, public")), "$user,\"public\"");
+    }
+}
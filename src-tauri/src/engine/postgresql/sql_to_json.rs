@@ -2,6 +2,9 @@ use serde_json::{json, Value};
 use sqlx::postgres::PgRow;
 use sqlx::{Column, Decode, Row, TypeInfo, ValueRef};
 
+use crate::engine::postgresql::binary;
+use crate::engine::types::value;
+
 pub fn row_to_json(row: PgRow) -> Value {
     let mut object = json!({});
     for column in row.columns().iter() {
@@ -12,6 +15,13 @@ pub fn row_to_json(row: PgRow) -> Value {
     object
 }
 
+pub fn row_to_array(row: &PgRow) -> Vec<Value> {
+    row.columns()
+        .iter()
+        .map(|column| sql_to_json(row, column))
+        .collect()
+}
+
 pub fn sql_to_json(row: &PgRow, col: &sqlx::postgres::PgColumn) -> Value {
     let raw_value_result = row.try_get_raw(col.ordinal());
     match raw_value_result {
@@ -48,8 +58,8 @@ fn sql_nonnull_to_json<'r>(
             .unwrap_or_default()
             .into(),
         "INT8" => <i64 as Decode<sqlx::Postgres>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
+            .map(value::bigint)
+            .unwrap_or(Value::Null),
         "FLOAT4" => {
             let v = <f32 as Decode<sqlx::Postgres>>::decode(raw_value).unwrap_or(f32::NAN);
             serde_json::Number::from_f64(v as f64)
@@ -62,12 +72,6 @@ fn sql_nonnull_to_json<'r>(
                 .map(Value::Number)
                 .unwrap_or(Value::Null)
         }
-        "NUMERIC" => <String as Decode<sqlx::Postgres>>::decode(raw_value)
-            .ok()
-            .and_then(|s| s.parse::<f64>().ok())
-            .and_then(serde_json::Number::from_f64)
-            .map(Value::Number)
-            .unwrap_or(Value::Null),
         "TEXT" | "VARCHAR" | "CHAR" | "NAME" | "UNKNOWN" | "BPCHAR" | "CITEXT" => {
             <String as Decode<sqlx::Postgres>>::decode(raw_value)
                 .unwrap_or_default()
@@ -78,14 +82,6 @@ fn sql_nonnull_to_json<'r>(
         "UUID" => <uuid::Uuid as Decode<sqlx::Postgres>>::decode(raw_value)
             .map(|u| Value::String(u.to_string()))
             .unwrap_or(Value::Null),
-        "TIMESTAMP" | "TIMESTAMPTZ" | "DATE" | "TIME" => {
-            <String as Decode<sqlx::Postgres>>::decode(raw_value)
-                .unwrap_or_default()
-                .into()
-        }
-        "INET" => <String as Decode<sqlx::Postgres>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
         "OID" => {
             let v = <i32 as Decode<sqlx::Postgres>>::decode(raw_value).unwrap_or_default();
             Value::Number(serde_json::Number::from(v))
@@ -93,7 +89,7 @@ fn sql_nonnull_to_json<'r>(
         "BOOL[]" => decode_array::<bool>(get_ref, |v| Value::Bool(v)),
         "INT2[]" => decode_array::<i16>(get_ref, |v| v.into()),
         "INT4[]" => decode_array::<i32>(get_ref, |v| v.into()),
-        "INT8[]" => decode_array::<i64>(get_ref, |v| v.into()),
+        "INT8[]" => decode_array::<i64>(get_ref, value::bigint),
         "FLOAT4[]" => decode_array::<f32>(get_ref, |v| {
             serde_json::Number::from_f64(v as f64)
                 .map(Value::Number)
@@ -107,10 +103,7 @@ fn sql_nonnull_to_json<'r>(
         "TEXT[]" | "VARCHAR[]" => decode_array::<String>(get_ref, |v| Value::String(v)),
         "UUID[]" => decode_array::<uuid::Uuid>(get_ref, |v| Value::String(v.to_string())),
         "JSON[]" | "JSONB[]" => decode_array::<Value>(get_ref, |v| v),
-        // Default: try string decode, fallback to null
-        _ => <String as Decode<sqlx::Postgres>>::decode(raw_value)
-            .map(Value::String)
-            .unwrap_or(Value::Null),
+        _ => binary::decode(col.type_info(), raw_value),
     }
 }
 
@@ -1,9 +1,9 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{PgPool, Row};
 use futures::try_join;
 use serde_json::{json, Value};
 
-use crate::engine::types::connection::InitiatedConnection;
+use crate::engine::types::connection::{ConnectionInfo, InitiatedConnection, TlsInfo};
 
 use super::query::raw_query;
 
@@ -184,3 +184,23 @@ pub async fn get_views(conn: &InitiatedConnection, pool: &PgPool) -> Result<Vec<
     );
     raw_query(pool, &query).await
 }
+
+pub async fn get_connection_info(pool: &PgPool) -> Result<ConnectionInfo> {
+    let row = sqlx::query(
+        "SELECT current_setting('server_version') AS server_version, s.ssl, s.version, s.cipher
+        FROM pg_stat_ssl s WHERE s.pid = pg_backend_pid()",
+    )
+    .fetch_one(pool)
+    .await?;
+    let tls = match row.try_get::<bool, _>("ssl")? {
+        true => Some(TlsInfo {
+            version: row.try_get::<Option<String>, _>("version")?.unwrap_or_default(),
+            cipher: row.try_get::<Option<String>, _>("cipher")?.unwrap_or_default(),
+        }),
+        false => None,
+    };
+    Ok(ConnectionInfo {
+        server_version: row.try_get("server_version")?,
+        tls,
+    })
+}
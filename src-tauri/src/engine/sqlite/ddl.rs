@@ -0,0 +1,93 @@
+use anyhow::{anyhow, bail, Result};
+use sqlx::SqlitePool;
+
+use crate::engine::types::ddl::{text, ObjectType, SchemaObject, TableDdl};
+
+use super::query::raw_query;
+
+pub async fn get_objects(pool: &SqlitePool) -> Result<Vec<SchemaObject>> {
+    let query = "SELECT type, name, tbl_name FROM sqlite_master
+        WHERE type IN ('table', 'view', 'trigger') AND name NOT LIKE 'sqlite_%'
+        ORDER BY rowid;";
+    let objects = raw_query(pool, query)
+        .await?
+        .iter()
+        .filter_map(|row| {
+            let (object_type, table) = match text(row, "type")?.as_str() {
+                "table" => (ObjectType::Table, None),
+                "view" => (ObjectType::View, None),
+                _ => (ObjectType::Trigger, text(row, "tbl_name")),
+            };
+            Some(SchemaObject::new(
+                object_type,
+                &text(row, "name")?,
+                table.as_deref(),
+            ))
+        })
+        .collect();
+    Ok(objects)
+}
+
+/// The statement as it was written, which SQLite keeps in `sqlite_master`.
+pub async fn get_create_statement(
+    pool: &SqlitePool,
+    object_type: ObjectType,
+    name: &str,
+) -> Result<String> {
+    let object_type = match object_type {
+        ObjectType::Table => "table",
+        ObjectType::View => "view",
+        ObjectType::Index => "index",
+        ObjectType::Trigger => "trigger",
+        ObjectType::MaterializedView => bail!("SQLite has no materialized views"),
+        ObjectType::Extension | ObjectType::Type => bail!("SQLite has no extensions or types"),
+        ObjectType::Function | ObjectType::Procedure => {
+            bail!("SQLite has no stored functions or procedures")
+        }
+    };
+    let query = format!(
+        "SELECT sql FROM sqlite_master WHERE type = '{}' AND name = '{}';",
+        object_type,
+        name.replace('\'', "''")
+    );
+    let row = raw_query(pool, &query)
+        .await?
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow!("{} was not found", name))?;
+    // indices SQLite creates for keys and unique constraints have no sql
+    text(&row, "sql").ok_or_else(|| anyhow!("{} is created by a constraint of its table", name))
+}
+
+/// Foreign keys are part of `CREATE TABLE`, which SQLite accepts before the
+/// tables they reference exist.
+pub async fn get_table_ddl(pool: &SqlitePool, table: &str) -> Result<TableDdl> {
+    let create = get_create_statement(pool, ObjectType::Table, table).await?;
+    // the indices of constraints come with `CREATE TABLE`
+    let query = format!(
+        "SELECT sql FROM sqlite_master
+        WHERE type = 'index' AND tbl_name = '{}' AND sql IS NOT NULL ORDER BY rowid;",
+        table.replace('\'', "''")
+    );
+    let indices = raw_query(pool, &query)
+        .await?
+        .iter()
+        .filter_map(|row| text(row, "sql"))
+        .collect();
+    // generated columns are hidden as 2 (virtual) or 3 (stored)
+    let query = format!(
+        "SELECT name FROM pragma_table_xinfo('{}') WHERE hidden IN (2, 3);",
+        table.replace('\'', "''")
+    );
+    let generated = raw_query(pool, &query)
+        .await?
+        .iter()
+        .filter_map(|row| text(row, "name"))
+        .collect();
+    Ok(TableDdl {
+        create,
+        indices,
+        generated,
+        ..Default::default()
+    })
+}
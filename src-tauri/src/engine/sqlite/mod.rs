@@ -1,3 +1,4 @@
+pub mod ddl;
 pub mod query;
 pub mod tables;
 pub mod sql_to_json;
@@ -1,11 +1,12 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, bail, Result};
 use serde_json::Value;
 use sqlx::SqlitePool;
+use tokio_util::sync::CancellationToken;
 
 use crate::database::QueryType;
-use crate::engine::types::result::ResultSet;
+use crate::engine::types::result::{describe_columns, ResultSet};
 
-use super::sql_to_json::row_to_json;
+use super::sql_to_json::{row_to_array, row_to_json};
 
 pub async fn raw_query(pool: &SqlitePool, query: &str) -> Result<Vec<Value>> {
     let rows = sqlx::query(query)
@@ -22,17 +23,19 @@ pub async fn execute_query(pool: &SqlitePool, query: &str, t: QueryType) -> Resu
         .as_millis() as u64;
     match t {
         QueryType::Select | QueryType::Show => {
-            let rows = sqlx::query(query)
-                .map(row_to_json)
-                .fetch_all(pool)
-                .await?;
+            let rows = sqlx::query(query).fetch_all(pool).await?;
+            let end_time = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("Time went backwards")
+                .as_millis() as u64;
+            let result_columns = describe_columns(pool, query, rows.first()).await;
+            let rows = rows.iter().map(row_to_array).collect();
             Ok(ResultSet {
                 start_time,
-                end_time: std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .expect("Time went backwards")
-                    .as_millis() as u64,
+                end_time,
                 affected_rows: 0,
+                written_bytes: None,
+                result_columns,
                 rows,
                 table: None,
             })
@@ -48,6 +51,8 @@ pub async fn execute_query(pool: &SqlitePool, query: &str, t: QueryType) -> Resu
                 start_time,
                 end_time,
                 affected_rows,
+                written_bytes: None,
+                result_columns: vec![],
                 rows: vec![],
                 table: None,
             })
@@ -69,3 +74,28 @@ pub async fn execute_tx(pool: &SqlitePool, queries: Vec<&str>) -> Result<()> {
     transaction.commit().await?;
     Ok(())
 }
+
+/// Runs the statements of a script in order and in one transaction, calling
+/// `progress` with the number run so far. Cancelling rolls the transaction back.
+pub async fn execute_script(
+    pool: &SqlitePool,
+    statements: &[String],
+    progress: impl Fn(usize),
+    token: &CancellationToken,
+) -> Result<()> {
+    let mut transaction = pool.begin().await?;
+    for (i, statement) in statements.iter().enumerate() {
+        tokio::select! {
+            _ = token.cancelled() => {
+                transaction.rollback().await?;
+                bail!("The script was cancelled");
+            }
+            res = sqlx::raw_sql(statement).execute(&mut *transaction) => {
+                res.map_err(|e| anyhow!("Statement {} failed: {}", i + 1, e))?;
+            }
+        }
+        progress(i + 1);
+    }
+    transaction.commit().await?;
+    Ok(())
+}
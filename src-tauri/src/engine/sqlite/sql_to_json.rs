@@ -1,7 +1,13 @@
+use chrono::DateTime;
 use serde_json::{json, Value};
 use sqlx::sqlite::SqliteRow;
 use sqlx::{Column, Decode, Row, TypeInfo, ValueRef};
 
+use crate::engine::types::value;
+
+/// Integers beyond this lose precision as JavaScript numbers.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
 pub fn row_to_json(row: SqliteRow) -> Value {
     let mut object = json!({});
     for column in row.columns().iter() {
@@ -12,12 +18,19 @@ pub fn row_to_json(row: SqliteRow) -> Value {
     object
 }
 
+pub fn row_to_array(row: &SqliteRow) -> Vec<Value> {
+    row.columns()
+        .iter()
+        .map(|column| sql_to_json(row, column))
+        .collect()
+}
+
 pub fn sql_to_json(row: &SqliteRow, col: &sqlx::sqlite::SqliteColumn) -> Value {
     let raw_value_result = row.try_get_raw(col.ordinal());
     match raw_value_result {
         Ok(raw_value) if !raw_value.is_null() => {
             let mut raw_value = Some(raw_value);
-            let decoded = sql_nonnull_to_json(|| {
+            let decoded = sql_nonnull_to_json(col.type_info().name(), || {
                 raw_value
                     .take()
                     .unwrap_or_else(|| row.try_get_raw(col.ordinal()).unwrap())
@@ -32,25 +45,95 @@ pub fn sql_to_json(row: &SqliteRow, col: &sqlx::sqlite::SqliteColumn) -> Value {
     }
 }
 
+/// Days between the start of the Julian calendar and the Unix epoch, SQLite keeps
+/// dates stored as REAL as Julian day numbers.
+const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+
+/// Dates and times stored as Unix seconds or Julian days, formatted as SQLite's
+/// `date()` and `datetime()` would.
+fn temporal(declared: &str, seconds: f64) -> Option<Value> {
+    let at = DateTime::from_timestamp_millis((seconds * 1000.0).round() as i64)?.naive_utc();
+    let text = match declared {
+        "DATE" => at.date().to_string(),
+        "TIME" => at.time().to_string(),
+        _ => at.to_string(),
+    };
+    Some(Value::String(text))
+}
+
+/// Text holding a JSON document or array, SQLite has no JSON type and sqlx doesn't
+/// report declared types it doesn't know. Only text that looks like JSON from its
+/// first character is parsed.
+fn json_text(text: &str) -> Option<Value> {
+    let trimmed = text.trim_start();
+    if !trimmed.starts_with(['{', '[']) {
+        return None;
+    }
+    serde_json::from_str(trimmed).ok()
+}
+
+/// Values are decoded by their storage class, the declared type of the column only
+/// refines them, e.g. integers in a BOOLEAN column or Unix seconds in a DATE one.
 fn sql_nonnull_to_json<'r>(
+    declared: &str,
     mut get_ref: impl FnMut() -> sqlx::sqlite::SqliteValueRef<'r>,
 ) -> Value {
     let raw_value = get_ref();
     match raw_value.type_info().name() {
-        "INTEGER" => <i64 as Decode<sqlx::Sqlite>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
-        "REAL" => <f64 as Decode<sqlx::Sqlite>>::decode(raw_value)
-            .unwrap_or(f64::NAN)
-            .into(),
-        "BLOB" => {
-            let bytes = <Vec<u8> as Decode<sqlx::Sqlite>>::decode(raw_value)
-                .unwrap_or_default();
-            Value::Array(bytes.into_iter().map(|b| Value::Number(b.into())).collect())
-        }
+        "INTEGER" => match <i64 as Decode<sqlx::Sqlite>>::decode(raw_value) {
+            Ok(v) if declared == "BOOLEAN" && (v == 0 || v == 1) => Value::Bool(v == 1),
+            Ok(v) if is_temporal(declared) => temporal(declared, v as f64).unwrap_or(v.into()),
+            Ok(v) if !(-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&v) => value::bigint(v),
+            Ok(v) => v.into(),
+            Err(_) => Value::Null,
+        },
+        // SQLite stores NaN as NULL, but infinities survive and have no JSON number
+        "REAL" => match <f64 as Decode<sqlx::Sqlite>>::decode(raw_value) {
+            Ok(v) if v.is_finite() && is_temporal(declared) => {
+                temporal(declared, (v - UNIX_EPOCH_JULIAN_DAY) * 86_400.0).unwrap_or(v.into())
+            }
+            Ok(v) if v.is_finite() => v.into(),
+            Ok(v) if v.is_nan() => Value::String("NaN".to_string()),
+            Ok(v) if v > 0.0 => Value::String("Infinity".to_string()),
+            Ok(_) => Value::String("-Infinity".to_string()),
+            Err(_) => Value::Null,
+        },
+        "BLOB" => <&[u8] as Decode<sqlx::Sqlite>>::decode(raw_value)
+            .map(value::binary)
+            .unwrap_or(Value::Null),
         // TEXT and everything else: decode as string
-        _ => <String as Decode<sqlx::Sqlite>>::decode(raw_value)
-            .unwrap_or_default()
-            .into(),
+        _ => {
+            let text = <String as Decode<sqlx::Sqlite>>::decode(raw_value).unwrap_or_default();
+            json_text(&text).unwrap_or(Value::String(text))
+        }
+    }
+}
+
+fn is_temporal(declared: &str) -> bool {
+    matches!(declared, "DATE" | "DATETIME" | "TIME")
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{json_text, temporal, UNIX_EPOCH_JULIAN_DAY};
+
+    #[test]
+    fn test_declared_types() {
+        assert_eq!(temporal("DATE", 86_400.0), Some(json!("1970-01-02")));
+        assert_eq!(
+            temporal("DATETIME", 1.5),
+            Some(json!("1970-01-01 00:00:01.500"))
+        );
+        // julianday('2024-02-29 12:00:00')
+        let seconds = (2_460_370.0 - UNIX_EPOCH_JULIAN_DAY) * 86_400.0;
+        assert_eq!(
+            temporal("DATETIME", seconds),
+            Some(json!("2024-02-29 12:00:00"))
+        );
+        assert_eq!(json_text(r#" {"a": [1]}"#), Some(json!({"a": [1]})));
+        assert_eq!(json_text("[not json"), None);
+        assert_eq!(json_text("12"), None);
     }
 }
@@ -1,8 +1,10 @@
 use anyhow::Result;
-use sqlx::SqlitePool;
+use sqlx::{Row, SqlitePool};
 use futures::try_join;
 use serde_json::{json, Value};
 
+use crate::engine::types::connection::ConnectionInfo;
+
 use super::query::raw_query;
 
 pub async fn get_table_structure(pool: &SqlitePool, table: String) -> Result<Value> {
@@ -109,3 +111,13 @@ pub async fn get_views(pool: &SqlitePool) -> Result<Vec<Value>> {
     let query = "SELECT * FROM sqlite_master WHERE type='view'";
     raw_query(pool, query).await
 }
+
+pub async fn get_connection_info(pool: &SqlitePool) -> Result<ConnectionInfo> {
+    let row = sqlx::query("SELECT sqlite_version()")
+        .fetch_one(pool)
+        .await?;
+    Ok(ConnectionInfo {
+        server_version: row.try_get(0)?,
+        tls: None,
+    })
+}
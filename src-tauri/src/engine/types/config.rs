@@ -35,6 +35,35 @@ impl Dialect {
             Dialect::ClickHouse => "clickhouse",
         }
     }
+
+    /// Quotes an identifier, doubling any quote characters inside it.
+    pub fn quote_ident(&self, name: &str) -> String {
+        match self {
+            Dialect::Mysql | Dialect::MariaDB | Dialect::ClickHouse => {
+                format!("`{}`", name.replace('`', "``"))
+            }
+            Dialect::Postgresql | Dialect::Sqlite => format!("\"{}\"", name.replace('"', "\"\"")),
+        }
+    }
+
+    /// Quotes a table name, which may be qualified by its schema as `schema.table`.
+    pub fn quote_table(&self, name: &str) -> String {
+        match name.split_once('.') {
+            Some((schema, table)) => {
+                format!("{}.{}", self.quote_ident(schema), self.quote_ident(table))
+            }
+            None => self.quote_ident(name),
+        }
+    }
+
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Dialect::Mysql | Dialect::MariaDB => 3306,
+            Dialect::Postgresql => 5432,
+            Dialect::Sqlite => 0,
+            Dialect::ClickHouse => 8123,
+        }
+    }
 }
 
 impl fmt::Display for Dialect {
@@ -99,9 +128,81 @@ impl FromStr for Mode {
 
 pub type Credentials = HashMap<String, String>;
 
+/// Credential keys holding secrets rather than connection settings.
+pub const SECRET_KEYS: [&str; 2] = ["password", "client_key"];
+
+/// Pool sizing and session initialization keys shared by the sqlx backed dialects.
+pub const POOL_KEYS: [&str; 6] = [
+    "pool_min",
+    "pool_max",
+    "pool_idle_timeout_secs",
+    "pool_max_lifetime_secs",
+    "pool_acquire_timeout_secs",
+    "init_sql",
+];
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+#[serde(default)]
 pub struct Metadata {
     pub sensitive: bool,
+    pub read_only: bool,
+    /// Lets `${cmd:...}` secret references run, which the user turns on per connection
+    pub trust_commands: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub enum Environment {
+    #[default]
+    None,
+    Development,
+    Testing,
+    Staging,
+    Production,
+}
+
+impl Environment {
+    /// Applies the policies a connection gets when it enters this environment.
+    pub fn apply_defaults(&self, metadata: &mut Metadata) {
+        if *self == Environment::Production {
+            metadata.sensitive = true;
+            metadata.read_only = true;
+        }
+    }
+}
+
+impl fmt::Display for Environment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Environment::None => write!(f, "None"),
+            Environment::Development => write!(f, "Development"),
+            Environment::Testing => write!(f, "Testing"),
+            Environment::Staging => write!(f, "Staging"),
+            Environment::Production => write!(f, "Production"),
+        }
+    }
+}
+
+impl FromStr for Environment {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "None" => Ok(Environment::None),
+            "Development" => Ok(Environment::Development),
+            "Testing" => Ok(Environment::Testing),
+            "Staging" => Ok(Environment::Staging),
+            "Production" => Ok(Environment::Production),
+            _ => Err(anyhow::anyhow!("Invalid environment: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct ConnectionFilter {
+    pub folder: Option<String>,
+    pub tag: Option<String>,
+    pub environment: Option<Environment>,
+    pub search: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -114,9 +215,77 @@ pub struct ConnectionConfig {
     pub name: String,
     pub color: String,
     pub metadata: Metadata,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub environment: Environment,
+    #[serde(default)]
+    pub sort_order: i64,
+    #[serde(default)]
+    pub last_used_at: Option<i64>,
 }
 
 impl ConnectionConfig {
+    /// Sets folder, tags and environment. Entering the production environment
+    /// turns on its default policies, which can be relaxed afterwards.
+    pub fn organize(
+        mut self,
+        folder: Option<String>,
+        tags: Vec<String>,
+        environment: Environment,
+        previous: Option<&Environment>,
+    ) -> Self {
+        if previous != Some(&environment) {
+            environment.apply_defaults(&mut self.metadata);
+        }
+        self.folder = folder.filter(|f| !f.trim().is_empty());
+        self.tags = tags;
+        self.tags.retain(|t| !t.trim().is_empty());
+        self.tags.sort();
+        self.tags.dedup();
+        self.environment = environment;
+        self
+    }
+
+    /// Drops passwords and client private keys.
+    pub fn without_secrets(mut self) -> Self {
+        self.credentials.retain(|k, _| !SECRET_KEYS.contains(&k.as_str()));
+        self
+    }
+
+    /// Identifies the database this config points at, regardless of name or id.
+    pub fn endpoint(&self) -> (String, u16, String) {
+        match self.dialect {
+            Dialect::Sqlite => (
+                self.credentials.get("path").cloned().unwrap_or_default(),
+                0,
+                String::new(),
+            ),
+            _ => {
+                let host = self
+                    .credentials
+                    .get("host")
+                    .or(self.credentials.get("socket"))
+                    .cloned()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                let port = self
+                    .credentials
+                    .get("port")
+                    .and_then(|p| p.parse::<u16>().ok())
+                    .unwrap_or(self.dialect.default_port());
+                let db = self
+                    .credentials
+                    .get("db_name")
+                    .cloned()
+                    .unwrap_or_default();
+                (host, port, db)
+            }
+        }
+    }
+
     pub fn new(
         dialect: Dialect,
         mode: Mode,
@@ -134,8 +303,6 @@ impl ConnectionConfig {
         match dialect {
             Dialect::Mysql | Dialect::MariaDB => {
                 let allowed_keys = vec![
-                    "pool_min",
-                    "pool_max",
                     "user",
                     "password",
                     "host",
@@ -161,7 +328,9 @@ impl ConnectionConfig {
                     "ssh_user",
                     "ssh_key",
                 ];
-                credentials.retain(|k, _| allowed_keys.contains(&k.as_str()));
+                credentials.retain(|k, _| {
+                    allowed_keys.contains(&k.as_str()) || POOL_KEYS.contains(&k.as_str())
+                });
                 let schema = credentials
                     .get("db_name")
                     .cloned()
@@ -175,9 +344,19 @@ impl ConnectionConfig {
                     color: color.to_string(),
                     schema,
                     metadata,
+                    folder: None,
+                    tags: vec![],
+                    environment: Environment::None,
+                    sort_order: 0,
+                    last_used_at: None,
                 })
             }
             Dialect::Postgresql => {
+                // the search path lives in the schema column, a comma separated list
+                let schema = credentials
+                    .remove("schema")
+                    .filter(|s| !s.trim().is_empty())
+                    .unwrap_or("public".to_string());
                 let allowed_keys = vec![
                     "user",
                     "password",
@@ -190,9 +369,11 @@ impl ConnectionConfig {
                     "connect_timeout",
                     "keepalives",
                     "keepalives_idle",
+                    // any, read-write, read-only, primary, standby, prefer-standby
                     "target_session_attrs",
                     "transaction_read_write",
-                    "ssl_mode", // disable, prefer, require, if with ca_cert ssl verify mode is peer
+                    "socket",
+                    "ssl_mode", // disable, prefer, require, verify-ca, verify-full
                     "ca_cert",
                     "client_cert",
                     "client_key",
@@ -201,7 +382,9 @@ impl ConnectionConfig {
                     "ssh_user",
                     "ssh_key",
                 ];
-                credentials.retain(|k, _| allowed_keys.contains(&k.as_str()));
+                credentials.retain(|k, _| {
+                    allowed_keys.contains(&k.as_str()) || POOL_KEYS.contains(&k.as_str())
+                });
                 Ok(ConnectionConfig {
                     id: Uuid::new_v4(),
                     dialect,
@@ -209,13 +392,20 @@ impl ConnectionConfig {
                     credentials,
                     name: name.to_string(),
                     color: color.to_string(),
-                    schema: "public".to_string(),
+                    schema,
                     metadata,
+                    folder: None,
+                    tags: vec![],
+                    environment: Environment::None,
+                    sort_order: 0,
+                    last_used_at: None,
                 })
             }
             Dialect::Sqlite => {
                 let available_keys = ["path"];
-                credentials.retain(|k, _| available_keys.contains(&k.as_str()));
+                credentials.retain(|k, _| {
+                    available_keys.contains(&k.as_str()) || POOL_KEYS.contains(&k.as_str())
+                });
                 let schema = credentials.get("path").cloned().unwrap_or("".to_string());
                 Ok(ConnectionConfig {
                     id: Uuid::new_v4(),
@@ -226,6 +416,11 @@ impl ConnectionConfig {
                     color: color.to_string(),
                     schema,
                     metadata,
+                    folder: None,
+                    tags: vec![],
+                    environment: Environment::None,
+                    sort_order: 0,
+                    last_used_at: None,
                 })
             }
             Dialect::ClickHouse => {
@@ -254,6 +449,11 @@ impl ConnectionConfig {
                     color: color.to_string(),
                     schema,
                     metadata,
+                    folder: None,
+                    tags: vec![],
+                    environment: Environment::None,
+                    sort_order: 0,
+                    last_used_at: None,
                 })
             }
         }
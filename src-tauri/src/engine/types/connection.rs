@@ -1,10 +1,28 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 use super::config::{ConnectionConfig, ConnectionPool};
-use super::result::{ResultSet, TableMetadata};
+use super::ddl::{ObjectType, SchemaObject, TableDdl};
+use super::result::{ResultColumn, ResultSet, TableMetadata};
 use crate::database::QueryType;
 use crate::engine::exec;
+use crate::engine::postgresql::search_path;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TlsInfo {
+    pub version: String,
+    pub cipher: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConnectionInfo {
+    pub server_version: String,
+    /// None when the session is not encrypted.
+    pub tls: Option<TlsInfo>,
+}
 
 #[derive(Debug, Clone)]
 pub struct InitiatedConnection {
@@ -18,14 +36,17 @@ impl InitiatedConnection {
         self.schema.clone()
     }
 
+    /// Switches the schema the connection works in. For Postgres this is a search
+    /// path, the pool has to be re-initiated for it to take effect.
     pub fn set_schema(mut self, schema: String) -> Self {
+        self.config.schema = schema.clone();
         self.schema = schema.clone();
         match self.config.dialect {
             super::config::Dialect::Mysql | super::config::Dialect::MariaDB => {
                 self.config.credentials.insert("db_name".to_string(), schema);
             }
             super::config::Dialect::Postgresql => {
-                self.config.credentials.insert("schema".to_string(), schema);
+                self.schema = search_path::primary(&schema);
             }
             super::config::Dialect::Sqlite => {
                 self.config.credentials.insert("path".to_string(), schema);
@@ -89,6 +110,33 @@ impl InitiatedConnection {
         exec::get_views(self).await
     }
 
+    pub async fn get_objects(&self) -> Result<Vec<SchemaObject>> {
+        exec::get_objects(self).await
+    }
+
+    pub async fn get_create_statement(
+        &self,
+        object_type: ObjectType,
+        name: &str,
+    ) -> Result<String> {
+        exec::get_create_statement(self, object_type, name).await
+    }
+
+    pub async fn get_table_ddl(&self, table: &str) -> Result<TableDdl> {
+        exec::get_table_ddl(self, table).await
+    }
+
+    pub async fn get_connection_info(&self) -> Result<ConnectionInfo> {
+        exec::get_connection_info(self).await
+    }
+
+    /// Runs a lightweight round trip and returns its latency.
+    pub async fn ping(&self) -> Result<Duration> {
+        let start = Instant::now();
+        exec::ping(self).await?;
+        Ok(start.elapsed())
+    }
+
     pub async fn execute_query(&self, q: &str, t: QueryType) -> Result<ResultSet> {
         exec::execute_query(self, q, t).await
     }
@@ -96,4 +144,22 @@ impl InitiatedConnection {
     pub async fn execute_tx(&self, queries: Vec<&str>) -> Result<()> {
         exec::execute_tx(self, queries).await
     }
+
+    pub async fn execute_script(
+        &self,
+        statements: &[String],
+        progress: impl Fn(usize),
+        token: &CancellationToken,
+    ) -> Result<()> {
+        exec::execute_script(self, statements, progress, token).await
+    }
+
+    pub async fn load(
+        &self,
+        table: &str,
+        columns: &[ResultColumn],
+        rows: &[Vec<Value>],
+    ) -> Result<u64> {
+        exec::load(self, table, columns, rows).await
+    }
 }
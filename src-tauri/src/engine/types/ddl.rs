@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::result::field;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectType {
+    Table,
+    View,
+    MaterializedView,
+    Index,
+    Trigger,
+    Function,
+    Procedure,
+    /// Postgres extensions, created before anything that may use them
+    Extension,
+    /// Postgres enums, domains and composite types
+    Type,
+}
+
+/// An object of a schema, with the table a trigger belongs to, or whose row type
+/// a Postgres routine uses. Indices are defined with their tables and aren't
+/// listed on their own.
+#[derive(Debug, Clone, Serialize)]
+pub struct SchemaObject {
+    pub object_type: ObjectType,
+    pub name: String,
+    pub table: Option<String>,
+}
+
+impl SchemaObject {
+    pub fn new(object_type: ObjectType, name: &str, table: Option<&str>) -> Self {
+        Self {
+            object_type,
+            name: name.to_string(),
+            table: table.map(str::to_string),
+        }
+    }
+}
+
+/// The statements defining a table, without their semicolons. Indices and
+/// foreign keys that aren't part of `create` are kept apart so that dumps can
+/// add them once every table and its rows are in.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TableDdl {
+    /// Sequences the columns' defaults draw from, created before the table
+    pub sequences: Vec<String>,
+    pub create: String,
+    /// Statements tying sequences to the serial columns that own them
+    pub owned: Vec<String>,
+    /// Comments on the table and its columns, where they aren't in `create`
+    pub comments: Vec<String>,
+    pub indices: Vec<String>,
+    pub foreign_keys: Vec<String>,
+    /// Statements setting the sequences to their current values
+    pub positions: Vec<String>,
+    /// Columns the database computes, which take no inserted values
+    pub generated: Vec<String>,
+    /// Whether the rows are kept in partitions, which are tables of their own
+    pub partitioned: bool,
+}
+
+impl TableDdl {
+    pub fn new(create: String) -> Self {
+        Self {
+            create,
+            ..Default::default()
+        }
+    }
+
+    /// The statements creating the table as one script.
+    pub fn script(&self) -> String {
+        self.sequences
+            .iter()
+            .chain(std::iter::once(&self.create))
+            .chain(&self.owned)
+            .chain(&self.comments)
+            .chain(&self.indices)
+            .chain(&self.foreign_keys)
+            .map(|s| format!("{};\n", s.trim_end().trim_end_matches(';')))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// A text field of a catalog row, whatever the case of its key.
+pub fn text(row: &Value, key: &str) -> Option<String> {
+    field(row, key)?.as_str().map(str::to_string)
+}
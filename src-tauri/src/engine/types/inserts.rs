@@ -0,0 +1,209 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::{
+    config::Dialect,
+    kind::{unwrap_type, Kind},
+    result::ResultColumn,
+    value,
+};
+
+/// Builds `INSERT` statements for one table in the syntax of a dialect.
+pub struct Inserts<'a> {
+    dialect: &'a Dialect,
+    table: String,
+    columns: Vec<ResultColumn>,
+    key: Vec<String>,
+    upsert: bool,
+    overriding: bool,
+}
+
+impl<'a> Inserts<'a> {
+    pub fn new(
+        dialect: &'a Dialect,
+        table: &str,
+        columns: Vec<ResultColumn>,
+        key: Vec<String>,
+        upsert: bool,
+    ) -> Result<Self> {
+        if upsert {
+            match dialect {
+                Dialect::ClickHouse => bail!("ClickHouse does not support upserts"),
+                Dialect::Postgresql if key.is_empty() => {
+                    bail!("Upserting into {} needs a primary key", table)
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            dialect,
+            table: table.to_string(),
+            columns,
+            key,
+            upsert,
+            overriding: false,
+        })
+    }
+
+    /// Writes values into Postgres identity columns that are `GENERATED ALWAYS`.
+    pub fn overriding_system_value(mut self) -> Self {
+        self.overriding = self.dialect == &Dialect::Postgresql;
+        self
+    }
+
+    fn ident(&self, name: &str) -> String {
+        self.dialect.quote_ident(name)
+    }
+
+    fn string(&self, s: &str) -> String {
+        match self.dialect {
+            // backslashes are escapes in MySQL unless NO_BACKSLASH_ESCAPES is set
+            Dialect::Mysql | Dialect::MariaDB => {
+                format!("'{}'", s.replace('\\', "\\\\").replace('\'', "''"))
+            }
+            Dialect::ClickHouse => format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'")),
+            Dialect::Postgresql | Dialect::Sqlite => format!("'{}'", s.replace('\'', "''")),
+        }
+    }
+
+    /// Writes a value as a literal for a column of the given type.
+    pub fn literal(&self, v: &Value, type_name: &str) -> String {
+        let kind = Kind::from_type_name(type_name);
+        if let Some(digits) = value::exact_str(kind, v) {
+            return digits.to_string();
+        }
+        if let Some(hex) = value::binary_hex(kind, v) {
+            return match self.dialect {
+                Dialect::Postgresql => format!("'\\x{}'::bytea", hex),
+                Dialect::ClickHouse => format!("unhex('{}')", hex),
+                _ => format!("X'{}'", hex),
+            };
+        }
+        match (v, self.dialect) {
+            (Value::Null, _) => "NULL".to_string(),
+            (Value::Bool(b), Dialect::Sqlite) => (*b as u8).to_string(),
+            (Value::Bool(b), _) => b.to_string().to_uppercase(),
+            (Value::Number(n), _) => n.to_string(),
+            (Value::String(s), _) => self.string(s),
+            (Value::Array(values), Dialect::Postgresql) if type_name.ends_with("[]") => {
+                let element = type_name.trim_end_matches("[]");
+                let values: Vec<String> = values.iter().map(|v| self.literal(v, element)).collect();
+                format!("ARRAY[{}]::{}", values.join(", "), type_name)
+            }
+            (Value::Array(values), Dialect::ClickHouse) => {
+                let element = unwrap_type(type_name)
+                    .strip_prefix("Array(")
+                    .and_then(|t| t.strip_suffix(')'))
+                    .unwrap_or_default();
+                let values: Vec<String> = values.iter().map(|v| self.literal(v, element)).collect();
+                format!("[{}]", values.join(", "))
+            }
+            // JSON documents, composites and the like go in as their JSON text
+            (v, _) => self.string(&v.to_string()),
+        }
+    }
+
+    /// One statement inserting `rows`, ending with a semicolon and a new line.
+    pub fn statement(&self, rows: &[Vec<Value>]) -> String {
+        let verb = match (self.dialect, self.upsert) {
+            (Dialect::Sqlite, true) => "INSERT OR REPLACE INTO",
+            _ => "INSERT INTO",
+        };
+        let names: Vec<String> = self
+            .columns
+            .iter()
+            .map(|c| self.ident(c.column.as_deref().unwrap_or(&c.name)))
+            .collect();
+        let values: Vec<String> = rows
+            .iter()
+            .map(|row| {
+                let literals: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(c, v)| self.literal(v, &c.type_name))
+                    .collect();
+                format!("  ({})", literals.join(", "))
+            })
+            .collect();
+        let overriding = match self.overriding {
+            true => " OVERRIDING SYSTEM VALUE",
+            false => "",
+        };
+        format!(
+            "{} {} ({}){} VALUES\n{}{};\n",
+            verb,
+            self.dialect.quote_table(&self.table),
+            names.join(", "),
+            overriding,
+            values.join(",\n"),
+            self.conflict(&names)
+        )
+    }
+
+    fn conflict(&self, names: &[String]) -> String {
+        if !self.upsert {
+            return String::new();
+        }
+        let key: Vec<String> = self.key.iter().map(|k| self.ident(k)).collect();
+        let rest = names.iter().filter(|n| !key.contains(n));
+        match self.dialect {
+            Dialect::Postgresql => {
+                let set: Vec<String> = rest.map(|n| format!("{} = EXCLUDED.{}", n, n)).collect();
+                match set.is_empty() {
+                    true => format!("\nON CONFLICT ({}) DO NOTHING", key.join(", ")),
+                    false => format!(
+                        "\nON CONFLICT ({}) DO UPDATE SET {}",
+                        key.join(", "),
+                        set.join(", ")
+                    ),
+                }
+            }
+            // MariaDB has no row alias syntax, VALUES() works on both
+            Dialect::Mysql | Dialect::MariaDB => {
+                let set: Vec<String> = names
+                    .iter()
+                    .map(|n| format!("{} = VALUES({})", n, n))
+                    .collect();
+                format!("\nON DUPLICATE KEY UPDATE {}", set.join(", "))
+            }
+            Dialect::Sqlite | Dialect::ClickHouse => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::Inserts;
+    use crate::engine::types::{config::Dialect, result::ResultColumn};
+
+    fn columns() -> Vec<ResultColumn> {
+        vec![
+            ResultColumn::new("id", "INT8", None),
+            ResultColumn::new("name", "TEXT", None),
+            ResultColumn::new("tags", "TEXT[]", None),
+        ]
+    }
+
+    #[test]
+    fn test_statements() {
+        let inserts = Inserts::new(&Dialect::Sqlite, "users", columns(), vec![], true).unwrap();
+        assert_eq!(
+            inserts.statement(&[vec![json!(1), json!("a"), json!(false)]]),
+            "INSERT OR REPLACE INTO \"users\" (\"id\", \"name\", \"tags\") VALUES\n  (1, 'a', 0);\n"
+        );
+
+        assert_eq!(inserts.literal(&json!("0x00ff"), "BLOB"), "X'00ff'");
+        assert_eq!(inserts.literal(&json!("-1.50"), "NUMERIC"), "-1.50");
+        assert_eq!(inserts.literal(&json!("NaN"), "NUMERIC"), "'NaN'");
+
+        let inserts = Inserts::new(&Dialect::ClickHouse, "t", columns(), vec![], false).unwrap();
+        let literal = inserts.literal(&json!(["1", "2"]), "Array(Nullable(UInt64))");
+        assert_eq!(literal, "[1, 2]");
+
+        assert!(Inserts::new(&Dialect::Postgresql, "users", columns(), vec![], true).is_err());
+        assert!(Inserts::new(&Dialect::ClickHouse, "users", columns(), vec![], true).is_err());
+    }
+}
@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    config::Dialect,
+    value::{self, cell_text, temporal, Temporal},
+};
+
+/// The broad type of a column, shared by every dialect. Imports and copies
+/// between connections use it to check values and to pick column types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Bool,
+    Integer,
+    Float,
+    Decimal,
+    Date,
+    DateTime,
+    Time,
+    Json,
+    Binary,
+    #[default]
+    Text,
+}
+
+/// Drops ClickHouse's `Nullable` and `LowCardinality` wrappers.
+pub fn unwrap_type(type_name: &str) -> &str {
+    let type_name = type_name.trim();
+    ["Nullable(", "LowCardinality("]
+        .iter()
+        .find_map(|w| type_name.strip_prefix(w)?.strip_suffix(')'))
+        .map_or(type_name, unwrap_type)
+}
+
+impl Kind {
+    /// The kind of a column from the type name its dialect reports. Arrays and
+    /// types without a counterpart are text.
+    pub fn from_type_name(type_name: &str) -> Self {
+        let lower = unwrap_type(type_name).to_lowercase();
+        if lower.starts_with("tinyint(1)") {
+            return Kind::Bool;
+        }
+        if lower.ends_with("[]") || lower.starts_with('_') || lower.starts_with("array(") {
+            return Kind::Text;
+        }
+        let base = lower.split('(').next().unwrap_or_default().trim();
+        match base.split_whitespace().next().unwrap_or_default() {
+            "bool" | "boolean" => Kind::Bool,
+            "int2" | "int4" | "int8" | "smallint" | "int" | "integer" | "mediumint" | "tinyint"
+            | "bigint" | "year" | "serial" | "smallserial" | "bigserial" | "int16" | "int32"
+            | "int64" | "int128" | "int256" | "uint8" | "uint16" | "uint32" | "uint64"
+            | "uint128" | "uint256" => Kind::Integer,
+            "float4" | "float8" | "real" | "float" | "double" | "float32" | "float64" => {
+                Kind::Float
+            }
+            "numeric" | "decimal" | "decimal32" | "decimal64" | "decimal128" | "decimal256" => {
+                Kind::Decimal
+            }
+            "date" | "date32" => Kind::Date,
+            "timestamp" | "timestamptz" | "datetime" | "datetime64" => Kind::DateTime,
+            "time" | "timetz" => Kind::Time,
+            "json" | "jsonb" => Kind::Json,
+            "bytea" | "blob" | "tinyblob" | "mediumblob" | "longblob" | "binary" | "varbinary" => {
+                Kind::Binary
+            }
+            _ => Kind::Text,
+        }
+    }
+
+    /// The narrowest kind holding a value, `None` for nulls. Strings are read
+    /// as the values they spell, except numbers with leading zeros which stay
+    /// text. Fractions written out in digits are decimals, so they keep every
+    /// digit.
+    pub fn of(v: &Value) -> Option<Self> {
+        match v {
+            Value::Null => None,
+            Value::Bool(_) => Some(Kind::Bool),
+            Value::Number(n) if n.is_f64() => Some(Kind::Float),
+            Value::Number(_) => Some(Kind::Integer),
+            Value::Array(_) | Value::Object(_) => Some(Kind::Json),
+            Value::String(s) => {
+                let s = s.trim();
+                let digits = s.trim_start_matches('-');
+                if ["true", "false"].iter().any(|b| s.eq_ignore_ascii_case(b)) {
+                    Some(Kind::Bool)
+                } else if digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.") {
+                    Some(Kind::Text)
+                } else if s.parse::<i64>().is_ok() {
+                    Some(Kind::Integer)
+                } else if s.parse::<f64>().is_err() || !s.chars().any(|c| c.is_ascii_digit()) {
+                    match temporal(s) {
+                        Some(Temporal::Date(_)) => Some(Kind::Date),
+                        Some(Temporal::DateTime(_)) | Some(Temporal::Zoned(..)) => {
+                            Some(Kind::DateTime)
+                        }
+                        Some(Temporal::Time(_)) => Some(Kind::Time),
+                        None => Some(Kind::Text),
+                    }
+                } else if digits.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                    Some(Kind::Decimal)
+                } else {
+                    Some(Kind::Float)
+                }
+            }
+        }
+    }
+
+    /// The kind holding values of both kinds.
+    pub fn widen(self, other: Kind) -> Kind {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Kind::Integer | Kind::Decimal, Kind::Float)
+            | (Kind::Float, Kind::Integer | Kind::Decimal) => Kind::Float,
+            (Kind::Integer, Kind::Decimal) | (Kind::Decimal, Kind::Integer) => Kind::Decimal,
+            (Kind::Date, Kind::DateTime) | (Kind::DateTime, Kind::Date) => Kind::DateTime,
+            _ => Kind::Text,
+        }
+    }
+
+    /// The kind of a column from a sample of its values, text when they are all
+    /// null.
+    pub fn infer<'a>(values: impl Iterator<Item = &'a Value>) -> Kind {
+        values
+            .filter_map(Kind::of)
+            .reduce(Kind::widen)
+            .unwrap_or(Kind::Text)
+    }
+
+    /// The column type used for the kind when creating tables.
+    pub fn sql_type(&self, dialect: &Dialect) -> &'static str {
+        match dialect {
+            Dialect::Postgresql => match self {
+                Kind::Bool => "BOOLEAN",
+                Kind::Integer => "BIGINT",
+                Kind::Float => "DOUBLE PRECISION",
+                Kind::Decimal => "NUMERIC",
+                Kind::Date => "DATE",
+                Kind::DateTime => "TIMESTAMP",
+                Kind::Time => "TIME",
+                Kind::Json => "JSONB",
+                Kind::Binary => "BYTEA",
+                Kind::Text => "TEXT",
+            },
+            // MySQL's DECIMAL without arguments has no fraction digits
+            Dialect::Mysql | Dialect::MariaDB => match self {
+                Kind::Bool => "BOOLEAN",
+                Kind::Integer => "BIGINT",
+                Kind::Float => "DOUBLE",
+                Kind::Decimal => "DECIMAL(38, 10)",
+                Kind::Date => "DATE",
+                Kind::DateTime => "DATETIME(6)",
+                Kind::Time => "TIME(6)",
+                Kind::Json => "JSON",
+                Kind::Binary => "LONGBLOB",
+                Kind::Text => "LONGTEXT",
+            },
+            Dialect::Sqlite => match self {
+                Kind::Bool => "BOOLEAN",
+                Kind::Integer => "INTEGER",
+                Kind::Float => "REAL",
+                Kind::Decimal => "NUMERIC",
+                Kind::Date => "DATE",
+                Kind::DateTime => "DATETIME",
+                Kind::Time => "TIME",
+                Kind::Json | Kind::Text => "TEXT",
+                Kind::Binary => "BLOB",
+            },
+            Dialect::ClickHouse => match self {
+                Kind::Bool => "Bool",
+                Kind::Integer => "Int64",
+                Kind::Float => "Float64",
+                Kind::Decimal => "Decimal(38, 10)",
+                Kind::Date => "Date32",
+                Kind::DateTime => "DateTime64(6)",
+                Kind::Time | Kind::Json | Kind::Binary | Kind::Text => "String",
+            },
+        }
+    }
+
+    /// Checks a value against the kind and converts it to the form the loaders
+    /// write, e.g. the string `"12"` to a number for an integer column.
+    pub fn coerce(&self, v: Value) -> Result<Value, String> {
+        if v.is_null() {
+            return Ok(v);
+        }
+        let text = cell_text(&v).unwrap_or_default();
+        let trimmed = text.trim();
+        let invalid = || Err(format!("{:?} is not a valid {}", text, self.name()));
+        match self {
+            Kind::Bool => match v {
+                Value::Bool(_) => Ok(v),
+                _ => match trimmed.to_lowercase().as_str() {
+                    "true" | "t" | "yes" | "y" | "1" => Ok(Value::Bool(true)),
+                    "false" | "f" | "no" | "n" | "0" => Ok(Value::Bool(false)),
+                    _ => invalid(),
+                },
+            },
+            Kind::Integer => match v {
+                Value::Number(n) if !n.is_f64() => Ok(Value::Number(n)),
+                // integers wider than 64 bits keep their digits
+                _ => match trimmed.parse::<i64>() {
+                    Ok(n) => Ok(Value::from(n)),
+                    Err(_) if is_integer(trimmed) => Ok(value::bigint(trimmed)),
+                    Err(_) => invalid(),
+                },
+            },
+            Kind::Float => match v {
+                Value::Number(_) => Ok(v),
+                // NaN and infinities have no JSON number, they go in as text
+                _ => match trimmed.parse::<f64>() {
+                    Ok(f) => Ok(serde_json::Number::from_f64(f)
+                        .map_or(Value::String(trimmed.to_string()), Value::Number)),
+                    Err(_) => invalid(),
+                },
+            },
+            Kind::Decimal => match trimmed.parse::<f64>() {
+                Ok(f) if f.is_finite() && !trimmed.contains(['e', 'E']) => {
+                    Ok(value::decimal(trimmed))
+                }
+                _ => invalid(),
+            },
+            Kind::Date | Kind::DateTime | Kind::Time => match (&v, temporal(trimmed)) {
+                (Value::String(_), Some(_)) => Ok(Value::String(trimmed.to_string())),
+                _ => invalid(),
+            },
+            Kind::Json => match v {
+                Value::String(s) => match serde_json::from_str::<Value>(&s) {
+                    Ok(_) => Ok(Value::String(s)),
+                    Err(_) => invalid(),
+                },
+                v => Ok(Value::String(v.to_string())),
+            },
+            Kind::Binary => match &v {
+                Value::String(s) => {
+                    Ok(value::binary(&from_hex(s).unwrap_or(s.as_bytes().to_vec())))
+                }
+                _ => invalid(),
+            },
+            Kind::Text => Ok(Value::String(text)),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Kind::Bool => "boolean",
+            Kind::Integer => "integer",
+            Kind::Float => "number",
+            Kind::Decimal => "decimal",
+            Kind::Date => "date",
+            Kind::DateTime => "date time",
+            Kind::Time => "time",
+            Kind::Json => "JSON document",
+            Kind::Binary => "binary value",
+            Kind::Text => "text",
+        }
+    }
+}
+
+fn is_integer(s: &str) -> bool {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Reads `0x` or `\x` prefixed hex, as binary values are exported.
+fn from_hex(s: &str) -> Option<Vec<u8>> {
+    let digits = s.strip_prefix("0x").or_else(|| s.strip_prefix("\\x"))?;
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A `CREATE TABLE` statement for columns of the given kinds, all nullable.
+/// ClickHouse tables use a MergeTree without a sorting key.
+pub fn create_table(dialect: &Dialect, table: &str, columns: &[(String, Kind)]) -> String {
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|(name, kind)| {
+            let sql_type = match dialect {
+                Dialect::ClickHouse => format!("Nullable({})", kind.sql_type(dialect)),
+                _ => kind.sql_type(dialect).to_string(),
+            };
+            format!("  {} {}", dialect.quote_ident(name), sql_type)
+        })
+        .collect();
+    let engine = match dialect {
+        Dialect::ClickHouse => "\nENGINE = MergeTree\nORDER BY tuple()",
+        _ => "",
+    };
+    format!(
+        "CREATE TABLE {} (\n{}\n){}",
+        dialect.quote_table(table),
+        columns.join(",\n"),
+        engine
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{create_table, Kind};
+    use crate::engine::types::config::Dialect;
+
+    #[test]
+    fn test_kinds() {
+        assert_eq!(Kind::from_type_name("Nullable(Int64)"), Kind::Integer);
+        assert_eq!(Kind::from_type_name("tinyint(1)"), Kind::Bool);
+        assert_eq!(Kind::from_type_name("bigint unsigned"), Kind::Integer);
+        assert_eq!(Kind::from_type_name("timestamptz(6)"), Kind::DateTime);
+        assert_eq!(Kind::from_type_name("_int4"), Kind::Text);
+        assert_eq!(Kind::from_type_name("interval"), Kind::Text);
+
+        let sample = [json!("1"), json!(null), json!("2.50")];
+        assert_eq!(Kind::infer(sample.iter()), Kind::Decimal);
+        assert_eq!(Kind::infer([json!("1e3"), json!(2)].iter()), Kind::Float);
+        let sample = [json!("2024-01-02"), json!("2024-01-02 10:00:00")];
+        assert_eq!(Kind::infer(sample.iter()), Kind::DateTime);
+        assert_eq!(Kind::infer([json!("007"), json!("1")].iter()), Kind::Text);
+        assert_eq!(
+            Kind::infer([json!("18446744073709551616")].iter()),
+            Kind::Decimal
+        );
+        assert_eq!(Kind::infer([json!(null)].iter()), Kind::Text);
+
+        assert_eq!(Kind::Integer.coerce(json!(" 12 ")), Ok(json!(12)));
+        assert_eq!(
+            Kind::Integer.coerce(json!("18446744073709551616")),
+            Ok(json!("18446744073709551616"))
+        );
+        assert!(Kind::Integer.coerce(json!("1.5")).is_err());
+        assert_eq!(Kind::Bool.coerce(json!("yes")), Ok(json!(true)));
+        assert_eq!(Kind::Decimal.coerce(json!("1.10")), Ok(json!("1.10")));
+        assert!(Kind::Date.coerce(json!("2024-13-01")).is_err());
+        assert_eq!(Kind::Json.coerce(json!({"a": 1})), Ok(json!("{\"a\":1}")));
+        assert_eq!(Kind::Binary.coerce(json!("0x00ff")), Ok(json!("0x00ff")));
+        assert_eq!(Kind::Binary.coerce(json!("ab")), Ok(json!("0x6162")));
+        assert_eq!(Kind::Text.coerce(json!(5)), Ok(json!("5")));
+
+        let columns = [
+            ("id".to_string(), Kind::Integer),
+            ("note".to_string(), Kind::Text),
+        ];
+        assert_eq!(
+            create_table(&Dialect::ClickHouse, "t", &columns),
+            "CREATE TABLE `t` (\n  `id` Nullable(Int64),\n  `note` Nullable(String)\n)\nENGINE = MergeTree\nORDER BY tuple()"
+        );
+    }
+}
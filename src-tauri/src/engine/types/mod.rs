@@ -1,3 +1,7 @@
 pub mod config;
 pub mod connection;
+pub mod ddl;
+pub mod inserts;
+pub mod kind;
 pub mod result;
+pub mod value;
@@ -1,5 +1,12 @@
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sqlx::{
+    database::HasArguments, Column, Database, Executor, IntoArguments, Pool, Row, TypeInfo,
+};
+use tokio::sync::mpsc::Sender;
+
+use super::kind::Kind;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct TableMetadata {
@@ -9,11 +16,261 @@ pub struct TableMetadata {
     pub columns: Option<Vec<Value>>,
 }
 
+/// A column of a result set, in select list order.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ResultColumn {
+    pub name: String,
+    /// The type name as the dialect reports it, e.g. `INT8` or `Nullable(String)`.
+    #[serde(rename = "type")]
+    pub type_name: String,
+    /// The kind of the type, telling readers how to take string values, e.g. the
+    /// digits of a decimal or the hex of a binary value.
+    #[serde(default)]
+    pub kind: Kind,
+    pub nullable: Option<bool>,
+    /// The table and column the values are read from, where known.
+    pub table: Option<String>,
+    pub column: Option<String>,
+}
+
+impl ResultColumn {
+    pub fn new(name: &str, type_name: &str, nullable: Option<bool>) -> Self {
+        Self {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            kind: Kind::from_type_name(type_name),
+            nullable,
+            ..Default::default()
+        }
+    }
+}
+
+/// Rows are positional arrays matching `result_columns`, so columns sharing a name
+/// (`SELECT a.id, b.id`) are all kept.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ResultSet {
     pub start_time: u64,
     pub end_time: u64,
     pub affected_rows: u64,
-    pub rows: Vec<Value>,
+    /// Bytes written by the statement, for dialects that report it.
+    pub written_bytes: Option<u64>,
+    pub result_columns: Vec<ResultColumn>,
+    pub rows: Vec<Vec<Value>>,
     pub table: Option<TableMetadata>,
 }
+
+impl ResultSet {
+    /// Attaches the metadata of the table a query reads from. The query is the
+    /// one the UI builds for browsing that table alone, so result columns without
+    /// a known source and a name found in the table are read from it.
+    pub fn set_table(&mut self, table: TableMetadata) {
+        let table_columns: Vec<&str> = table
+            .columns
+            .iter()
+            .flatten()
+            .filter_map(column_name)
+            .collect();
+        for column in self.result_columns.iter_mut() {
+            if column.table.is_none() && table_columns.contains(&column.name.as_str()) {
+                column.table = Some(table.table.clone());
+                column.column = Some(column.name.clone());
+            }
+        }
+        self.table = Some(table);
+    }
+}
+
+impl TableMetadata {
+    /// The primary key columns, in key order.
+    pub fn primary_key_columns(&self) -> Vec<&str> {
+        self.primary_key
+            .iter()
+            .flatten()
+            .filter_map(column_name)
+            .collect()
+    }
+}
+
+/// Reads a field of a metadata row, whatever the case of its keys. MySQL
+/// returns information schema columns in upper case.
+pub fn field<'a>(row: &'a Value, key: &str) -> Option<&'a Value> {
+    row.as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, v)| v)
+}
+
+fn column_name(row: &Value) -> Option<&str> {
+    field(row, "column_name")?.as_str()
+}
+
+/// Keys for turning positional rows into objects, repeated names get a `_2`, `_3`...
+/// suffix.
+pub fn unique_names(columns: &[ResultColumn]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::with_capacity(columns.len());
+    for column in columns {
+        let mut name = column.name.clone();
+        let mut n = 1;
+        while names.contains(&name) {
+            n += 1;
+            name = format!("{}_{}", column.name, n);
+        }
+        names.push(name);
+    }
+    names
+}
+
+/// Lists the columns of a result. Names and types come from the first row, which
+/// carries the types actually sent. Only results without rows have the statement
+/// described, which costs another round trip, and that also tells nullability.
+pub async fn describe_columns<'e, DB, E>(
+    executor: E,
+    query: &'e str,
+    first: Option<&DB::Row>,
+) -> Vec<ResultColumn>
+where
+    DB: Database,
+    E: Executor<'e, Database = DB>,
+{
+    if let Some(row) = first {
+        return row
+            .columns()
+            .iter()
+            .map(|c| ResultColumn::new(c.name(), c.type_info().name(), None))
+            .collect();
+    }
+    let described = match executor.describe(query).await {
+        Ok(described) => described,
+        Err(e) => {
+            log::debug!("Could not describe query: {}", e);
+            return vec![];
+        }
+    };
+    described
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(i, c)| ResultColumn::new(c.name(), c.type_info().name(), described.nullable(i)))
+        .collect()
+}
+
+/// Fetches the rows of a query one at a time and sends them in pages, see
+/// `exec::stream_query`.
+pub async fn stream_rows<DB>(
+    pool: Pool<DB>,
+    query: String,
+    to_array: fn(&DB::Row) -> Vec<Value>,
+    mut pages: PageSender,
+) where
+    DB: Database,
+    for<'c> &'c Pool<DB>: Executor<'c, Database = DB>,
+    for<'q> <DB as HasArguments<'q>>::Arguments: IntoArguments<'q, DB>,
+{
+    let mut rows = sqlx::query(&query).fetch(&pool);
+    let result = loop {
+        match rows.try_next().await {
+            Ok(Some(row)) => {
+                if pages.is_empty() {
+                    pages.columns = describe_columns(&pool, &query, Some(&row)).await;
+                }
+                if !pages.push(to_array(&row)).await {
+                    return;
+                }
+            }
+            Ok(None) => break Ok(()),
+            Err(e) => break Err(e.into()),
+        }
+    };
+    drop(rows);
+    if result.is_ok() && pages.is_empty() {
+        pages.columns = describe_columns(&pool, &query, None).await;
+    }
+    pages.finish(result).await;
+}
+
+fn now_millis() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("Time went backwards")
+        .as_millis() as u64
+}
+
+/// Gathers the rows of a query as the database streams them into result sets of
+/// `page_size` rows, sending each once full, see `exec::stream_query`.
+pub struct PageSender {
+    tx: Sender<anyhow::Result<ResultSet>>,
+    page_size: usize,
+    start_time: u64,
+    /// The result columns, set from the first row or by describing the query.
+    pub columns: Vec<ResultColumn>,
+    rows: Vec<Vec<Value>>,
+    sent: bool,
+}
+
+impl PageSender {
+    pub fn new(tx: Sender<anyhow::Result<ResultSet>>, page_size: usize) -> Self {
+        Self {
+            tx,
+            page_size: page_size.max(1),
+            start_time: now_millis(),
+            columns: vec![],
+            rows: vec![],
+            sent: false,
+        }
+    }
+
+    /// Whether no row came yet, the columns of an empty result are described then.
+    pub fn is_empty(&self) -> bool {
+        !self.sent && self.rows.is_empty()
+    }
+
+    /// Adds a row and sends the page once full. False when the receiver is gone,
+    /// reading should stop then.
+    pub async fn push(&mut self, row: Vec<Value>) -> bool {
+        self.rows.push(row);
+        self.rows.len() < self.page_size || self.send().await
+    }
+
+    async fn send(&mut self) -> bool {
+        let page = ResultSet {
+            start_time: self.start_time,
+            end_time: now_millis(),
+            affected_rows: 0,
+            written_bytes: None,
+            result_columns: self.columns.clone(),
+            rows: std::mem::take(&mut self.rows),
+            table: None,
+        };
+        self.sent = true;
+        self.start_time = now_millis();
+        self.tx.send(Ok(page)).await.is_ok()
+    }
+
+    /// Sends the last page, an empty one with the columns when there were no
+    /// rows, or the error that ended the query.
+    pub async fn finish(mut self, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) if !self.sent || !self.rows.is_empty() => {
+                self.send().await;
+            }
+            Ok(()) => {}
+            Err(e) => {
+                let _ = self.tx.send(Err(e)).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{unique_names, ResultColumn};
+
+    #[test]
+    fn test_unique_names() {
+        let columns: Vec<ResultColumn> = ["id", "name", "id", "id_2", "id"]
+            .iter()
+            .map(|n| ResultColumn::new(n, "INT4", None))
+            .collect();
+        assert_eq!(unique_names(&columns), ["id", "name", "id_2", "id_2_2", "id_3"]);
+    }
+}
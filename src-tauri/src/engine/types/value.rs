@@ -0,0 +1,141 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use serde_json::Value;
+use std::fmt::Write;
+
+use super::kind::Kind;
+
+/// Decimals and 64-bit (or wider) integers don't survive as JSON numbers, JavaScript
+/// rounds anything above 2^53. They are carried as strings of their exact digits,
+/// and binary data as `0x` prefixed hex. The kind of the result column tells them
+/// apart from text, so user JSON is never mistaken for them.
+pub fn decimal(digits: impl Into<String>) -> Value {
+    Value::String(digits.into())
+}
+
+pub fn bigint(value: impl ToString) -> Value {
+    Value::String(value.to_string())
+}
+
+pub fn binary(bytes: &[u8]) -> Value {
+    Value::String(format!("0x{}", hex(bytes)))
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut out, b| {
+            let _ = write!(out, "{:02x}", b);
+            out
+        })
+}
+
+/// Returns the hex digits of a value in a binary column.
+pub fn binary_hex(kind: Kind, value: &Value) -> Option<&str> {
+    match kind {
+        Kind::Binary => value.as_str()?.strip_prefix("0x"),
+        _ => None,
+    }
+}
+
+/// Returns the exact digits of a value in an integer or decimal column. Special
+/// values such as Postgres' `NaN` and `Infinity` are no number literal, they are
+/// left to be treated as text.
+pub fn exact_str(kind: Kind, value: &Value) -> Option<&str> {
+    match kind {
+        Kind::Integer | Kind::Decimal => value.as_str().filter(|s| is_plain_number(s)),
+        _ => None,
+    }
+}
+
+/// Digits with an optional sign and decimal point, e.g. `-12.50`.
+fn is_plain_number(s: &str) -> bool {
+    let unsigned = s.strip_prefix('-').unwrap_or(s);
+    let (int, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    !int.is_empty()
+        && int.chars().all(|c| c.is_ascii_digit())
+        && fraction.chars().all(|c| c.is_ascii_digit())
+}
+
+/// A temporal value as the dialects return it.
+pub enum Temporal {
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+    /// RFC 3339 with an offset, the local time and the offset's UTC time
+    Zoned(NaiveDateTime, NaiveDateTime),
+    Time(NaiveTime),
+}
+
+/// Reads RFC 3339 date times, or plain dates, times and date times. MySQL zero
+/// dates and durations past 24 hours are not read.
+pub fn temporal(s: &str) -> Option<Temporal> {
+    if let Ok(d) = DateTime::parse_from_rfc3339(s) {
+        return Some(Temporal::Zoned(d.naive_local(), d.naive_utc()));
+    }
+    ["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .map(Temporal::DateTime)
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .map(Temporal::Date)
+        })
+        .or_else(|| {
+            NaiveTime::parse_from_str(s, "%H:%M:%S%.f")
+                .ok()
+                .map(Temporal::Time)
+        })
+}
+
+pub fn is_temporal(type_name: &str) -> bool {
+    let type_name = type_name.to_uppercase();
+    type_name.contains("DATE") || type_name.contains("TIME")
+}
+
+/// The text shown for a cell in text based formats, `None` for nulls. Arrays and
+/// objects are written as compact JSON.
+pub fn cell_text(v: &Value) -> Option<String> {
+    match v {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        v => Some(v.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{binary, binary_hex, exact_str, temporal, Temporal};
+    use crate::engine::types::kind::Kind;
+
+    #[test]
+    fn test_binary() {
+        assert_eq!(binary(&[0xca, 0xfe]), json!("0xcafe"));
+        assert_eq!(binary_hex(Kind::Binary, &json!("0xcafe")), Some("cafe"));
+        assert_eq!(binary_hex(Kind::Text, &json!("0xcafe")), None);
+        assert_eq!(exact_str(Kind::Decimal, &json!("1.10")), Some("1.10"));
+        assert_eq!(exact_str(Kind::Decimal, &json!("-0.5")), Some("-0.5"));
+        assert_eq!(exact_str(Kind::Decimal, &json!("NaN")), None);
+        assert_eq!(exact_str(Kind::Decimal, &json!("-Infinity")), None);
+        assert_eq!(exact_str(Kind::Json, &json!("1.10")), None);
+    }
+
+    #[test]
+    fn test_temporal() {
+        assert!(matches!(
+            temporal("2024-02-29T13:05:09+02:00"),
+            Some(Temporal::Zoned(local, utc))
+                if local.to_string() == "2024-02-29 13:05:09"
+                    && utc.to_string() == "2024-02-29 11:05:09"
+        ));
+        assert!(matches!(
+            temporal("2024-02-29 13:05:09.5"),
+            Some(Temporal::DateTime(_))
+        ));
+        assert!(matches!(temporal("2024-02-29"), Some(Temporal::Date(_))));
+        assert!(matches!(temporal("13:05:09.5"), Some(Temporal::Time(_))));
+        assert!(temporal("-838:59:59").is_none());
+        assert!(temporal("0000-00-00 00:00:00").is_none());
+    }
+}
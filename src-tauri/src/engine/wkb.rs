@@ -0,0 +1,200 @@
+use anyhow::{anyhow, Result};
+
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+/// Converts (E)WKB to WKT. PostGIS EWKB carries the SRID, which is written as an
+/// EWKT `SRID=4326;` prefix.
+pub fn to_wkt(buf: &[u8]) -> Result<String> {
+    let mut reader = Reader { buf, pos: 0 };
+    let mut out = String::new();
+    let srid = write_geometry(&mut reader, &mut out, true)?;
+    Ok(match srid {
+        Some(srid) => format!("SRID={};{}", srid, out),
+        None => out,
+    })
+}
+
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + N)
+            .ok_or_else(|| anyhow!("Truncated WKB"))?;
+        self.pos += N;
+        Ok(bytes.try_into()?)
+    }
+
+    fn u32(&mut self, little_endian: bool) -> Result<u32> {
+        let bytes = self.take::<4>()?;
+        Ok(match little_endian {
+            true => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        })
+    }
+
+    fn f64(&mut self, little_endian: bool) -> Result<f64> {
+        let bytes = self.take::<8>()?;
+        Ok(match little_endian {
+            true => f64::from_le_bytes(bytes),
+            false => f64::from_be_bytes(bytes),
+        })
+    }
+}
+
+fn write_geometry(reader: &mut Reader, out: &mut String, top: bool) -> Result<Option<u32>> {
+    let little_endian = reader.take::<1>()?[0] == 1;
+    let raw_type = reader.u32(little_endian)?;
+    let srid = match raw_type & EWKB_SRID != 0 {
+        true => Some(reader.u32(little_endian)?),
+        false => None,
+    };
+    // ISO WKB adds 1000/2000/3000 to the type code for Z, M and ZM
+    let iso = raw_type & 0xffff;
+    let (base, iso_z, iso_m) = (iso % 1000, matches!(iso / 1000, 1 | 3), iso / 1000 >= 2);
+    let z = raw_type & EWKB_Z != 0 || iso_z;
+    let m = raw_type & EWKB_M != 0 || iso_m;
+    let dims = 2 + z as usize + m as usize;
+    let name = match base {
+        1 => "POINT",
+        2 => "LINESTRING",
+        3 => "POLYGON",
+        4 => "MULTIPOINT",
+        5 => "MULTILINESTRING",
+        6 => "MULTIPOLYGON",
+        7 => "GEOMETRYCOLLECTION",
+        t => return Err(anyhow!("Unsupported WKB geometry type {}", t)),
+    };
+    out.push_str(name);
+    match (z, m) {
+        (true, true) => out.push_str(" ZM"),
+        (true, false) => out.push_str(" Z"),
+        (false, true) => out.push_str(" M"),
+        _ => {}
+    }
+    match base {
+        1 => {
+            let coords = read_coords(reader, little_endian, dims)?;
+            // an empty point is encoded with NaN coordinates
+            if coords.iter().all(|c| c.is_nan()) {
+                out.push_str(" EMPTY");
+            } else {
+                out.push('(');
+                write_coords(out, &coords);
+                out.push(')');
+            }
+        }
+        2 => write_points(reader, out, little_endian, dims)?,
+        3 => write_rings(reader, out, little_endian, dims)?,
+        _ => {
+            let count = reader.u32(little_endian)?;
+            if count == 0 {
+                out.push_str(" EMPTY");
+            } else {
+                out.push('(');
+                for i in 0..count {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    let mut part = String::new();
+                    write_geometry(reader, &mut part, false)?;
+                    match base {
+                        // members of multi geometries drop their type name
+                        4..=6 => out.push_str(
+                            part.trim_start_matches(|c: char| c.is_ascii_uppercase() || c == ' '),
+                        ),
+                        _ => out.push_str(&part),
+                    }
+                }
+                out.push(')');
+            }
+        }
+    }
+    Ok(if top { srid } else { None })
+}
+
+fn read_coords(reader: &mut Reader, little_endian: bool, dims: usize) -> Result<Vec<f64>> {
+    (0..dims).map(|_| reader.f64(little_endian)).collect()
+}
+
+fn write_coords(out: &mut String, coords: &[f64]) {
+    let coords: Vec<String> = coords.iter().map(|c| c.to_string()).collect();
+    out.push_str(&coords.join(" "));
+}
+
+fn write_points(reader: &mut Reader, out: &mut String, le: bool, dims: usize) -> Result<()> {
+    let count = reader.u32(le)?;
+    if count == 0 {
+        out.push_str(" EMPTY");
+        return Ok(());
+    }
+    out.push('(');
+    for i in 0..count {
+        if i > 0 {
+            out.push(',');
+        }
+        write_coords(out, &read_coords(reader, le, dims)?);
+    }
+    out.push(')');
+    Ok(())
+}
+
+fn write_rings(reader: &mut Reader, out: &mut String, le: bool, dims: usize) -> Result<()> {
+    let count = reader.u32(le)?;
+    if count == 0 {
+        out.push_str(" EMPTY");
+        return Ok(());
+    }
+    out.push('(');
+    for i in 0..count {
+        if i > 0 {
+            out.push(',');
+        }
+        write_points(reader, out, le, dims)?;
+    }
+    out.push(')');
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::to_wkt;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_to_wkt() -> Result<()> {
+        // SELECT 'SRID=4326;POINT(1 2)'::geometry
+        assert_eq!(
+            to_wkt(&hex("0101000020E6100000000000000000F03F0000000000000040"))?,
+            "SRID=4326;POINT(1 2)"
+        );
+        // ST_AsBinary('LINESTRING(0 0,1 1)')
+        assert_eq!(
+            to_wkt(&hex(
+                "01020000000200000000000000000000000000000000000000000000000000F03F000000000000F03F"
+            ))?,
+            "LINESTRING(0 0,1 1)"
+        );
+        // ST_AsBinary('MULTIPOINT((1 2),(3 4))')
+        assert_eq!(
+            to_wkt(&hex("0104000000020000000101000000000000000000F03F0000000000000040010100000000000000000008400000000000001040"))?,
+            "MULTIPOINT((1 2),(3 4))"
+        );
+        assert!(to_wkt(&hex("0101000000")).is_err());
+        Ok(())
+    }
+}
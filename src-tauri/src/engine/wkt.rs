@@ -0,0 +1,204 @@
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::{json, Value};
+
+/// Converts (E)WKT, as geometries are shown in results, to a GeoJSON geometry.
+/// GeoJSON has no place for the SRID or M values, they are dropped.
+pub fn to_geojson(wkt: &str) -> Result<Value> {
+    let wkt = match wkt.split_once(';') {
+        Some((srid, rest)) if srid.trim().to_uppercase().starts_with("SRID=") => rest,
+        _ => wkt,
+    };
+    let mut tokens = Tokens {
+        src: wkt,
+        chars: wkt.char_indices().peekable(),
+    };
+    let geometry = geometry(&mut tokens)?;
+    match tokens.next() {
+        None => Ok(geometry),
+        Some(token) => bail!("Unexpected {:?} after the geometry", token),
+    }
+}
+
+struct Tokens<'a> {
+    src: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Tokens<'a> {
+    fn peek(&mut self) -> Option<&'a str> {
+        let mut copy = Tokens {
+            src: self.src,
+            chars: self.chars.clone(),
+        };
+        copy.next()
+    }
+
+    /// A parenthesis, a comma or a run of anything else, e.g. a type name or
+    /// a number.
+    fn next(&mut self) -> Option<&'a str> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        let (start, c) = self.chars.next()?;
+        if matches!(c, '(' | ')' | ',') {
+            return Some(&self.src[start..start + 1]);
+        }
+        let mut end = start + c.len_utf8();
+        while let Some((i, c)) = self
+            .chars
+            .next_if(|(_, c)| !c.is_whitespace() && !matches!(c, '(' | ')' | ','))
+        {
+            end = i + c.len_utf8();
+        }
+        Some(&self.src[start..end])
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<()> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => bail!("Expected {:?}, found {:?}", expected, token),
+        }
+    }
+}
+
+fn geometry(tokens: &mut Tokens) -> Result<Value> {
+    let name = tokens
+        .next()
+        .ok_or_else(|| anyhow!("The geometry is empty"))?
+        .to_uppercase();
+    let (type_name, key) = match name.as_str() {
+        "POINT" => ("Point", "coordinates"),
+        "LINESTRING" => ("LineString", "coordinates"),
+        "POLYGON" => ("Polygon", "coordinates"),
+        "MULTIPOINT" => ("MultiPoint", "coordinates"),
+        "MULTILINESTRING" => ("MultiLineString", "coordinates"),
+        "MULTIPOLYGON" => ("MultiPolygon", "coordinates"),
+        "GEOMETRYCOLLECTION" => ("GeometryCollection", "geometries"),
+        _ => bail!("Unsupported geometry type {}", name),
+    };
+    let measured = match tokens.peek().map(str::to_uppercase).as_deref() {
+        Some(dims @ ("Z" | "M" | "ZM")) => {
+            let measured = dims.ends_with('M');
+            tokens.next();
+            measured
+        }
+        _ => false,
+    };
+    if tokens
+        .peek()
+        .is_some_and(|t| t.eq_ignore_ascii_case("EMPTY"))
+    {
+        tokens.next();
+        return Ok(json!({ "type": type_name, key: [] }));
+    }
+    let value = match type_name {
+        "GeometryCollection" => {
+            tokens.expect("(")?;
+            let mut geometries = vec![geometry(tokens)?];
+            while tokens.peek() == Some(",") {
+                tokens.next();
+                geometries.push(geometry(tokens)?);
+            }
+            tokens.expect(")")?;
+            Value::Array(geometries)
+        }
+        "Point" => match nested(tokens, measured)? {
+            Value::Array(mut points) if points.len() == 1 => points.remove(0),
+            _ => bail!("A point has one position"),
+        },
+        // members of a multipoint may or may not be in parentheses
+        "MultiPoint" => match nested(tokens, measured)? {
+            Value::Array(points) => Value::Array(
+                points
+                    .into_iter()
+                    .map(|p| match p {
+                        Value::Array(mut inner) if inner.len() == 1 && inner[0].is_array() => {
+                            inner.remove(0)
+                        }
+                        p => p,
+                    })
+                    .collect(),
+            ),
+            v => v,
+        },
+        _ => nested(tokens, measured)?,
+    };
+    Ok(json!({ "type": type_name, key: value }))
+}
+
+/// A parenthesised list of positions or of nested lists.
+fn nested(tokens: &mut Tokens, measured: bool) -> Result<Value> {
+    tokens.expect("(")?;
+    let mut items = vec![];
+    loop {
+        items.push(match tokens.peek() {
+            Some("(") => nested(tokens, measured)?,
+            _ => position(tokens, measured)?,
+        });
+        match tokens.next() {
+            Some(",") => continue,
+            Some(")") => break,
+            token => bail!("Expected \",\" or \")\", found {:?}", token),
+        }
+    }
+    Ok(Value::Array(items))
+}
+
+fn position(tokens: &mut Tokens, measured: bool) -> Result<Value> {
+    let mut coords = vec![];
+    while let Some(token) = tokens.peek().filter(|t| !matches!(*t, "," | ")" | "(")) {
+        tokens.next();
+        let n: f64 = token
+            .parse()
+            .map_err(|_| anyhow!("{:?} is not a coordinate", token))?;
+        coords.push(
+            serde_json::Number::from_f64(n).ok_or_else(|| anyhow!("{} has no GeoJSON form", n))?,
+        );
+    }
+    if measured {
+        coords.pop();
+    }
+    if coords.len() < 2 {
+        bail!("A position needs at least two coordinates");
+    }
+    Ok(Value::Array(
+        coords.into_iter().map(Value::Number).collect(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use serde_json::json;
+
+    use super::to_geojson;
+
+    #[test]
+    fn test_to_geojson() -> Result<()> {
+        assert_eq!(
+            to_geojson("SRID=4326;POINT(1 2)")?,
+            json!({"type": "Point", "coordinates": [1.0, 2.0]})
+        );
+        assert_eq!(
+            to_geojson("MULTIPOINT((1 2),(3 4))")?,
+            to_geojson("MULTIPOINT(1 2, 3 4)")?
+        );
+        assert_eq!(
+            to_geojson("POLYGON ZM ((0 0 1 9,1 0 1 9,0 1 1 9,0 0 1 9))")?,
+            json!({"type": "Polygon", "coordinates": [[
+                [0.0, 0.0, 1.0], [1.0, 0.0, 1.0], [0.0, 1.0, 1.0], [0.0, 0.0, 1.0]
+            ]]})
+        );
+        assert_eq!(
+            to_geojson("GEOMETRYCOLLECTION(POINT(1 2),LINESTRING EMPTY)")?,
+            json!({"type": "GeometryCollection", "geometries": [
+                {"type": "Point", "coordinates": [1.0, 2.0]},
+                {"type": "LineString", "coordinates": []}
+            ]})
+        );
+        assert!(to_geojson("POINT(1)").is_err());
+        assert!(to_geojson("CIRCLE(1 2)").is_err());
+        Ok(())
+    }
+}
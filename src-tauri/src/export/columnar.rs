@@ -0,0 +1,439 @@
+use std::{fs::File, sync::Arc};
+
+use anyhow::Result;
+use arrow::{
+    array::{
+        ArrayRef, BinaryArray, BooleanArray, Date32Array, Decimal128Array, Float64Array,
+        Int64Array, StringArray, Time64MicrosecondArray, TimestampMicrosecondArray, UInt64Array,
+    },
+    datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit},
+    ipc::writer::FileWriter,
+    record_batch::RecordBatch,
+};
+use chrono::{NaiveDate, NaiveDateTime, Timelike};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, GzipLevel, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::read_rows;
+use crate::{
+    engine::types::{
+        kind::{unwrap_type, Kind},
+        result::ResultColumn,
+        value::{self, cell_text, temporal, Temporal},
+    },
+    utils::fs::read_result_columns,
+};
+
+/// Rows converted at a time, which bounds memory use for any result size.
+const BATCH_SIZE: usize = 8192;
+/// Parquet buffers a row group before writing it out.
+const ROW_GROUP_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParquetCompression {
+    None,
+    #[default]
+    Snappy,
+    Gzip,
+    Zstd,
+    Lz4,
+}
+
+impl From<ParquetCompression> for Compression {
+    fn from(c: ParquetCompression) -> Self {
+        match c {
+            ParquetCompression::None => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip => Compression::GZIP(GzipLevel::default()),
+            ParquetCompression::Zstd => Compression::ZSTD(ZstdLevel::default()),
+            ParquetCompression::Lz4 => Compression::LZ4_RAW,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ParquetOptions {
+    pub compression: ParquetCompression,
+}
+
+/// Reads the arguments of a type name such as `Decimal(10, 2)`.
+fn args(type_name: &str) -> Vec<&str> {
+    type_name
+        .split_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .map(|args| args.split(',').map(str::trim).collect())
+        .unwrap_or_default()
+}
+
+/// Decimals become Decimal128 when the type names its precision and scale, as
+/// ClickHouse's do, and strings of their digits otherwise.
+fn decimal_type(type_name: &str) -> DataType {
+    let precision_scale = match (type_name.split('(').next(), args(type_name).as_slice()) {
+        (Some("Decimal32"), [s]) => Some(("9", *s)),
+        (Some("Decimal64"), [s]) => Some(("18", *s)),
+        (Some("Decimal128"), [s]) => Some(("38", *s)),
+        (_, [p, s]) => Some((*p, *s)),
+        _ => None,
+    };
+    match precision_scale.and_then(|(p, s)| Some((p.parse::<u8>().ok()?, s.parse::<i8>().ok()?))) {
+        Some((p, s)) if (1..=38).contains(&p) && s >= 0 && s as u8 <= p => {
+            DataType::Decimal128(p, s)
+        }
+        _ => DataType::Utf8,
+    }
+}
+
+/// The Arrow type for a result column, from the type name its dialect reports.
+/// Arrays, JSON and types without an Arrow counterpart are written as text.
+pub fn data_type(column: &ResultColumn) -> DataType {
+    let type_name = unwrap_type(&column.type_name);
+    let upper = type_name.to_uppercase();
+    let base = upper.split('(').next().unwrap_or_default().trim();
+    // ClickHouse's Int8 is a byte rather than Postgres' bigint, Int64 holds both
+    match base {
+        "BOOL" | "BOOLEAN" => DataType::Boolean,
+        "INT2" | "INT4" | "INT8" | "SMALLINT" | "INT" | "INTEGER" | "MEDIUMINT" | "TINYINT"
+        | "BIGINT" | "YEAR" | "SMALLINT UNSIGNED" | "INT UNSIGNED" | "MEDIUMINT UNSIGNED"
+        | "TINYINT UNSIGNED" | "INT16" | "INT32" | "INT64" | "UINT8" | "UINT16" | "UINT32" => {
+            DataType::Int64
+        }
+        "BIGINT UNSIGNED" | "UINT64" => DataType::UInt64,
+        "FLOAT4" | "FLOAT8" | "REAL" | "FLOAT" | "DOUBLE" | "FLOAT32" | "FLOAT64" => {
+            DataType::Float64
+        }
+        "NUMERIC" | "DECIMAL" | "DECIMAL32" | "DECIMAL64" | "DECIMAL128" => decimal_type(type_name),
+        "DATE" | "DATE32" => DataType::Date32,
+        "TIMESTAMPTZ" => DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+        "TIMESTAMP" | "DATETIME" | "DATETIME64" => DataType::Timestamp(TimeUnit::Microsecond, None),
+        "TIME" => DataType::Time64(TimeUnit::Microsecond),
+        "BYTEA" | "BLOB" | "TINYBLOB" | "MEDIUMBLOB" | "LONGBLOB" | "BINARY" | "VARBINARY" => {
+            DataType::Binary
+        }
+        _ => DataType::Utf8,
+    }
+}
+
+pub fn schema(columns: &[ResultColumn]) -> SchemaRef {
+    Arc::new(Schema::new(
+        columns
+            .iter()
+            .map(|c| Field::new(&c.name, data_type(c), true))
+            .collect::<Vec<Field>>(),
+    ))
+}
+
+/// The text of strings and numbers, for parsing.
+fn text(v: &Value) -> Option<String> {
+    match v {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn decimal(v: &Value, scale: i8) -> Option<i128> {
+    let digits = text(v)?;
+    let (int, fraction) = digits.split_once('.').unwrap_or((&digits, ""));
+    let scale = scale as usize;
+    if fraction.len() > scale && fraction[scale..].chars().any(|c| c != '0') {
+        return None;
+    }
+    let fraction: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(scale)
+        .collect();
+    format!("{}{}", int, fraction).parse().ok()
+}
+
+fn micros(d: NaiveDateTime) -> i64 {
+    d.and_utc().timestamp_micros()
+}
+
+fn timestamp(v: &Value, zoned: bool) -> Option<i64> {
+    match temporal(v.as_str()?)? {
+        Temporal::Zoned(_, utc) if zoned => Some(micros(utc)),
+        Temporal::Zoned(local, _) => Some(micros(local)),
+        Temporal::DateTime(d) => Some(micros(d)),
+        Temporal::Date(d) => Some(micros(d.and_hms_opt(0, 0, 0)?)),
+        Temporal::Time(_) => None,
+    }
+}
+
+fn date(v: &Value) -> Option<i32> {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+    let date = match temporal(v.as_str()?)? {
+        Temporal::Date(d) => d,
+        Temporal::DateTime(d) | Temporal::Zoned(d, _) => d.date(),
+        Temporal::Time(_) => return None,
+    };
+    Some((date - epoch).num_days() as i32)
+}
+
+fn time(v: &Value) -> Option<i64> {
+    match temporal(v.as_str()?)? {
+        Temporal::Time(t) => {
+            Some(t.num_seconds_from_midnight() as i64 * 1_000_000 + t.nanosecond() as i64 / 1000)
+        }
+        _ => None,
+    }
+}
+
+fn binary(kind: Kind, v: &Value) -> Option<Vec<u8>> {
+    match (value::binary_hex(kind, v), v) {
+        (Some(hex), _) => (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect(),
+        (None, Value::String(s)) => Some(s.as_bytes().to_vec()),
+        _ => None,
+    }
+}
+
+/// Converts the values of one column. Values that do not fit the column type are
+/// written as nulls and counted in `invalid`, so one odd value doesn't abort the
+/// export.
+fn to_array(
+    column: &ResultColumn,
+    data_type: &DataType,
+    values: &[&Value],
+    invalid: &mut usize,
+) -> Result<ArrayRef> {
+    fn convert<T>(
+        column: &ResultColumn,
+        values: &[&Value],
+        invalid: &mut usize,
+        f: impl Fn(&Value) -> Option<T>,
+    ) -> Vec<Option<T>> {
+        values
+            .iter()
+            .map(|v| match v {
+                Value::Null => None,
+                v => f(v).or_else(|| {
+                    if *invalid == 0 {
+                        log::warn!(
+                            "Writing value {} in column {} as null, it is not a valid {}",
+                            v,
+                            column.name,
+                            column.type_name
+                        );
+                    }
+                    *invalid += 1;
+                    None
+                }),
+            })
+            .collect()
+    }
+
+    let array: ArrayRef = match data_type {
+        DataType::Boolean => Arc::new(BooleanArray::from(convert(column, values, invalid, |v| {
+            v.as_bool().or_else(|| v.as_i64().map(|n| n != 0))
+        }))),
+        DataType::Int64 => Arc::new(Int64Array::from(convert(column, values, invalid, |v| {
+            text(v)?.parse().ok()
+        }))),
+        DataType::UInt64 => Arc::new(UInt64Array::from(convert(column, values, invalid, |v| {
+            text(v)?.parse().ok()
+        }))),
+        DataType::Float64 => Arc::new(Float64Array::from(convert(column, values, invalid, |v| {
+            text(v)?.parse().ok()
+        }))),
+        DataType::Decimal128(p, s) => Arc::new(
+            Decimal128Array::from(convert(column, values, invalid, |v| decimal(v, *s)))
+                .with_precision_and_scale(*p, *s)?,
+        ),
+        DataType::Date32 => Arc::new(Date32Array::from(convert(column, values, invalid, date))),
+        DataType::Timestamp(_, tz) => Arc::new(
+            TimestampMicrosecondArray::from(convert(column, values, invalid, |v| {
+                timestamp(v, tz.is_some())
+            }))
+            .with_timezone_opt(tz.clone()),
+        ),
+        DataType::Time64(_) => Arc::new(Time64MicrosecondArray::from(convert(
+            column, values, invalid, time,
+        ))),
+        DataType::Binary => {
+            let values = convert(column, values, invalid, |v| binary(column.kind, v));
+            Arc::new(BinaryArray::from_iter(values))
+        }
+        _ => Arc::new(StringArray::from(convert(
+            column, values, invalid, cell_text,
+        ))),
+    };
+    Ok(array)
+}
+
+/// Reads a stored result file in record batches of its column types.
+pub struct Batches {
+    columns: Vec<ResultColumn>,
+    schema: SchemaRef,
+    rows: Box<dyn Iterator<Item = Result<Vec<Value>>>>,
+    /// Values written as nulls as they don't fit their column type.
+    pub invalid: usize,
+}
+
+impl Batches {
+    pub fn open(source: &str) -> Result<Self> {
+        let columns = read_result_columns(source)?;
+        let schema = schema(&columns);
+        let rows = Box::new(read_rows(source)?);
+        Ok(Self {
+            columns,
+            schema,
+            rows,
+            invalid: 0,
+        })
+    }
+
+    fn batch(&mut self, rows: &[Vec<Value>]) -> Result<RecordBatch> {
+        let invalid = &mut self.invalid;
+        let arrays = self
+            .columns
+            .iter()
+            .zip(self.schema.fields())
+            .enumerate()
+            .map(|(i, (column, field))| {
+                let values: Vec<&Value> = rows
+                    .iter()
+                    .map(|r| r.get(i).unwrap_or(&Value::Null))
+                    .collect();
+                to_array(column, field.data_type(), &values, invalid)
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        Ok(RecordBatch::try_new(self.schema.clone(), arrays)?)
+    }
+}
+
+impl Iterator for Batches {
+    type Item = Result<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rows = match self
+            .rows
+            .by_ref()
+            .take(BATCH_SIZE)
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(rows) if rows.is_empty() => return None,
+            Ok(rows) => rows,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(self.batch(&rows))
+    }
+}
+
+/// Returns the number of values written as nulls.
+pub fn export_parquet(source: &str, destination: &str, options: &ParquetOptions) -> Result<usize> {
+    let mut batches = Batches::open(source)?;
+    let properties = WriterProperties::builder()
+        .set_compression(options.compression.into())
+        .set_max_row_group_size(ROW_GROUP_SIZE)
+        .build();
+    let mut writer = ArrowWriter::try_new(
+        File::create(destination)?,
+        batches.schema.clone(),
+        Some(properties),
+    )?;
+    for batch in batches.by_ref() {
+        writer.write(&batch?)?;
+    }
+    writer.close()?;
+    Ok(batches.invalid)
+}
+
+/// Writes an Arrow IPC file, which is also what Feather v2 is. Returns the
+/// number of values written as nulls.
+pub fn export_arrow(source: &str, destination: &str) -> Result<usize> {
+    let mut batches = Batches::open(source)?;
+    let mut writer = FileWriter::try_new(File::create(destination)?, &batches.schema.clone())?;
+    for batch in batches.by_ref() {
+        writer.write(&batch?)?;
+    }
+    writer.finish()?;
+    Ok(batches.invalid)
+}
+
+#[cfg(test)]
+mod test {
+    use arrow::{
+        array::{
+            Array, BinaryArray, Decimal128Array, Int64Array, StringArray, TimestampMicrosecondArray,
+        },
+        datatypes::{DataType, TimeUnit},
+    };
+    use serde_json::json;
+
+    use super::{data_type, to_array};
+    use crate::engine::types::result::ResultColumn;
+
+    #[test]
+    fn test_to_array() {
+        let column = |t: &str| ResultColumn::new("c", t, None);
+        assert_eq!(data_type(&column("INT4")), DataType::Int64);
+        assert_eq!(
+            data_type(&column("Nullable(Decimal(10, 2))")),
+            DataType::Decimal128(10, 2)
+        );
+        assert_eq!(
+            data_type(&column("Decimal64(4)")),
+            DataType::Decimal128(18, 4)
+        );
+        assert_eq!(data_type(&column("NUMERIC")), DataType::Utf8);
+        assert_eq!(data_type(&column("INT8[]")), DataType::Utf8);
+        assert_eq!(
+            data_type(&column("DateTime64(3, 'UTC')")),
+            DataType::Timestamp(TimeUnit::Microsecond, None)
+        );
+
+        let c = column("Decimal(10, 2)");
+        let values = [json!("-1.5"), json!(null), json!("12.30")];
+        let refs: Vec<_> = values.iter().collect();
+        let array = to_array(&c, &data_type(&c), &refs, &mut 0).unwrap();
+        let array = array.as_any().downcast_ref::<Decimal128Array>().unwrap();
+        assert_eq!(array.value(0), -150);
+        assert!(array.is_null(1));
+        assert_eq!(array.value(2), 1230);
+
+        let c = column("TIMESTAMPTZ");
+        let values = [json!("1970-01-01T01:00:00.5+01:00")];
+        let refs: Vec<_> = values.iter().collect();
+        let array = to_array(&c, &data_type(&c), &refs, &mut 0).unwrap();
+        let array = array
+            .as_any()
+            .downcast_ref::<TimestampMicrosecondArray>()
+            .unwrap();
+        assert_eq!(array.value(0), 500_000);
+
+        let c = column("JSONB");
+        let values = [json!({"a": [1, "2"]})];
+        let refs: Vec<_> = values.iter().collect();
+        let array = to_array(&c, &data_type(&c), &refs, &mut 0).unwrap();
+        let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(array.value(0), r#"{"a":[1,"2"]}"#);
+
+        let c = column("BYTEA");
+        let values = [json!("0x00ff"), json!("ab")];
+        let refs: Vec<_> = values.iter().collect();
+        let array = to_array(&c, &data_type(&c), &refs, &mut 0).unwrap();
+        let array = array.as_any().downcast_ref::<BinaryArray>().unwrap();
+        assert_eq!(array.value(0), [0, 255]);
+        assert_eq!(array.value(1), b"ab");
+
+        let c = column("INT4");
+        let values = [json!("abc"), json!(1), json!("x")];
+        let refs: Vec<_> = values.iter().collect();
+        let mut invalid = 0;
+        let array = to_array(&c, &data_type(&c), &refs, &mut invalid).unwrap();
+        let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+        assert!(array.is_null(0));
+        assert_eq!(array.value(1), 1);
+        assert_eq!(invalid, 2);
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::read_rows;
+use crate::{engine::types::value::cell_text, utils::fs::read_result_columns};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// UTF-8 with a byte order mark, which Excel needs to detect the encoding
+    Utf8Bom,
+    /// UTF-16 little endian with a byte order mark
+    Utf16Le,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LineEnding {
+    /// `\r\n`, as RFC 4180 asks for
+    #[default]
+    Crlf,
+    Lf,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub header: bool,
+    /// Written for null cells, empty by default
+    pub null: String,
+    pub encoding: Encoding,
+    pub line_ending: LineEnding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            header: true,
+            null: String::new(),
+            encoding: Encoding::default(),
+            line_ending: LineEnding::default(),
+        }
+    }
+}
+
+impl CsvOptions {
+    fn line_ending(&self) -> &'static str {
+        match self.line_ending {
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Lf => "\n",
+        }
+    }
+
+    /// Quotes a field when it holds the delimiter, a quote or a line break, or
+    /// has leading or trailing spaces, doubling any quotes inside it.
+    fn field(&self, text: &str) -> String {
+        let quote = text.is_empty() && !self.null.is_empty()
+            || text.starts_with(' ')
+            || text.ends_with(' ')
+            || text
+                .chars()
+                .any(|c| c == self.delimiter || c == '"' || c == '\n' || c == '\r');
+        match quote {
+            true => format!("\"{}\"", text.replace('"', "\"\"")),
+            false => text.to_string(),
+        }
+    }
+
+    /// Formats one record, including its line ending. Nulls are written as the
+    /// null text, unquoted, so that an empty string can still be told apart.
+    pub fn record<'a>(&self, values: impl Iterator<Item = Option<&'a str>>) -> String {
+        let delimiter = self.delimiter.to_string();
+        let fields: Vec<String> = values
+            .map(|v| match v {
+                Some(text) => self.field(text),
+                None => self.null.clone(),
+            })
+            .collect();
+        fields.join(&delimiter) + self.line_ending()
+    }
+}
+
+/// Writes text in the chosen encoding.
+struct Encoder<W: Write> {
+    inner: W,
+    encoding: Encoding,
+}
+
+impl<W: Write> Encoder<W> {
+    fn new(mut inner: W, encoding: Encoding) -> Result<Self> {
+        match encoding {
+            Encoding::Utf8 => {}
+            Encoding::Utf8Bom => inner.write_all(&[0xef, 0xbb, 0xbf])?,
+            Encoding::Utf16Le => inner.write_all(&[0xff, 0xfe])?,
+        }
+        Ok(Self { inner, encoding })
+    }
+
+    fn write(&mut self, text: &str) -> Result<()> {
+        match self.encoding {
+            Encoding::Utf8 | Encoding::Utf8Bom => self.inner.write_all(text.as_bytes())?,
+            Encoding::Utf16Le => {
+                let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+                self.inner.write_all(&bytes)?
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W> {
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+/// Writes rows as CSV, or any other delimited text, to `out`.
+pub fn write<W: Write>(
+    out: W,
+    names: &[String],
+    rows: impl Iterator<Item = Result<Vec<Value>>>,
+    options: &CsvOptions,
+) -> Result<W> {
+    let mut out = Encoder::new(out, options.encoding)?;
+    if options.header && !names.is_empty() {
+        out.write(&options.record(names.iter().map(|n| Some(n.as_str()))))?;
+    }
+    for row in rows {
+        let cells: Vec<Option<String>> = row?.iter().map(cell_text).collect();
+        out.write(&options.record(cells.iter().map(Option::as_deref)))?;
+    }
+    out.finish()
+}
+
+/// Exports a stored result file row by row.
+pub fn export(source: &str, destination: &str, options: &CsvOptions) -> Result<()> {
+    let names: Vec<String> = read_result_columns(source)?
+        .into_iter()
+        .map(|c| c.name)
+        .collect();
+    let out = BufWriter::new(File::create(destination)?);
+    write(out, &names, read_rows(source)?, options)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{write, CsvOptions, Encoding, LineEnding};
+
+    #[test]
+    fn test_write() {
+        let names = vec!["id".to_string(), "note".to_string(), "amount".to_string()];
+        let rows = vec![
+            Ok(vec![json!(1), json!("a, \"b\"\nc"), json!("1.50")]),
+            Ok(vec![json!(2), json!(""), json!(null)]),
+            Ok(vec![json!(3), json!(" x"), json!("0x00ff")]),
+        ];
+        let out = write(vec![], &names, rows.into_iter(), &CsvOptions::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "id,note,amount\r\n1,\"a, \"\"b\"\"\nc\",1.50\r\n2,,\r\n3,\" x\",0x00ff\r\n"
+        );
+
+        let options = CsvOptions {
+            delimiter: '\t',
+            header: false,
+            null: "NULL".to_string(),
+            encoding: Encoding::Utf8Bom,
+            line_ending: LineEnding::Lf,
+        };
+        let rows = vec![Ok(vec![
+            json!("a,b"),
+            json!(""),
+            json!(null),
+            json!([1, 2]),
+        ])];
+        let out = write(vec![], &names, rows.into_iter(), &options).unwrap();
+        assert_eq!(out, "\u{feff}a,b\t\"\"\tNULL\t[1,2]\n".as_bytes());
+
+        let options = CsvOptions {
+            encoding: Encoding::Utf16Le,
+            ..CsvOptions::default()
+        };
+        let out = write(vec![], &[], std::iter::empty(), &options).unwrap();
+        assert_eq!(out, vec![0xff, 0xfe]);
+    }
+}
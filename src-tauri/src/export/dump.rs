@@ -0,0 +1,311 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+use super::sql::write;
+use crate::{
+    engine::types::{
+        config::Dialect,
+        connection::InitiatedConnection,
+        ddl::{ObjectType, SchemaObject},
+        inserts::Inserts,
+    },
+    import::transfer::{Pages, TransferSource},
+};
+
+/// Rows read from a table at a time.
+const PAGE_ROWS: usize = 1000;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DumpOptions {
+    /// Tables to dump with their triggers, every object of the schema when empty
+    pub tables: Vec<String>,
+    /// Writes the statements creating the objects
+    pub schema: bool,
+    /// Writes the tables' rows
+    pub data: bool,
+    /// Rows per `INSERT` statement
+    pub batch_size: usize,
+}
+
+impl Default for DumpOptions {
+    fn default() -> Self {
+        Self {
+            tables: vec![],
+            schema: true,
+            data: true,
+            batch_size: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DumpReport {
+    /// The table being written
+    pub table: Option<String>,
+    pub tables: usize,
+    pub rows: u64,
+    /// Views, routines and triggers written
+    pub objects: usize,
+}
+
+fn statement<W: Write>(out: &mut W, statement: &str) -> Result<()> {
+    writeln!(out, "{};\n", statement.trim_end().trim_end_matches(';'))?;
+    Ok(())
+}
+
+/// Routines and triggers have statements in their bodies, the MySQL client
+/// needs another delimiter to read them.
+fn routine<W: Write>(out: &mut W, dialect: &Dialect, definition: &str) -> Result<()> {
+    match dialect {
+        Dialect::Mysql | Dialect::MariaDB => {
+            writeln!(
+                out,
+                "DELIMITER ;;\n{};;\nDELIMITER ;\n",
+                definition.trim_end()
+            )?;
+            Ok(())
+        }
+        _ => statement(out, definition),
+    }
+}
+
+/// Writes the definitions of the objects of some types, a type at a time.
+/// Postgres defines every overload of a routine, or trigger of a name, at once.
+async fn write_objects<W: Write>(
+    out: &mut W,
+    conn: &InitiatedConnection,
+    objects: &[SchemaObject],
+    object_types: &[ObjectType],
+    report: &mut DumpReport,
+    progress: &impl Fn(&DumpReport),
+) -> Result<()> {
+    let dialect = &conn.config.dialect;
+    let mut written = HashSet::new();
+    for object_type in object_types {
+        for object in objects.iter().filter(|o| &o.object_type == object_type) {
+            if !written.insert((object_type, object.name.as_str())) {
+                continue;
+            }
+            let definition = conn
+                .get_create_statement(*object_type, &object.name)
+                .await?;
+            match object_type {
+                ObjectType::Function | ObjectType::Procedure | ObjectType::Trigger => {
+                    routine(out, dialect, &definition)?
+                }
+                _ => statement(out, &definition)?,
+            }
+            report.objects += 1;
+        }
+        progress(report);
+    }
+    Ok(())
+}
+
+/// Writes the rows of a table as `INSERT` statements, reading them with `Pages`.
+/// Generated columns are left out.
+async fn write_rows<W: Write>(
+    out: &mut W,
+    conn: &InitiatedConnection,
+    table: &str,
+    generated: &[String],
+    options: &DumpOptions,
+    report: &mut DumpReport,
+    progress: &impl Fn(&DumpReport),
+) -> Result<()> {
+    let dialect = &conn.config.dialect;
+    let source = TransferSource::Table(table.to_string());
+    let mut pages = Pages::new(conn, &source, PAGE_ROWS).await?;
+    while let Some(page) = pages.next().await? {
+        let positions: Vec<usize> = (0..page.result_columns.len())
+            .filter(|i| !generated.contains(&page.result_columns[*i].name))
+            .collect();
+        let count = page.rows.len();
+        if count > 0 && !positions.is_empty() {
+            let columns = positions
+                .iter()
+                .map(|i| page.result_columns[*i].clone())
+                .collect();
+            let inserts =
+                Inserts::new(dialect, table, columns, vec![], false)?.overriding_system_value();
+            let rows = page.rows.into_iter().map(|row| {
+                Ok(positions
+                    .iter()
+                    .map(|i| row.get(*i).cloned().unwrap_or_default())
+                    .collect())
+            });
+            write(&mut *out, &inserts, rows, options.batch_size)?;
+            writeln!(out)?;
+        }
+        report.rows += count as u64;
+        progress(report);
+    }
+    Ok(())
+}
+
+/// Writes a schema, or some of its tables, as a SQL script in the
+/// connection's dialect: extensions, types and routines, tables, their rows,
+/// then indices and foreign keys, then routines using the tables' row types,
+/// views and triggers. Restoring it replays the script.
+pub async fn dump(
+    conn: &InitiatedConnection,
+    destination: &str,
+    options: &DumpOptions,
+    progress: impl Fn(&DumpReport),
+) -> Result<DumpReport> {
+    let dialect = &conn.config.dialect;
+    let objects: Vec<SchemaObject> = conn
+        .get_objects()
+        .await?
+        .into_iter()
+        .filter(|o| match (options.tables.is_empty(), o.object_type) {
+            (true, _) => true,
+            (false, ObjectType::Table) => options.tables.contains(&o.name),
+            (false, ObjectType::Trigger) => {
+                o.table.as_ref().is_some_and(|t| options.tables.contains(t))
+            }
+            // the tables' columns may be of these
+            (false, ObjectType::Extension | ObjectType::Type) => true,
+            (false, _) => false,
+        })
+        .collect();
+    let tables: Vec<&SchemaObject> = objects
+        .iter()
+        .filter(|o| o.object_type == ObjectType::Table)
+        .collect();
+    if let Some(missing) = options
+        .tables
+        .iter()
+        .find(|t| !tables.iter().any(|o| &&o.name == t))
+    {
+        bail!("{} is not a table of {}", missing, conn.get_schema());
+    }
+
+    let mut out = BufWriter::new(File::create(destination)?);
+    writeln!(
+        out,
+        "-- Dump of {} ({:?})\n-- {}\n",
+        conn.get_schema(),
+        dialect,
+        chrono::Utc::now().to_rfc3339()
+    )?;
+    match dialect {
+        Dialect::Mysql | Dialect::MariaDB => {
+            statement(&mut out, "SET NAMES utf8mb4")?;
+            statement(&mut out, "SET FOREIGN_KEY_CHECKS = 0")?;
+        }
+        // only takes effect in a transaction, which restores run in
+        Dialect::Sqlite => statement(&mut out, "PRAGMA defer_foreign_keys = ON")?,
+        // functions are created before the tables their bodies may use
+        Dialect::Postgresql => statement(&mut out, "SET check_function_bodies = false")?,
+        Dialect::ClickHouse => {}
+    }
+
+    // tables may be of extensions' types and call functions in their defaults
+    // and checks. Bodies aren't checked, signatures are, so routines taking or
+    // returning a table's rows come after the tables
+    let (typed, untyped): (Vec<SchemaObject>, Vec<SchemaObject>) =
+        objects.iter().cloned().partition(|o| {
+            matches!(o.object_type, ObjectType::Function | ObjectType::Procedure)
+                && o.table.is_some()
+        });
+    let routines = [ObjectType::Function, ObjectType::Procedure];
+    let mut report = DumpReport::default();
+    if options.schema {
+        let object_types = [
+            ObjectType::Extension,
+            ObjectType::Type,
+            ObjectType::Function,
+            ObjectType::Procedure,
+        ];
+        write_objects(
+            &mut out,
+            conn,
+            &untyped,
+            &object_types,
+            &mut report,
+            &progress,
+        )
+        .await?;
+    }
+
+    let mut ddls = vec![];
+    for table in &tables {
+        let ddl = conn.get_table_ddl(&table.name).await?;
+        if options.schema {
+            writeln!(out, "-- Table {}\n", table.name)?;
+            for sequence in &ddl.sequences {
+                statement(&mut out, sequence)?;
+            }
+            statement(&mut out, &ddl.create)?;
+            for owned in &ddl.owned {
+                statement(&mut out, owned)?;
+            }
+            for comment in &ddl.comments {
+                statement(&mut out, comment)?;
+            }
+        }
+        ddls.push(ddl);
+    }
+
+    for (table, ddl) in tables.iter().zip(&ddls) {
+        // rows of partitioned tables are written with their partitions
+        if options.data && !ddl.partitioned {
+            report.table = Some(table.name.clone());
+            progress(&report);
+            writeln!(out, "-- Rows of {}\n", table.name)?;
+            write_rows(
+                &mut out,
+                conn,
+                &table.name,
+                &ddl.generated,
+                options,
+                &mut report,
+                &progress,
+            )
+            .await?;
+        }
+        report.tables += 1;
+    }
+    report.table = None;
+
+    // foreign keys may reference unique indices of other tables
+    let positions = ddls.iter().flat_map(|ddl| &ddl.positions);
+    let indices = ddls.iter().flat_map(|ddl| &ddl.indices);
+    let foreign_keys = ddls.iter().flat_map(|ddl| &ddl.foreign_keys);
+    let constraints = indices.chain(foreign_keys).filter(|_| options.schema);
+    for s in positions.chain(constraints) {
+        statement(&mut out, s)?;
+    }
+    if options.schema {
+        write_objects(&mut out, conn, &typed, &routines, &mut report, &progress).await?;
+        let object_types = [
+            ObjectType::View,
+            ObjectType::MaterializedView,
+            ObjectType::Trigger,
+        ];
+        write_objects(
+            &mut out,
+            conn,
+            &objects,
+            &object_types,
+            &mut report,
+            &progress,
+        )
+        .await?;
+    }
+
+    if matches!(dialect, Dialect::Mysql | Dialect::MariaDB) {
+        statement(&mut out, "SET FOREIGN_KEY_CHECKS = 1")?;
+    }
+    out.flush()?;
+    progress(&report);
+    Ok(report)
+}
@@ -0,0 +1,145 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::read_rows;
+use crate::{engine::types::result::unique_names, utils::fs::read_result_columns};
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonFormat {
+    /// One JSON array holding every row
+    #[default]
+    Array,
+    /// One row per line, see https://github.com/ndjson/ndjson-spec
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowShape {
+    /// Objects keyed by column name, repeated names get a suffix
+    #[default]
+    Object,
+    /// Arrays in column order
+    Array,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct JsonOptions {
+    pub format: JsonFormat,
+    /// Indents rows, NDJSON keeps one row per line regardless
+    pub pretty: bool,
+    pub rows: RowShape,
+}
+
+/// Writes rows as JSON to `out`. Exact numbers are written as strings holding
+/// their digits and binary values as `0x` hex.
+pub fn write<W: Write>(
+    mut out: W,
+    names: &[String],
+    rows: impl Iterator<Item = Result<Vec<Value>>>,
+    options: &JsonOptions,
+) -> Result<W> {
+    let pretty = options.pretty && options.format == JsonFormat::Array;
+    if options.format == JsonFormat::Array {
+        out.write_all(b"[")?;
+    }
+    for (i, row) in rows.enumerate() {
+        let row = row?;
+        let row = match options.rows {
+            RowShape::Object => {
+                Value::Object(names.iter().cloned().zip(row).collect::<Map<_, _>>())
+            }
+            RowShape::Array => Value::Array(row),
+        };
+        let text = match pretty {
+            true => serde_json::to_string_pretty(&row)?.replace('\n', "\n  "),
+            false => serde_json::to_string(&row)?,
+        };
+        let separator = match (options.format, i) {
+            (JsonFormat::Ndjson, _) => "",
+            (JsonFormat::Array, 0) => "\n  ",
+            (JsonFormat::Array, _) => ",\n  ",
+        };
+        out.write_all(separator.as_bytes())?;
+        out.write_all(text.as_bytes())?;
+        if options.format == JsonFormat::Ndjson {
+            out.write_all(b"\n")?;
+        }
+    }
+    if options.format == JsonFormat::Array {
+        out.write_all(b"\n]\n")?;
+    }
+    out.flush()?;
+    Ok(out)
+}
+
+/// Exports a stored result file row by row. Empty results are an error rather
+/// than an empty file.
+pub fn export(source: &str, destination: &str, options: &JsonOptions) -> Result<()> {
+    let names = unique_names(&read_result_columns(source)?);
+    let mut rows = read_rows(source)?.peekable();
+    if rows.peek().is_none() {
+        bail!("The results have no rows to export");
+    }
+    let out = BufWriter::new(File::create(destination)?);
+    write(out, &names, rows, options)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{write, JsonFormat, JsonOptions, RowShape};
+
+    #[test]
+    fn test_write() {
+        let names = vec!["id".to_string(), "id_2".to_string()];
+        let rows = || {
+            vec![
+                Ok(vec![json!("1"), json!("a")]),
+                Ok(vec![json!(2), json!(null)]),
+            ]
+            .into_iter()
+        };
+
+        let out = write(vec![], &names, rows(), &JsonOptions::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[\n  {\"id\":\"1\",\"id_2\":\"a\"},\n  {\"id\":2,\"id_2\":null}\n]\n"
+        );
+
+        let options = JsonOptions {
+            pretty: true,
+            rows: RowShape::Array,
+            ..JsonOptions::default()
+        };
+        let out = write(vec![], &names, rows(), &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "[\n  [\n    \"1\",\n    \"a\"\n  ],\n  [\n    2,\n    null\n  ]\n]\n"
+        );
+
+        let options = JsonOptions {
+            format: JsonFormat::Ndjson,
+            pretty: true,
+            ..JsonOptions::default()
+        };
+        let out = write(vec![], &names, rows(), &options).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "{\"id\":\"1\",\"id_2\":\"a\"}\n{\"id\":2,\"id_2\":null}\n"
+        );
+
+        let out = write(vec![], &names, std::iter::empty(), &JsonOptions::default()).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "[\n]\n");
+    }
+}
@@ -0,0 +1,24 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::Result;
+use serde_json::Value;
+
+pub mod columnar;
+pub mod csv;
+pub mod dump;
+pub mod json;
+pub mod sql;
+pub mod table;
+pub mod xlsx;
+
+/// Streams the rows of a result file written by `write_query`, one line per row.
+pub fn read_rows(path: &str) -> Result<impl Iterator<Item = Result<Vec<Value>>>> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(reader
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .map(|line| Ok(serde_json::from_str(&line?)?)))
+}
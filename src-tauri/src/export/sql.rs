@@ -0,0 +1,164 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::read_rows;
+use crate::{
+    engine::types::{config::Dialect, inserts::Inserts},
+    utils::fs::{read_result_columns, read_table_metadata},
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SqlOptions {
+    /// Rows per `INSERT` statement
+    pub batch_size: usize,
+    /// Writes statements that update rows already present, by primary key
+    pub upsert: bool,
+    /// Inserts into this table instead of the one the result was read from
+    pub table: Option<String>,
+}
+
+impl Default for SqlOptions {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            upsert: false,
+            table: None,
+        }
+    }
+}
+
+/// Writes rows as batches of `INSERT` statements to `out`.
+pub fn write<W: Write>(
+    mut out: W,
+    inserts: &Inserts,
+    rows: impl Iterator<Item = Result<Vec<Value>>>,
+    batch_size: usize,
+) -> Result<W> {
+    let mut batch = Vec::with_capacity(batch_size);
+    for row in rows {
+        batch.push(row?);
+        if batch.len() >= batch_size.max(1) {
+            out.write_all(inserts.statement(&batch).as_bytes())?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        out.write_all(inserts.statement(&batch).as_bytes())?;
+    }
+    out.flush()?;
+    Ok(out)
+}
+
+/// Exports a stored result file as `INSERT` statements into the table it was
+/// read from. Writes to `destination` or, without one, returns the statements.
+pub fn export(
+    source: &str,
+    destination: Option<&str>,
+    dialect: &Dialect,
+    options: &SqlOptions,
+) -> Result<Option<String>> {
+    let metadata = read_table_metadata(source)?;
+    let table = options
+        .table
+        .as_deref()
+        .or(metadata.as_ref().map(|m| m.table.as_str()))
+        .ok_or_else(|| anyhow!("The results are not from a table, choose one to insert into"))?;
+    let key = metadata
+        .as_ref()
+        .map(|m| {
+            m.primary_key_columns()
+                .iter()
+                .map(|k| k.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // computed columns are left out when the table columns are known
+    let columns = read_result_columns(source)?;
+    let from_table = columns.iter().any(|c| c.column.is_some());
+    let positions: Vec<usize> = (0..columns.len())
+        .filter(|i| !from_table || columns[*i].column.is_some())
+        .collect();
+    if positions.is_empty() {
+        bail!("The results have no columns to insert");
+    }
+    let columns = positions.iter().map(|i| columns[*i].clone()).collect();
+    let rows = read_rows(source)?.map(|row| {
+        let row = row?;
+        Ok(positions
+            .iter()
+            .map(|i| row.get(*i).cloned().unwrap_or_default())
+            .collect())
+    });
+
+    let inserts = Inserts::new(dialect, table, columns, key, options.upsert)?;
+    match destination {
+        Some(destination) => {
+            let out = BufWriter::new(File::create(destination)?);
+            write(out, &inserts, rows, options.batch_size)?;
+            Ok(None)
+        }
+        None => {
+            let out = write(vec![], &inserts, rows, options.batch_size)?;
+            Ok(Some(String::from_utf8(out)?))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::write;
+    use crate::engine::types::{config::Dialect, inserts::Inserts, result::ResultColumn};
+
+    fn columns() -> Vec<ResultColumn> {
+        vec![
+            ResultColumn::new("id", "INT8", None),
+            ResultColumn::new("name", "TEXT", None),
+            ResultColumn::new("tags", "TEXT[]", None),
+        ]
+    }
+
+    #[test]
+    fn test_write() {
+        let rows = vec![
+            Ok(vec![json!("1"), json!("O'Brien \\"), json!(["a"])]),
+            Ok(vec![json!("2"), json!(null), json!([])]),
+            Ok(vec![json!("3"), json!("0x00ff"), json!(null)]),
+        ];
+
+        let inserts = Inserts::new(
+            &Dialect::Postgresql,
+            "users",
+            columns(),
+            vec!["id".to_string()],
+            true,
+        )
+        .unwrap();
+        let out = write(vec![], &inserts, rows.into_iter().take(2), 1).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "INSERT INTO \"users\" (\"id\", \"name\", \"tags\") VALUES\n  (1, 'O''Brien \\', ARRAY['a']::TEXT[])\nON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\", \"tags\" = EXCLUDED.\"tags\";\n\
+             INSERT INTO \"users\" (\"id\", \"name\", \"tags\") VALUES\n  (2, NULL, ARRAY[]::TEXT[])\nON CONFLICT (\"id\") DO UPDATE SET \"name\" = EXCLUDED.\"name\", \"tags\" = EXCLUDED.\"tags\";\n"
+        );
+
+        let rows = vec![
+            Ok(vec![json!(1), json!("O'Brien \\"), json!(["a"])]),
+            Ok(vec![json!(3), json!("0x00ff"), json!(true)]),
+        ];
+        let inserts = Inserts::new(&Dialect::Mysql, "users", columns(), vec![], true).unwrap();
+        let out = write(vec![], &inserts, rows.into_iter(), 100).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "INSERT INTO `users` (`id`, `name`, `tags`) VALUES\n  (1, 'O''Brien \\\\', '[\"a\"]'),\n  (3, '0x00ff', TRUE)\nON DUPLICATE KEY UPDATE `id` = VALUES(`id`), `name` = VALUES(`name`), `tags` = VALUES(`tags`);\n"
+        );
+    }
+}
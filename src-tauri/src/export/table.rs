@@ -0,0 +1,276 @@
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::read_rows;
+use crate::{
+    engine::types::{
+        kind::Kind,
+        value::{self, cell_text},
+    },
+    utils::fs::read_result_columns,
+};
+
+const NULL: &str = "NULL";
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TableFormat {
+    /// A GitHub flavoured Markdown table
+    #[default]
+    Markdown,
+    Html,
+    /// Box drawn with `+`, `-` and `|`, as psql and mysql print results
+    Ascii,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TableOptions {
+    pub format: TableFormat,
+    /// The first row to render, counting from 0
+    pub first_row: usize,
+    /// How many rows to render, all of them by default
+    pub row_count: Option<usize>,
+    /// Positions of the columns to render, in the order given
+    pub columns: Option<Vec<usize>>,
+}
+
+/// The rows and columns selected for rendering, cells already turned to text.
+pub struct Table {
+    pub names: Vec<String>,
+    pub rows: Vec<Vec<Option<String>>>,
+    /// Columns holding only numbers, which are aligned right
+    pub numeric: Vec<bool>,
+}
+
+impl Table {
+    pub fn new(names: Vec<String>, kinds: &[Kind], rows: &[Vec<Value>]) -> Self {
+        let numeric = (0..names.len())
+            .map(|i| {
+                let kind = kinds.get(i).copied().unwrap_or_default();
+                let mut values = rows
+                    .iter()
+                    .filter_map(|r| r.get(i))
+                    .filter(|v| !v.is_null());
+                let first = values.next();
+                first.is_some()
+                    && first
+                        .into_iter()
+                        .chain(values)
+                        .all(|v| v.is_number() || value::exact_str(kind, v).is_some())
+            })
+            .collect();
+        let rows = rows
+            .iter()
+            .map(|r| {
+                (0..names.len())
+                    .map(|i| r.get(i).and_then(cell_text))
+                    .collect()
+            })
+            .collect();
+        Self {
+            names,
+            rows,
+            numeric,
+        }
+    }
+
+    pub fn render(&self, format: TableFormat) -> String {
+        match format {
+            TableFormat::Markdown => self.markdown(),
+            TableFormat::Html => self.html(),
+            TableFormat::Ascii => self.ascii(),
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('\\', "\\\\")
+                .replace('|', "\\|")
+                .replace("\r\n", "<br>")
+                .replace('\n', "<br>")
+        };
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let mut out = line(self.names.iter().map(|n| escape(n)).collect());
+        out += &line(
+            self.numeric
+                .iter()
+                .map(|n| if *n { "---:" } else { "---" }.to_string())
+                .collect(),
+        );
+        for row in &self.rows {
+            out += &line(
+                row.iter()
+                    .map(|c| c.as_deref().map_or(NULL.to_string(), escape))
+                    .collect(),
+            );
+        }
+        out
+    }
+
+    fn html(&self) -> String {
+        let escape = |s: &str| {
+            s.replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+                .replace('\'', "&#39;")
+        };
+        let mut out = String::from("<table>\n  <thead>\n    <tr>\n");
+        for name in &self.names {
+            out += &format!("      <th>{}</th>\n", escape(name));
+        }
+        out += "    </tr>\n  </thead>\n  <tbody>\n";
+        for row in &self.rows {
+            out += "    <tr>\n";
+            for (cell, numeric) in row.iter().zip(&self.numeric) {
+                let align = if *numeric {
+                    " style=\"text-align: right\""
+                } else {
+                    ""
+                };
+                let text = match cell {
+                    Some(text) => escape(text),
+                    None => format!("<em>{}</em>", NULL),
+                };
+                out += &format!("      <td{}>{}</td>\n", align, text);
+            }
+            out += "    </tr>\n";
+        }
+        out + "  </tbody>\n</table>\n"
+    }
+
+    fn ascii(&self) -> String {
+        // line breaks and tabs would break the box, they are shown escaped
+        let cell = |c: &Option<String>| match c {
+            Some(text) => text
+                .replace('\r', "\\r")
+                .replace('\n', "\\n")
+                .replace('\t', "\\t"),
+            None => NULL.to_string(),
+        };
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|r| r.iter().map(cell).collect())
+            .collect();
+        let widths: Vec<usize> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                rows.iter()
+                    .map(|r| r[i].chars().count())
+                    .chain([name.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let border = widths
+            .iter()
+            .map(|w| "-".repeat(w + 2))
+            .collect::<Vec<String>>()
+            .join("+");
+        let border = format!("+{}+\n", border);
+        let line = |cells: &[String], align: &dyn Fn(usize) -> bool| {
+            let cells: Vec<String> = cells
+                .iter()
+                .enumerate()
+                .map(|(i, c)| match align(i) {
+                    true => format!(" {:>w$} ", c, w = widths[i]),
+                    false => format!(" {:<w$} ", c, w = widths[i]),
+                })
+                .collect();
+            format!("|{}|\n", cells.join("|"))
+        };
+        let mut out = border.clone() + &line(&self.names, &|_| false) + &border;
+        for row in &rows {
+            out += &line(row, &|i| self.numeric[i]);
+        }
+        if !rows.is_empty() {
+            out += &border;
+        }
+        out
+    }
+}
+
+/// Renders the selected rows and columns of a stored result file. Only the
+/// selected rows are kept in memory.
+pub fn render(source: &str, options: &TableOptions) -> Result<String> {
+    let columns = read_result_columns(source)?;
+    let positions: Vec<usize> = match &options.columns {
+        Some(positions) => positions.clone(),
+        None => (0..columns.len()).collect(),
+    };
+    if let Some(p) = positions.iter().find(|p| **p >= columns.len()) {
+        bail!("The results have no column {}", p + 1);
+    }
+    if positions.is_empty() {
+        bail!("There are no columns to render");
+    }
+    let names = positions.iter().map(|p| columns[*p].name.clone()).collect();
+    let kinds: Vec<Kind> = positions.iter().map(|p| columns[*p].kind).collect();
+    let rows = read_rows(source)?
+        .skip(options.first_row)
+        .take(options.row_count.unwrap_or(usize::MAX))
+        .map(|row| {
+            let row = row?;
+            Ok(positions
+                .iter()
+                .map(|p| row.get(*p).cloned().unwrap_or_default())
+                .collect())
+        })
+        .collect::<Result<Vec<Vec<Value>>>>()?;
+    Ok(Table::new(names, &kinds, &rows).render(options.format))
+}
+
+/// Writes the rendered table to `destination` or, without one, returns it.
+pub fn export(
+    source: &str,
+    destination: Option<&str>,
+    options: &TableOptions,
+) -> Result<Option<String>> {
+    let table = render(source, options)?;
+    match destination {
+        Some(destination) => {
+            std::fs::write(destination, table)?;
+            Ok(None)
+        }
+        None => Ok(Some(table)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{Table, TableFormat};
+    use crate::engine::types::kind::Kind;
+
+    #[test]
+    fn test_render() {
+        let names = vec!["id".to_string(), "note".to_string()];
+        let rows = vec![
+            vec![json!(1), json!("a|b\nc")],
+            vec![json!("10"), json!(null)],
+        ];
+        let table = Table::new(names, &[Kind::Integer, Kind::Text], &rows);
+        assert_eq!(
+            table.render(TableFormat::Markdown),
+            "| id | note |\n| ---: | --- |\n| 1 | a\\|b<br>c |\n| 10 | NULL |\n"
+        );
+        assert_eq!(
+            table.render(TableFormat::Ascii),
+            "+----+--------+\n\
+             | id | note   |\n\
+             +----+--------+\n\
+             |  1 | a|b\\nc |\n\
+             | 10 | NULL   |\n\
+             +----+--------+\n"
+        );
+        assert!(table
+            .render(TableFormat::Html)
+            .contains("<td style=\"text-align: right\">10</td>\n      <td><em>NULL</em></td>"));
+    }
+}
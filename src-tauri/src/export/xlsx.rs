@@ -0,0 +1,180 @@
+use anyhow::{bail, Result};
+use rust_xlsxwriter::{ColNum, Format, RowNum, Workbook, Worksheet};
+use serde_json::Value;
+
+use super::read_rows;
+use crate::{
+    engine::types::{
+        result::ResultColumn,
+        value::{self, cell_text, is_temporal, temporal, Temporal},
+    },
+    utils::fs::{read_result_columns, read_table_metadata},
+};
+
+/// Excel keeps 15 significant digits, longer numbers are written as text.
+const MAX_DIGITS: usize = 15;
+const MAX_WIDTH: usize = 60;
+
+/// Exact numbers become numbers when Excel can hold all their digits.
+fn exact_number(digits: &str) -> Option<f64> {
+    let significant = digits
+        .trim_start_matches('-')
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    match significant.trim_start_matches('0').len() <= MAX_DIGITS {
+        true => digits.parse().ok(),
+        false => None,
+    }
+}
+
+struct Formats {
+    header: Format,
+    date: Format,
+    datetime: Format,
+    time: Format,
+}
+
+impl Formats {
+    fn new() -> Self {
+        Self {
+            header: Format::new().set_bold(),
+            date: Format::new().set_num_format("yyyy-mm-dd"),
+            datetime: Format::new().set_num_format("yyyy-mm-dd hh:mm:ss"),
+            time: Format::new().set_num_format("hh:mm:ss"),
+        }
+    }
+}
+
+/// Writes a cell with its native type and returns the number of characters it
+/// shows, for sizing the column.
+fn write_cell(
+    sheet: &mut Worksheet,
+    formats: &Formats,
+    (row, col): (RowNum, ColNum),
+    column: &ResultColumn,
+    v: &Value,
+) -> Result<usize> {
+    let number = match v {
+        // 64-bit integers can have more digits than Excel keeps
+        Value::Number(n) if !n.is_f64() => exact_number(&n.to_string()),
+        Value::Number(n) => n.as_f64(),
+        v => value::exact_str(column.kind, v).and_then(exact_number),
+    };
+    if let Some(n) = number {
+        sheet.write_number(row, col, n)?;
+        return Ok(n.to_string().len());
+    }
+    match v {
+        Value::Null => Ok(0),
+        Value::Bool(b) => {
+            sheet.write_boolean(row, col, *b)?;
+            Ok(5)
+        }
+        Value::String(s) if is_temporal(&column.type_name) => match temporal(s) {
+            Some(Temporal::Date(d)) => {
+                sheet.write_datetime_with_format(row, col, d, &formats.date)?;
+                Ok(10)
+            }
+            // Excel has no time zones, zoned values keep their local time
+            Some(Temporal::DateTime(d)) | Some(Temporal::Zoned(d, _)) => {
+                sheet.write_datetime_with_format(row, col, d, &formats.datetime)?;
+                Ok(19)
+            }
+            Some(Temporal::Time(t)) => {
+                sheet.write_datetime_with_format(row, col, t, &formats.time)?;
+                Ok(8)
+            }
+            None => {
+                sheet.write_string(row, col, s)?;
+                Ok(s.chars().count())
+            }
+        },
+        v => {
+            // text keeps leading zeros and long numbers as they are
+            let text = cell_text(v).unwrap_or_default();
+            sheet.write_string(row, col, &text)?;
+            Ok(text.lines().map(|l| l.chars().count()).max().unwrap_or(0))
+        }
+    }
+}
+
+/// Writes one result file to a sheet with a frozen header row.
+fn write_sheet(sheet: &mut Worksheet, formats: &Formats, source: &str) -> Result<()> {
+    let columns = read_result_columns(source)?;
+    let mut widths: Vec<usize> = columns.iter().map(|c| c.name.chars().count()).collect();
+    for (col, column) in columns.iter().enumerate() {
+        sheet.write_string_with_format(0, col as ColNum, &column.name, &formats.header)?;
+    }
+    sheet.set_freeze_panes(1, 0)?;
+
+    for (i, row) in read_rows(source)?.enumerate() {
+        let row_num = i as RowNum + 1;
+        for (col, (column, v)) in columns.iter().zip(row?.iter()).enumerate() {
+            let width = write_cell(sheet, formats, (row_num, col as ColNum), column, v)?;
+            widths[col] = widths[col].max(width);
+        }
+    }
+    for (col, width) in widths.into_iter().enumerate() {
+        sheet.set_column_width(col as ColNum, (width.min(MAX_WIDTH) + 2) as f64)?;
+    }
+    Ok(())
+}
+
+/// Sheet names are at most 31 characters, unique, and without `[]:*?/\`.
+fn sheet_name(name: &str, taken: &[String]) -> String {
+    let clean: String = name
+        .chars()
+        .filter(|c| !"[]:*?/\\".contains(*c))
+        .take(31)
+        .collect();
+    let mut name = clean.clone();
+    let mut n = 1;
+    while name.is_empty() || taken.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+        n += 1;
+        let suffix = format!(" ({})", n);
+        name = clean.chars().take(31 - suffix.len()).collect::<String>() + &suffix;
+    }
+    name
+}
+
+/// Exports stored result files to a workbook, one sheet per result named after
+/// its table.
+pub fn export(sources: &[String], destination: &str) -> Result<()> {
+    if sources.is_empty() {
+        bail!("There are no results to export");
+    }
+    let mut workbook = Workbook::new();
+    let formats = Formats::new();
+    let mut names = vec![];
+    for (i, source) in sources.iter().enumerate() {
+        let table = read_table_metadata(source)?.map(|t| t.table);
+        let name = sheet_name(
+            &table.unwrap_or_else(|| format!("Result {}", i + 1)),
+            &names,
+        );
+        let sheet = workbook.add_worksheet_with_constant_memory();
+        sheet.set_name(&name)?;
+        write_sheet(sheet, &formats, source)?;
+        names.push(name);
+    }
+    workbook.save(destination)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{exact_number, sheet_name};
+
+    #[test]
+    fn test_cells() {
+        assert_eq!(exact_number("-0012.50"), Some(-12.5));
+        assert_eq!(exact_number("1234567890.12345"), Some(1234567890.12345));
+        assert_eq!(exact_number("18446744073709551615"), None);
+
+        let taken = vec!["users".to_string()];
+        assert_eq!(sheet_name("Users", &taken), "Users (2)");
+        assert_eq!(sheet_name("a/b", &taken), "ab");
+        assert_eq!(sheet_name(&"x".repeat(40), &taken), "x".repeat(31));
+    }
+}
@@ -1,8 +1,16 @@
 use crate::{
-    database::queries,
+    database::{
+        bundle::{self, BundlePreview, ImportAction, ImportSummary},
+        queries,
+    },
     engine::{
+        discovery::{self, files::Discovery},
+        health::{self, health_token_id},
         init::init_conn,
-        types::config::{ConnectionConfig, Credentials, Dialect, Metadata, Mode},
+        types::connection::ConnectionInfo,
+        types::config::{
+            ConnectionConfig, ConnectionFilter, Credentials, Dialect, Environment, Metadata, Mode,
+        },
     },
     handlers::task::cancel_task_token,
     state::ServiceAccess,
@@ -13,9 +21,11 @@ use crate::{
 };
 use anyhow::anyhow;
 use log::info;
-use tauri::{command, AppHandle};
+use std::{collections::HashMap, fs};
+use tauri::{command, AppHandle, Manager};
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn add_connection(
     app_handle: AppHandle,
     dialect: Dialect,
@@ -24,9 +34,18 @@ pub async fn add_connection(
     name: &str,
     color: &str,
     metadata: Metadata,
+    folder: Option<String>,
+    tags: Option<Vec<String>>,
+    environment: Option<Environment>,
 ) -> CommandResult<()> {
     info!("Add connection: {name}, {dialect}, {mode}, {color}");
-    let conn = ConnectionConfig::new(dialect, mode, credentials, name, color, metadata)?;
+    let conn = ConnectionConfig::new(dialect, mode, credentials, name, color, metadata)?
+        .organize(
+            folder,
+            tags.unwrap_or_default(),
+            environment.unwrap_or_default(),
+            None,
+        );
     let key = get_app_key(app_handle.clone())?;
     let pool = app_handle.db();
     queries::add_connection(pool, &conn, key)
@@ -35,6 +54,7 @@ pub async fn add_connection(
 }
 
 #[command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_connection(
     app_handle: AppHandle,
     id: String,
@@ -44,11 +64,26 @@ pub async fn update_connection(
     name: &str,
     color: &str,
     metadata: Metadata,
+    folder: Option<String>,
+    tags: Option<Vec<String>>,
+    environment: Option<Environment>,
 ) -> CommandResult<()> {
     info!("Update connection: {name}, {dialect}, {mode}, {color}");
-    let conn = ConnectionConfig::new(dialect, mode, credentials, name, color, metadata)?;
     let key = get_app_key(app_handle.clone())?;
     let pool = app_handle.db();
+    let current = queries::get_connection(pool, &id, &key).await?;
+    let keep_schema = dialect == Dialect::Postgresql && !credentials.contains_key("schema");
+    let mut conn = ConnectionConfig::new(dialect, mode, credentials, name, color, metadata)?
+        .organize(
+            folder.or(current.folder),
+            tags.unwrap_or(current.tags),
+            environment.unwrap_or(current.environment.clone()),
+            Some(&current.environment),
+        );
+    if keep_schema {
+        // the search path picked in the schema selector survives editing the connection
+        conn.schema = current.schema;
+    }
     queries::update_connection(pool, id, &conn, key)
         .await
         .map_err(Error::from)
@@ -85,10 +120,22 @@ pub async fn delete_connection(app_handle: AppHandle, id: String) -> CommandResu
 }
 
 #[command]
-pub async fn get_connections(app_handle: AppHandle) -> CommandResult<Vec<ConnectionConfig>> {
+pub async fn get_connections(
+    app_handle: AppHandle,
+    filter: Option<ConnectionFilter>,
+) -> CommandResult<Vec<ConnectionConfig>> {
     let key = get_app_key(app_handle.clone())?;
     let pool = app_handle.db();
-    queries::get_all_connections(pool, key)
+    queries::get_connections(pool, &filter.unwrap_or_default(), &key)
+        .await
+        .map_err(Error::from)
+}
+
+#[command]
+pub async fn reorder_connections(app_handle: AppHandle, ids: Vec<String>) -> CommandResult<()> {
+    info!("Reorder connections");
+    let pool = app_handle.db();
+    queries::reorder_connections(pool, &ids)
         .await
         .map_err(Error::from)
 }
@@ -108,7 +155,11 @@ pub async fn init_connection(
     let conn = init_conn(config.clone(), app_handle.clone()).await;
     match conn {
         Ok(c) => match app_handle.connect(&c) {
-            Ok(schema) => Ok(schema),
+            Ok(schema) => {
+                queries::touch_connection(app_handle.db(), &config.id.to_string()).await?;
+                health::spawn_monitor(app_handle.clone(), config.id.to_string()).await;
+                Ok(schema)
+            }
             Err(_) => {
                 cancel_task_token(app_handle.clone(), vec![config.id.to_string()]).await?;
                 Err(anyhow!("Failed to connect to the database").into())
@@ -124,11 +175,27 @@ pub async fn init_connection(
 #[command]
 pub async fn disconnect(mut app_handle: AppHandle, id: &str) -> CommandResult<()> {
     info!("Disconnect: {id}");
-    cancel_task_token(app_handle.clone(), vec![id.to_string()]).await?;
+    cancel_task_token(app_handle.clone(), vec![id.to_string(), health_token_id(id)]).await?;
     app_handle.disconnect(id)?;
     Ok(())
 }
 
+#[command]
+pub async fn ping_connection(app_handle: AppHandle, conn_id: String) -> CommandResult<u64> {
+    let conn = app_handle.acquire_connection(conn_id);
+    let latency = conn.ping().await?;
+    Ok(latency.as_millis() as u64)
+}
+
+#[command]
+pub async fn get_connection_info(
+    app_handle: AppHandle,
+    conn_id: String,
+) -> CommandResult<ConnectionInfo> {
+    let conn = app_handle.acquire_connection(conn_id);
+    Ok(conn.get_connection_info().await?)
+}
+
 #[command]
 pub async fn set_schema(
     mut app_handle: AppHandle,
@@ -143,9 +210,9 @@ pub async fn set_schema(
     let conn = init_conn(conn.config.clone(), app_handle.clone()).await;
     match conn {
         Ok(c) => match app_handle.connect(&c) {
-            Ok(schema) => {
+            Ok(_) => {
                 let pool = app_handle.db();
-                queries::update_connection_schema(pool, &conn_id, &schema).await?;
+                queries::update_connection_schema(pool, &conn_id, &c.config.schema).await?;
                 Ok(app_handle.update_connection(c)?)
             }
             Err(_) => {
@@ -159,3 +226,69 @@ pub async fn set_schema(
         }
     }
 }
+
+#[command]
+pub async fn parse_connection_url(url: &str) -> CommandResult<ConnectionConfig> {
+    // the url may carry a password, so it is deliberately not logged
+    info!("Parse connection url");
+    discovery::url::parse_connection_url(url).map_err(Error::from)
+}
+
+#[command]
+pub async fn discover_connections(app_handle: AppHandle) -> CommandResult<Discovery> {
+    info!("Discover connections");
+    let home = app_handle.path().home_dir()?;
+    discovery::files::discover(&home).map_err(Error::from)
+}
+
+#[command]
+pub async fn export_connections(
+    app_handle: AppHandle,
+    ids: Vec<String>,
+    destination: &str,
+    passphrase: &str,
+    include_secrets: bool,
+    include_saved_queries: bool,
+) -> CommandResult<()> {
+    info!("Export {} connections to {destination}", ids.len());
+    let key = get_app_key(app_handle.clone())?;
+    let pool = app_handle.db();
+    let bundle =
+        bundle::build_bundle(pool, key, &ids, include_secrets, include_saved_queries).await?;
+    let content = bundle::encode_bundle(&bundle, passphrase)?;
+    fs::write(destination, content)?;
+    Ok(())
+}
+
+#[command]
+pub async fn inspect_bundle(
+    app_handle: AppHandle,
+    source: &str,
+    passphrase: &str,
+) -> CommandResult<BundlePreview> {
+    info!("Inspect bundle: {source}");
+    let content = fs::read_to_string(source)?;
+    let bundle = bundle::decode_bundle(&content, passphrase)?;
+    let key = get_app_key(app_handle.clone())?;
+    let pool = app_handle.db();
+    bundle::preview_bundle(pool, key, bundle)
+        .await
+        .map_err(Error::from)
+}
+
+#[command]
+pub async fn import_bundle(
+    app_handle: AppHandle,
+    source: &str,
+    passphrase: &str,
+    actions: HashMap<uuid::Uuid, ImportAction>,
+) -> CommandResult<ImportSummary> {
+    info!("Import bundle: {source}");
+    let content = fs::read_to_string(source)?;
+    let bundle = bundle::decode_bundle(&content, passphrase)?;
+    let key = get_app_key(app_handle.clone())?;
+    let pool = app_handle.db();
+    bundle::import_bundle(pool, key, bundle, actions)
+        .await
+        .map_err(Error::from)
+}
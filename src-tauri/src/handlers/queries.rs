@@ -1,5 +1,20 @@
 use crate::{
     database::QueryType,
+    engine::{types::ddl::ObjectType, wkt},
+    export::{
+        self,
+        columnar::ParquetOptions,
+        csv::CsvOptions,
+        dump::{self, DumpOptions, DumpReport},
+        json::JsonOptions,
+        sql::SqlOptions,
+        table::TableOptions,
+    },
+    import::{
+        self, restore,
+        transfer::{self, TransferSource},
+        ImportOptions, ImportReport, LoadOptions,
+    },
     query::{Events, QueryTask, QueryTaskEnqueueResult, QueryTaskResult, QueryTaskStatus},
     state::{AppState, ServiceAccess},
     utils::{
@@ -15,7 +30,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use sqlparser::{ast::Statement, dialect::dialect_from_str, parser::Parser};
 use std::str;
-use std::{fs::read_to_string, path::PathBuf};
+use std::fs::read_to_string;
 use tauri::{command, AppHandle, Emitter, Manager, State};
 use tokio_util::sync::CancellationToken;
 
@@ -88,6 +103,12 @@ pub async fn sql_to_statements(_dialect: String, sql: &str) -> CommandResult<Vec
     })
 }
 
+/// Geometries are shown as (E)WKT, this gives the GeoJSON of one.
+#[command]
+pub async fn geometry_to_geojson(wkt: String) -> CommandResult<Value> {
+    Ok(wkt::to_geojson(&wkt)?)
+}
+
 #[command]
 pub async fn enqueue_query(
     app_handle: AppHandle,
@@ -137,6 +158,9 @@ pub async fn enqueue_query(
             vec![(statement, QueryType::Other, md5_hash(&id))]
         }
     };
+    if conn.config.metadata.read_only && statements.iter().any(|s| !s.1.is_read_only()) {
+        return Err(Error::ReadOnly);
+    }
     let mut binding = state.cancel_tokens.lock().await;
     for (idx, stmt) in statements.iter().enumerate() {
         let temp_dir = app_handle
@@ -161,7 +185,8 @@ pub async fn enqueue_query(
                     match res {
                         Ok(mut result_set) => {
                             if let Some(table) = task.table.clone() {
-                                result_set.table = Some(task.conn.get_table_metadata(&table).await.unwrap_or_default());
+                                let metadata = task.conn.get_table_metadata(&table).await;
+                                result_set.set_table(metadata.unwrap_or_default());
                             }
                             match write_query(&task.id, &result_set, task.query_type, temp_dir) {
                                 Ok(path) => {
@@ -221,6 +246,9 @@ pub async fn execute_tx(
         info!("Execute tx on {}, sql:{query}", conn_id.clone());
     }
     let connection = app_handle.acquire_connection(conn_id);
+    if connection.config.metadata.read_only {
+        return Err(Error::ReadOnly);
+    }
     connection.execute_tx(queries).await?;
     Ok(())
 }
@@ -252,6 +280,9 @@ pub async fn execute_query(
         }
         _ => (query.clone(), QueryType::Other),
     };
+    if conn.config.metadata.read_only && !query_type.is_read_only() {
+        return Err(Error::ReadOnly);
+    }
     let result = conn.execute_query(&sql, query_type).await?;
     Ok(json!(result))
 }
@@ -289,6 +320,17 @@ pub async fn get_table_structure(
     Ok(result)
 }
 
+#[command]
+pub async fn get_create_statement(
+    app_handle: AppHandle,
+    conn_id: String,
+    object_type: ObjectType,
+    name: String,
+) -> CommandResult<String> {
+    let connection = app_handle.acquire_connection(conn_id);
+    Ok(connection.get_create_statement(object_type, &name).await?)
+}
+
 #[command]
 pub async fn get_columns(app_handle: AppHandle, conn_id: String) -> CommandResult<Vec<Value>> {
     let connection = app_handle.acquire_connection(conn_id);
@@ -334,61 +376,242 @@ pub async fn get_procedures(app_handle: AppHandle, conn_id: String) -> CommandRe
 }
 
 #[command]
-pub async fn download_json(source: &str, destination: &str) -> CommandResult<()> {
-    let data = read_to_string(source)?;
-    let content: String = data
-        .lines()
-        .map(|line| format!("{},", line))
-        .collect::<Vec<String>>()
-        .join("\n");
-    let content = content[..content.len() - 1].to_string();
-    let content = format!("[\n{}\n]", content);
-
-    Ok(utils::fs::write_file(
-        &PathBuf::from(destination),
-        &content,
+pub async fn download_json(
+    source: &str,
+    destination: &str,
+    options: Option<JsonOptions>,
+) -> CommandResult<()> {
+    Ok(export::json::export(
+        source,
+        destination,
+        &options.unwrap_or_default(),
+    )?)
+}
+
+#[command]
+pub async fn download_csv(
+    source: &str,
+    destination: &str,
+    options: Option<CsvOptions>,
+) -> CommandResult<()> {
+    Ok(export::csv::export(
+        source,
+        destination,
+        &options.unwrap_or_default(),
+    )?)
+}
+
+#[command]
+pub async fn download_sql(
+    app_handle: AppHandle,
+    conn_id: String,
+    source: &str,
+    destination: Option<&str>,
+    options: Option<SqlOptions>,
+) -> CommandResult<Option<String>> {
+    let conn = app_handle.acquire_connection(conn_id);
+    Ok(export::sql::export(
+        source,
+        destination,
+        &conn.config.dialect,
+        &options.unwrap_or_default(),
+    )?)
+}
+
+#[command]
+pub async fn download_xlsx(sources: Vec<String>, destination: &str) -> CommandResult<()> {
+    Ok(export::xlsx::export(&sources, destination)?)
+}
+
+#[command]
+pub async fn download_parquet(
+    source: &str,
+    destination: &str,
+    options: Option<ParquetOptions>,
+) -> CommandResult<usize> {
+    Ok(export::columnar::export_parquet(
+        source,
+        destination,
+        &options.unwrap_or_default(),
     )?)
 }
 
 #[command]
-pub async fn download_csv(source: &str, destination: &str) -> CommandResult<()> {
-    let data = read_to_string(source)?;
-    let content: String = data
-        .lines()
-        .map(|line| format!("{},", line))
-        .collect::<Vec<String>>()
-        .join("\n");
-    let content = content[..content.len() - 1].to_string();
-    let content: Vec<Value> = serde_json::from_str(&format!("[{}]", content))?;
-    let keys = content[0]
-        .as_object()
-        .expect("Failed to get object")
-        .keys()
-        .map(|k| k.to_string())
-        .collect::<Vec<String>>();
-
-    let csv = keys.join(",") + "\n";
-    let rows = content
-        .iter()
-        .map(|row| {
-            keys.iter()
-                .map(|k| {
-                    row.get(k)
-                        .unwrap_or_else(|| panic!("Failed to get key {} from {}", k, row))
-                        .to_string()
-                })
-                .collect::<Vec<String>>()
-                .join(",")
-        })
-        .collect::<Vec<String>>()
-        .join("\n");
-
-    Ok(utils::fs::write_file(
-        &PathBuf::from(destination),
-        &format!("{}{}", csv, rows),
+pub async fn download_arrow(source: &str, destination: &str) -> CommandResult<usize> {
+    Ok(export::columnar::export_arrow(source, destination)?)
+}
+
+#[command]
+pub async fn download_table(
+    source: &str,
+    destination: Option<&str>,
+    options: Option<TableOptions>,
+) -> CommandResult<Option<String>> {
+    Ok(export::table::export(
+        source,
+        destination,
+        &options.unwrap_or_default(),
     )?)
 }
 
+/// Progress of an import or transfer, the report so far and the id it was
+/// started with.
+#[derive(Serialize, Clone)]
+struct ImportProgress<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    report: &'a ImportReport,
+}
+
+/// The columns of a file to import, for mapping them to the table's.
+#[command]
+pub async fn import_columns(
+    path: &str,
+    options: Option<ImportOptions>,
+) -> CommandResult<Vec<String>> {
+    let source = import::Source::open(path, &options.unwrap_or_default())?;
+    Ok(source.columns)
+}
+
+#[command]
+pub async fn import_file(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    conn_id: String,
+    id: String,
+    path: &str,
+    table: &str,
+    options: Option<ImportOptions>,
+) -> CommandResult<ImportReport> {
+    let conn = app_handle.acquire_connection(conn_id);
+    let options = options.unwrap_or_default();
+    if conn.config.metadata.read_only && !options.load.dry_run {
+        return Err(Error::ReadOnly);
+    }
+    let token = CancellationToken::new();
+    state.cancel_tokens.lock().await.insert(id.clone(), token.clone());
+    let progress = |report: &ImportReport| {
+        let payload = ImportProgress { id: &id, report };
+        if let Err(e) = app_handle.emit(Events::ImportProgress.as_str(), payload) {
+            info!("Failed to emit import_progress event: {e}");
+        }
+    };
+    let result = tokio::select! {
+        _ = token.cancelled() => Err(anyhow!("The import was cancelled")),
+        res = import::import(&conn, path, table, &options, progress) => res,
+    };
+    state.cancel_tokens.lock().await.remove(&id);
+    Ok(result?)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[command]
+pub async fn transfer_data(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    source_conn_id: String,
+    source: TransferSource,
+    target_conn_id: String,
+    table: &str,
+    id: String,
+    options: Option<LoadOptions>,
+) -> CommandResult<ImportReport> {
+    let source_conn = app_handle.acquire_connection(source_conn_id);
+    let target_conn = app_handle.acquire_connection(target_conn_id);
+    let options = options.unwrap_or_default();
+    if target_conn.config.metadata.read_only && !options.dry_run {
+        return Err(Error::ReadOnly);
+    }
+    let token = CancellationToken::new();
+    state.cancel_tokens.lock().await.insert(id.clone(), token.clone());
+    let progress = |report: &ImportReport| {
+        let payload = ImportProgress { id: &id, report };
+        if let Err(e) = app_handle.emit(Events::TransferProgress.as_str(), payload) {
+            info!("Failed to emit transfer_progress event: {e}");
+        }
+    };
+    let copy = transfer::transfer(&source_conn, &source, &target_conn, table, &options, progress);
+    let result = tokio::select! {
+        _ = token.cancelled() => Err(anyhow!("The transfer was cancelled")),
+        res = copy => res,
+    };
+    state.cancel_tokens.lock().await.remove(&id);
+    Ok(result?)
+}
+
+/// Progress of a dump, the report so far and the id it was started with.
+#[derive(Serialize, Clone)]
+struct DumpProgress<'a> {
+    id: &'a str,
+    #[serde(flatten)]
+    report: &'a DumpReport,
+}
+
+#[derive(Serialize, Clone)]
+struct RestoreProgress<'a> {
+    id: &'a str,
+    statements: usize,
+    total: usize,
+}
+
+#[command]
+pub async fn dump_schema(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    conn_id: String,
+    id: String,
+    destination: &str,
+    options: Option<DumpOptions>,
+) -> CommandResult<DumpReport> {
+    let conn = app_handle.acquire_connection(conn_id);
+    let options = options.unwrap_or_default();
+    let token = CancellationToken::new();
+    state.cancel_tokens.lock().await.insert(id.clone(), token.clone());
+    let progress = |report: &DumpReport| {
+        let payload = DumpProgress { id: &id, report };
+        if let Err(e) = app_handle.emit(Events::DumpProgress.as_str(), payload) {
+            info!("Failed to emit dump_progress event: {e}");
+        }
+    };
+    let result = tokio::select! {
+        _ = token.cancelled() => Err(anyhow!("The dump was cancelled")),
+        res = dump::dump(&conn, destination, &options, progress) => res,
+    };
+    state.cancel_tokens.lock().await.remove(&id);
+    Ok(result?)
+}
+
+#[command]
+pub async fn restore_script(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    conn_id: String,
+    id: String,
+    path: &str,
+) -> CommandResult<usize> {
+    let conn = app_handle.acquire_connection(conn_id);
+    if conn.config.metadata.read_only {
+        return Err(Error::ReadOnly);
+    }
+    let token = CancellationToken::new();
+    state.cancel_tokens.lock().await.insert(id.clone(), token.clone());
+    let progress = |statements: usize, total: usize| {
+        let payload = RestoreProgress {
+            id: &id,
+            statements,
+            total,
+        };
+        if let Err(e) = app_handle.emit(Events::RestoreProgress.as_str(), payload) {
+            info!("Failed to emit restore_progress event: {e}");
+        }
+    };
+    // the script connection is rolled back and closed when cancelled, so the
+    // token is handed down rather than the restore dropped
+    let result = restore::restore(&conn, path, progress, &token).await;
+    state.cancel_tokens.lock().await.remove(&id);
+    Ok(result?)
+}
+
 #[command]
 pub async fn invalidate_query(path: &str) -> CommandResult<()> {
     utils::fs::remove_dir(path)?;
@@ -0,0 +1,65 @@
+use std::{fs::File, io::Read};
+
+use anyhow::{anyhow, bail, Result};
+use serde_json::Value;
+
+use super::{Record, RowError, Source};
+use crate::export::csv::{CsvOptions, Encoding};
+
+/// Opens a delimited text file. Fields equal to the null text are read as
+/// nulls, everything else as strings for the column kinds to parse.
+pub fn open(path: &str, options: &CsvOptions) -> Result<Source> {
+    let mut bom = [0u8; 2];
+    let utf16 = File::open(path)?.read(&mut bom)? == 2 && bom == [0xff, 0xfe];
+    if utf16 || options.encoding == Encoding::Utf16Le {
+        bail!("UTF-16 files can't be imported, save the file as UTF-8");
+    }
+    let delimiter = u8::try_from(options.delimiter)
+        .ok()
+        .filter(u8::is_ascii)
+        .ok_or_else(|| anyhow!("The delimiter has to be an ASCII character"))?;
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(options.header)
+        .flexible(true)
+        .from_path(path)?;
+
+    // without a header row this is the first record, which is still read
+    let headers = reader.headers()?;
+    let columns: Vec<String> = match options.header {
+        true => headers.iter().map(|h| h.trim().to_string()).collect(),
+        false => (1..=headers.len())
+            .map(|i| format!("column_{}", i))
+            .collect(),
+    };
+    if columns.is_empty() {
+        bail!("The file has no columns");
+    }
+
+    let null = options.null.clone();
+    let width = columns.len();
+    let records = reader.into_records().enumerate().map(move |(i, record)| {
+        let row = i + 1;
+        let record = record.map_err(|e| RowError::new(row, None, e.to_string()))?;
+        if record.len() != width {
+            return Err(RowError::new(
+                row,
+                None,
+                format!("Expected {} fields, found {}", width, record.len()),
+            ));
+        }
+        let values = record
+            .iter()
+            .map(|field| match field == null {
+                true => Value::Null,
+                false => Value::String(field.to_string()),
+            })
+            .collect();
+        Ok(Record { row, values })
+    });
+    Ok(Source {
+        columns,
+        kinds: None,
+        records: Box::new(records),
+    })
+}
@@ -0,0 +1,34 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read},
+};
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::{ndjson, Source};
+
+/// Opens a file holding one JSON array of objects or arrays, as results are
+/// exported to JSON. The array is read whole, files that turn out to be one
+/// value per line are read as NDJSON instead.
+pub fn open(path: &str) -> Result<Source> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut first = [0u8; 1];
+    loop {
+        if reader.read(&mut first)? == 0 {
+            bail!("The file is empty");
+        }
+        if !first[0].is_ascii_whitespace() {
+            break;
+        }
+    }
+    if first[0] != b'[' {
+        return ndjson::open(path);
+    }
+    let values: Vec<Value> = serde_json::from_reader((&first[..]).chain(reader))?;
+    let lines = values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| (i + 1, Ok(value)));
+    ndjson::source(lines)
+}
@@ -0,0 +1,489 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    database::QueryType,
+    engine::types::{
+        config::Dialect,
+        connection::InitiatedConnection,
+        kind::{create_table, Kind},
+        result::{field, ResultColumn},
+    },
+    export::csv::CsvOptions,
+};
+
+pub mod csv;
+pub mod json;
+pub mod ndjson;
+pub mod parquet;
+pub mod restore;
+pub mod transfer;
+
+/// Rows read to infer the kinds of columns in text formats.
+pub const SAMPLE_ROWS: usize = 1000;
+/// Errors kept for the report, the rest are only counted.
+const MAX_REPORTED_ERRORS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    Csv,
+    Json,
+    Ndjson,
+    Parquet,
+}
+
+impl ImportFormat {
+    fn from_path(path: &str) -> Result<Self> {
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match extension.as_str() {
+            "csv" | "tsv" | "txt" => Ok(ImportFormat::Csv),
+            "json" => Ok(ImportFormat::Json),
+            "ndjson" | "jsonl" => Ok(ImportFormat::Ndjson),
+            "parquet" => Ok(ImportFormat::Parquet),
+            _ => bail!("Can't tell the format of {}, choose one", path),
+        }
+    }
+}
+
+/// How rows are written to the target table, for imports and transfers.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LoadOptions {
+    /// Target column for each source column, columns left out are skipped.
+    /// Columns are matched by name, ignoring case, without one.
+    pub mapping: Option<HashMap<String, String>>,
+    /// Creates the table from the source's columns when it doesn't exist
+    pub create_table: bool,
+    /// Reads and checks every row without writing anything
+    pub dry_run: bool,
+    pub batch_size: usize,
+    /// Rows that may fail before loading stops
+    pub max_errors: usize,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            mapping: None,
+            create_table: false,
+            dry_run: false,
+            batch_size: 1000,
+            max_errors: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ImportOptions {
+    /// Told by the file extension when missing
+    pub format: Option<ImportFormat>,
+    /// Defaults to comma separated, or tab separated for `.tsv` files
+    pub csv: Option<CsvOptions>,
+    #[serde(flatten)]
+    pub load: LoadOptions,
+}
+
+/// A row that could not be read, converted or written.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct RowError {
+    /// The row of the file counting from 1, without the header and blank lines
+    pub row: usize,
+    pub column: Option<String>,
+    pub message: String,
+}
+
+impl RowError {
+    pub fn new(row: usize, column: Option<&str>, message: String) -> Self {
+        Self {
+            row,
+            column: column.map(str::to_string),
+            message,
+        }
+    }
+}
+
+pub struct Record {
+    pub row: usize,
+    pub values: Vec<Value>,
+}
+
+/// The columns of a file and a stream of its rows. Formats with a schema give
+/// the kinds of their columns, the others have them inferred from a sample.
+pub struct Source {
+    pub columns: Vec<String>,
+    pub kinds: Option<Vec<Kind>>,
+    pub records: Box<dyn Iterator<Item = Result<Record, RowError>> + Send>,
+}
+
+impl Source {
+    pub fn open(path: &str, options: &ImportOptions) -> Result<Self> {
+        let format = match options.format {
+            Some(format) => format,
+            None => ImportFormat::from_path(path)?,
+        };
+        let mut source = match format {
+            ImportFormat::Csv => {
+                let csv_options = options.csv.clone().unwrap_or_else(|| CsvOptions {
+                    delimiter: if path.to_lowercase().ends_with(".tsv") {
+                        '\t'
+                    } else {
+                        ','
+                    },
+                    ..CsvOptions::default()
+                });
+                csv::open(path, &csv_options)?
+            }
+            ImportFormat::Json => json::open(path)?,
+            ImportFormat::Ndjson => ndjson::open(path)?,
+            ImportFormat::Parquet => parquet::open(path)?,
+        };
+        if source.kinds.is_none() {
+            let sample: Vec<_> = source.records.by_ref().take(SAMPLE_ROWS).collect();
+            let rows: Vec<&Record> = sample.iter().filter_map(|r| r.as_ref().ok()).collect();
+            source.kinds = Some(
+                (0..source.columns.len())
+                    .map(|i| Kind::infer(rows.iter().filter_map(|r| r.values.get(i))))
+                    .collect(),
+            );
+            source.records = Box::new(sample.into_iter().chain(source.records));
+        }
+        Ok(source)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImportReport {
+    pub rows_read: usize,
+    pub rows_imported: u64,
+    pub error_count: usize,
+    /// The first errors, in row order
+    pub errors: Vec<RowError>,
+    /// The statement creating the table, when it was or would be created
+    pub created_table: Option<String>,
+    /// Source columns that were not imported
+    pub unmapped: Vec<String>,
+    pub dry_run: bool,
+    /// Set when the import stopped for having too many errors
+    pub stopped: bool,
+}
+
+impl ImportReport {
+    fn error(&mut self, error: RowError) {
+        self.error_count += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(error);
+        }
+    }
+}
+
+/// A target column and how values for it are checked.
+#[derive(Clone)]
+struct Target {
+    column: ResultColumn,
+    kind: Kind,
+    /// Nulls are refused for `NOT NULL` columns without a default. MySQL fills
+    /// auto increment columns and ClickHouse the type's default instead.
+    required: bool,
+}
+
+fn target(dialect: &Dialect, row: &Value) -> Option<Target> {
+    let name = field(row, "column_name")?.as_str()?;
+    let type_name = field(row, "column_type")
+        .or_else(|| field(row, "data_type"))
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let nullable = match field(row, "is_nullable") {
+        Some(Value::String(s)) => s.eq_ignore_ascii_case("yes"),
+        Some(Value::Bool(b)) => *b,
+        _ => true,
+    };
+    let has_default = field(row, "column_default").is_some_and(|d| !d.is_null());
+    Some(Target {
+        column: ResultColumn::new(name, type_name, Some(nullable)),
+        kind: Kind::from_type_name(type_name),
+        required: !nullable
+            && !has_default
+            && matches!(dialect, Dialect::Postgresql | Dialect::Sqlite),
+    })
+}
+
+/// Checks rows against the columns of a table and writes them in batches with
+/// the dialect's bulk path. A batch that fails is retried row by row to find
+/// the rows at fault, except in ClickHouse where every row of it fails.
+struct Loader<'a> {
+    conn: &'a InitiatedConnection,
+    table: &'a str,
+    options: &'a LoadOptions,
+    /// The position in the source row and the target of each loaded column
+    plan: Vec<(usize, Target)>,
+    columns: Vec<ResultColumn>,
+    batch: Vec<Record>,
+    report: ImportReport,
+}
+
+impl<'a> Loader<'a> {
+    /// Matches source columns to the table's, creating the table from the
+    /// source columns first when it doesn't exist and that is asked for.
+    async fn new(
+        conn: &'a InitiatedConnection,
+        table: &'a str,
+        columns: &[String],
+        kinds: &[Kind],
+        options: &'a LoadOptions,
+    ) -> Result<Self> {
+        let dialect = &conn.config.dialect;
+        let mut report = ImportReport {
+            dry_run: options.dry_run,
+            ..Default::default()
+        };
+        let target_name = |source: &str| match &options.mapping {
+            Some(mapping) => mapping.get(source).cloned(),
+            None => Some(source.to_string()),
+        };
+        let mut targets: Vec<Target> = conn
+            .get_columns(Some(table))
+            .await?
+            .iter()
+            .filter_map(|row| target(dialect, row))
+            .collect();
+        if targets.is_empty() {
+            if !options.create_table {
+                bail!("There is no table {}", table);
+            }
+            let columns: Vec<(String, Kind)> = columns
+                .iter()
+                .zip(kinds)
+                .filter_map(|(c, kind)| Some((target_name(c)?, *kind)))
+                .collect();
+            let statement = create_table(dialect, table, &columns);
+            if !options.dry_run {
+                conn.execute_query(&statement, QueryType::Create).await?;
+            }
+            report.created_table = Some(statement);
+            targets = columns
+                .into_iter()
+                .map(|(name, kind)| Target {
+                    column: ResultColumn::new(&name, kind.sql_type(dialect), Some(true)),
+                    kind,
+                    required: false,
+                })
+                .collect();
+        }
+
+        let mut plan = vec![];
+        for (i, column) in columns.iter().enumerate() {
+            let target = target_name(column).and_then(|name| {
+                targets
+                    .iter()
+                    .find(|t| t.column.name.eq_ignore_ascii_case(&name))
+            });
+            match target {
+                Some(target) => plan.push((i, target.clone())),
+                None => report.unmapped.push(column.clone()),
+            }
+        }
+        if plan.is_empty() {
+            bail!("None of the source columns are in {}", table);
+        }
+        Ok(Self {
+            conn,
+            table,
+            options,
+            columns: plan.iter().map(|(_, t)| t.column.clone()).collect(),
+            plan,
+            batch: Vec::with_capacity(options.batch_size),
+            report,
+        })
+    }
+
+    /// Whether more rows have failed than allowed. Dry runs check every row.
+    fn stopped(&self) -> bool {
+        !self.options.dry_run && self.report.error_count > self.options.max_errors
+    }
+
+    /// Adds a row, writing the batch once it is full. Returns whether it was.
+    async fn push(&mut self, record: Result<Record, RowError>) -> bool {
+        self.report.rows_read += 1;
+        match record.and_then(|r| self.convert(r)) {
+            Ok(record) => self.batch.push(record),
+            Err(e) => self.report.error(e),
+        }
+        if self.batch.len() < self.options.batch_size.max(1) {
+            return false;
+        }
+        self.flush().await;
+        true
+    }
+
+    async fn finish(mut self) -> ImportReport {
+        if !self.stopped() {
+            self.flush().await;
+        }
+        self.report.stopped = self.stopped();
+        self.report
+    }
+
+    /// Picks the loaded columns of a record and converts them to their kinds.
+    fn convert(&self, record: Record) -> Result<Record, RowError> {
+        let Record { row, mut values } = record;
+        let values = self
+            .plan
+            .iter()
+            .map(|(i, target)| {
+                let v = values.get_mut(*i).map(Value::take).unwrap_or_default();
+                let name = target.column.name.as_str();
+                if v.is_null() && target.required {
+                    return Err(RowError::new(
+                        row,
+                        Some(name),
+                        "The value can't be null".into(),
+                    ));
+                }
+                target
+                    .kind
+                    .coerce(v)
+                    .map_err(|message| RowError::new(row, Some(name), message))
+            })
+            .collect::<Result<Vec<Value>, RowError>>()?;
+        Ok(Record { row, values })
+    }
+
+    async fn flush(&mut self) {
+        let records = std::mem::take(&mut self.batch);
+        if self.options.dry_run || records.is_empty() {
+            return;
+        }
+        let (conn, table, columns) = (self.conn, self.table, &self.columns);
+        let rows: Vec<Vec<Value>> = records.iter().map(|r| r.values.clone()).collect();
+        let e = match conn.load(table, columns, &rows).await {
+            Ok(n) => {
+                self.report.rows_imported += n;
+                return;
+            }
+            Err(e) => e,
+        };
+        // ClickHouse may have written part of a failed batch, rows retried one
+        // by one could be written twice
+        if records.len() == 1 || conn.config.dialect == Dialect::ClickHouse {
+            for record in &records {
+                self.report
+                    .error(RowError::new(record.row, None, e.to_string()));
+            }
+            return;
+        }
+        for (record, row) in records.iter().zip(rows) {
+            match conn.load(table, columns, &[row]).await {
+                Ok(n) => self.report.rows_imported += n,
+                Err(e) => self
+                    .report
+                    .error(RowError::new(record.row, None, e.to_string())),
+            }
+            if self.stopped() {
+                return;
+            }
+        }
+    }
+}
+
+/// Reads a file into a table. The import stops once more than `max_errors`
+/// rows have failed, rows written until then are kept.
+pub async fn import(
+    conn: &InitiatedConnection,
+    path: &str,
+    table: &str,
+    options: &ImportOptions,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport> {
+    let mut source = Source::open(path, options)?;
+    let kinds = source.kinds.take().unwrap_or_default();
+    let mut loader = Loader::new(conn, table, &source.columns, &kinds, &options.load).await?;
+    for record in source.records.by_ref() {
+        if loader.push(record).await {
+            progress(&loader.report);
+        }
+        if loader.stopped() {
+            break;
+        }
+    }
+    let report = loader.finish().await;
+    progress(&report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{ImportOptions, Record, RowError, Source};
+    use crate::{engine::types::kind::Kind, export::csv::CsvOptions};
+
+    type Read = (Vec<String>, Vec<Kind>, Vec<Result<Record, RowError>>);
+
+    fn read(contents: &str, extension: &str, options: &ImportOptions) -> Read {
+        let path = std::env::temp_dir().join(format!("import_test{}", extension));
+        std::fs::write(&path, contents).unwrap();
+        let source = Source::open(path.to_str().unwrap(), options).unwrap();
+        let read = (
+            source.columns,
+            source.kinds.unwrap(),
+            source.records.collect(),
+        );
+        std::fs::remove_file(path).unwrap();
+        read
+    }
+
+    #[test]
+    fn test_sources() {
+        let options = ImportOptions::default();
+        let csv_options = ImportOptions {
+            csv: Some(CsvOptions {
+                delimiter: ';',
+                ..CsvOptions::default()
+            }),
+            ..options.clone()
+        };
+        let (columns, kinds, records) =
+            read("id;name\r\n1;\"a;b\"\r\n2;\r\n3\r\n", ".csv", &csv_options);
+        assert_eq!(columns, vec!["id", "name"]);
+        assert_eq!(kinds, vec![Kind::Integer, Kind::Text]);
+        assert_eq!(
+            records[0].as_ref().unwrap().values,
+            vec![json!("1"), json!("a;b")]
+        );
+        assert_eq!(
+            records[1].as_ref().unwrap().values,
+            vec![json!("2"), json!(null)]
+        );
+        assert_eq!(records[2].as_ref().err().map(|e| e.row), Some(3));
+
+        let (_, kinds, _) = read("a\tb\n1\t2.5\n", ".tsv", &options);
+        assert_eq!(kinds, vec![Kind::Integer, Kind::Decimal]);
+
+        let ndjson = "{\"a\": 1}\n\n{\"b\": \"x\", \"a\": 2}\nnope\n";
+        let (columns, _, records) = read(ndjson, ".jsonl", &options);
+        assert_eq!(columns, vec!["a", "b"]);
+        assert_eq!(
+            records[1].as_ref().unwrap().values,
+            vec![json!(2), json!("x")]
+        );
+        assert_eq!(records[2].as_ref().err().map(|e| e.row), Some(3));
+
+        let (columns, _, records) = read("[\n  {\"a\": 1},\n  [2, \"x\"]\n]\n", ".json", &options);
+        assert_eq!(columns, vec!["a", "column_2"]);
+        assert_eq!(
+            records[1].as_ref().unwrap().values,
+            vec![json!(2), json!("x")]
+        );
+        let (columns, _, _) = read("{\"a\": 1}\n{\"b\": 2}\n", ".json", &options);
+        assert_eq!(columns, vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,82 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader},
+};
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::{Record, RowError, Source, SAMPLE_ROWS};
+
+pub(super) type Line = (usize, Result<Value, RowError>);
+
+/// Opens a file of one JSON object or array per line.
+pub fn open(path: &str) -> Result<Source> {
+    let lines = BufReader::new(File::open(path)?)
+        .lines()
+        .filter(|line| !matches!(line, Ok(l) if l.trim().is_empty()))
+        .enumerate()
+        .map(|(i, line)| {
+            let row = i + 1;
+            let value = line
+                .map_err(anyhow::Error::from)
+                .and_then(|l| Ok(serde_json::from_str::<Value>(&l)?))
+                .map_err(|e| RowError::new(row, None, e.to_string()));
+            (row, value)
+        });
+    source(lines)
+}
+
+/// Rows from JSON values. Columns are the keys of the objects in the first rows,
+/// in the order they are first seen, or the positions of the arrays.
+pub(super) fn source(mut lines: impl Iterator<Item = Line> + Send + 'static) -> Result<Source> {
+    let sample: Vec<Line> = lines.by_ref().take(SAMPLE_ROWS).collect();
+    let mut columns: Vec<String> = vec![];
+    for (_, value) in &sample {
+        match value {
+            Ok(Value::Object(object)) => {
+                for key in object.keys() {
+                    if !columns.contains(key) {
+                        columns.push(key.clone());
+                    }
+                }
+            }
+            Ok(Value::Array(values)) => {
+                for i in columns.len()..values.len() {
+                    columns.push(format!("column_{}", i + 1));
+                }
+            }
+            _ => {}
+        }
+    }
+    if columns.is_empty() {
+        bail!("The file has no rows with columns");
+    }
+
+    let names = columns.clone();
+    let records = sample.into_iter().chain(lines).map(move |(row, value)| {
+        let values = match value? {
+            Value::Object(mut object) => names
+                .iter()
+                .map(|c| object.remove(c).unwrap_or_default())
+                .collect(),
+            Value::Array(mut values) => {
+                values.resize(names.len(), Value::Null);
+                values
+            }
+            _ => {
+                return Err(RowError::new(
+                    row,
+                    None,
+                    "Expected an object or an array".into(),
+                ))
+            }
+        };
+        Ok(Record { row, values })
+    });
+    Ok(Source {
+        columns,
+        kinds: None,
+        records: Box::new(records),
+    })
+}
@@ -0,0 +1,109 @@
+use std::fs::File;
+
+use anyhow::Result;
+use arrow::{
+    array::{Array, ArrayRef, AsArray, RecordBatch},
+    compute::cast,
+    datatypes::DataType,
+    util::display::{ArrayFormatter, FormatOptions},
+};
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde_json::Value;
+
+use super::{Record, RowError, Source};
+use crate::engine::types::{kind::Kind, value};
+
+const BATCH_SIZE: usize = 8192;
+
+fn kind(data_type: &DataType) -> Kind {
+    match data_type {
+        DataType::Boolean => Kind::Bool,
+        t if t.is_integer() => Kind::Integer,
+        t if t.is_floating() => Kind::Float,
+        DataType::Decimal128(..) | DataType::Decimal256(..) => Kind::Decimal,
+        DataType::Date32 | DataType::Date64 => Kind::Date,
+        DataType::Timestamp(..) => Kind::DateTime,
+        DataType::Time32(_) | DataType::Time64(_) => Kind::Time,
+        DataType::Binary | DataType::LargeBinary | DataType::FixedSizeBinary(_) => Kind::Binary,
+        _ => Kind::Text,
+    }
+}
+
+/// The values of a column. Numbers and strings keep their JSON types, decimals
+/// and binary values are tagged and anything else is read as its display text,
+/// e.g. `2024-01-02T03:04:05` for timestamps.
+fn column_values(array: &ArrayRef) -> Result<Vec<Value>> {
+    // named time zones can't be displayed without chrono-tz, the values are
+    // in UTC either way
+    let (array, zone) = match array.data_type() {
+        DataType::Timestamp(unit, Some(_)) => {
+            (cast(array, &DataType::Timestamp(*unit, None))?, "Z")
+        }
+        _ => (array.clone(), ""),
+    };
+    let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default())?;
+    let values = (0..array.len()).map(|i| {
+        if array.is_null(i) {
+            return Value::Null;
+        }
+        let text = formatter.value(i).to_string();
+        match array.data_type() {
+            DataType::Boolean => Value::Bool(array.as_boolean().value(i)),
+            t if t.is_integer() || t.is_floating() => {
+                serde_json::from_str(&text).unwrap_or(Value::String(text))
+            }
+            DataType::Decimal128(..) | DataType::Decimal256(..) => value::decimal(text),
+            DataType::Timestamp(..) => Value::String(text + zone),
+            DataType::Binary => value::binary(array.as_binary::<i32>().value(i)),
+            DataType::LargeBinary => value::binary(array.as_binary::<i64>().value(i)),
+            DataType::FixedSizeBinary(_) => value::binary(array.as_fixed_size_binary().value(i)),
+            _ => Value::String(text),
+        }
+    });
+    Ok(values.collect())
+}
+
+fn batch_rows(batch: &RecordBatch) -> Result<Vec<Vec<Value>>> {
+    let columns = batch
+        .columns()
+        .iter()
+        .map(column_values)
+        .collect::<Result<Vec<_>>>()?;
+    Ok((0..batch.num_rows())
+        .map(|i| columns.iter().map(|c| c[i].clone()).collect())
+        .collect())
+}
+
+/// Opens a Parquet file, its columns typed by the file's schema.
+pub fn open(path: &str) -> Result<Source> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path)?)?;
+    let fields = builder.schema().fields().clone();
+    let reader = builder.with_batch_size(BATCH_SIZE).build()?;
+
+    let mut offset = 0;
+    let records = reader.flat_map(move |batch| {
+        let rows = batch
+            .map_err(anyhow::Error::from)
+            .and_then(|batch| batch_rows(&batch));
+        let records: Vec<Result<Record, RowError>> = match rows {
+            Ok(rows) => rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, values)| {
+                    Ok(Record {
+                        row: offset + i + 1,
+                        values,
+                    })
+                })
+                .collect(),
+            Err(e) => vec![Err(RowError::new(offset + 1, None, e.to_string()))],
+        };
+        offset += records.len();
+        records
+    });
+    Ok(Source {
+        columns: fields.iter().map(|f| f.name().clone()).collect(),
+        kinds: Some(fields.iter().map(|f| kind(f.data_type())).collect()),
+        records: Box::new(records),
+    })
+}
@@ -0,0 +1,205 @@
+use std::fs::read_to_string;
+
+use anyhow::{bail, Result};
+use tokio_util::sync::CancellationToken;
+
+use crate::engine::types::{config::Dialect, connection::InitiatedConnection};
+
+fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// The end of a quoted string or identifier starting at `start`. Quotes are
+/// escaped by doubling them, and by backslashes in MySQL and ClickHouse.
+fn quoted(bytes: &[u8], start: usize, backslash: bool) -> usize {
+    let quote = bytes[start];
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if backslash && quote != b'`' => i += 1,
+            b if b == quote && bytes.get(i + 1) == Some(&quote) => i += 1,
+            b if b == quote => return i + 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The end of a Postgres dollar quoted string if one starts at `start`.
+fn dollar_quoted(script: &str, start: usize) -> Option<usize> {
+    let rest = &script[start + 1..];
+    let tag_end = rest.find('$')?;
+    let tag = &rest[..tag_end];
+    if !tag.bytes().all(is_word) || tag.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let delimiter = &script[start..start + tag_end + 2];
+    let body = start + delimiter.len();
+    Some(
+        script[body..]
+            .find(delimiter)
+            .map_or(script.len(), |end| body + end + delimiter.len()),
+    )
+}
+
+/// Splits a script into statements at semicolons outside of strings, quoted
+/// identifiers, comments and Postgres dollar quoting. `DELIMITER` lines change
+/// the separator as in the MySQL client, and the body of `CREATE TRIGGER ...
+/// BEGIN ... END` is kept whole as in the SQLite shell.
+pub fn split_statements(dialect: &Dialect, script: &str) -> Vec<String> {
+    let backslash = matches!(
+        dialect,
+        Dialect::Mysql | Dialect::MariaDB | Dialect::ClickHouse
+    );
+    let bytes = script.as_bytes();
+    let mut statements = vec![];
+    let mut delimiter = ";".to_string();
+    let mut start = 0;
+    let mut i = 0;
+    // the statement's first words and whether it has more than comments
+    let mut words: Vec<String> = vec![];
+    let mut has_code = false;
+    let mut last_word = String::new();
+
+    while i < bytes.len() {
+        let line_start = i == 0 || bytes[i - 1] == b'\n';
+        if !has_code && line_start {
+            let line_end = script[i..].find('\n').map_or(script.len(), |n| i + n);
+            let line = script[i..line_end].trim();
+            let keyword = line.get(..10).unwrap_or_default();
+            if keyword.eq_ignore_ascii_case("DELIMITER ") && !line[10..].trim().is_empty() {
+                delimiter = line[10..].trim().to_string();
+                i = line_end;
+                start = i;
+                continue;
+            }
+        }
+        let in_trigger_body = words.len() >= 2
+            && words[0] == "CREATE"
+            && words.iter().take(4).any(|w| w == "TRIGGER")
+            && words.iter().any(|w| w == "BEGIN");
+        if script[i..].starts_with(&delimiter) && (!in_trigger_body || last_word == "END") {
+            if has_code {
+                statements.push(script[start..i].trim().to_string());
+            }
+            i += delimiter.len();
+            start = i;
+            words.clear();
+            has_code = false;
+            last_word.clear();
+            continue;
+        }
+        match bytes[i] {
+            b'\'' | b'"' | b'`' => {
+                i = quoted(bytes, i, backslash);
+                has_code = true;
+                last_word.clear();
+            }
+            b'-' if bytes.get(i + 1) == Some(&b'-') => {
+                i = script[i..].find('\n').map_or(script.len(), |n| i + n);
+            }
+            b'#' if matches!(dialect, Dialect::Mysql | Dialect::MariaDB) => {
+                i = script[i..].find('\n').map_or(script.len(), |n| i + n);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = script[i + 2..]
+                    .find("*/")
+                    .map_or(script.len(), |n| i + n + 4);
+            }
+            b'$' if *dialect == Dialect::Postgresql && dollar_quoted(script, i).is_some() => {
+                i = dollar_quoted(script, i).unwrap_or(script.len());
+                has_code = true;
+                last_word.clear();
+            }
+            b if is_word(b) => {
+                let end = bytes[i..]
+                    .iter()
+                    .position(|b| !is_word(*b))
+                    .map_or(bytes.len(), |n| i + n);
+                last_word = script[i..end].to_uppercase();
+                if words.len() < 4 || last_word == "BEGIN" {
+                    words.push(last_word.clone());
+                }
+                has_code = true;
+                i = end;
+            }
+            b if b.is_ascii_whitespace() => i += 1,
+            _ => {
+                has_code = true;
+                last_word.clear();
+                i += 1;
+            }
+        }
+    }
+    if has_code {
+        statements.push(script[start..].trim().to_string());
+    }
+    statements
+}
+
+/// Replays a script, such as a dump, statement by statement in the order they
+/// are written. `progress` is called with the number of statements run and
+/// their total. Cancelling `token` stops between or during statements.
+pub async fn restore(
+    conn: &InitiatedConnection,
+    path: &str,
+    progress: impl Fn(usize, usize),
+    token: &CancellationToken,
+) -> Result<usize> {
+    let statements = split_statements(&conn.config.dialect, &read_to_string(path)?);
+    if statements.is_empty() {
+        bail!("The file has no statements");
+    }
+    let total = statements.len();
+    conn.execute_script(&statements, |count| progress(count, total), token)
+        .await?;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod test {
+    use super::split_statements;
+    use crate::engine::types::config::Dialect;
+
+    #[test]
+    fn test_split_statements() {
+        let script = "-- header; with a semicolon\n\
+            CREATE TABLE t (a text DEFAULT ';');\n\
+            /* block; */ INSERT INTO t VALUES ('it''s; fine');\n\
+            CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql;\n\
+            -- trailing comment";
+        assert_eq!(
+            split_statements(&Dialect::Postgresql, script),
+            vec![
+                "-- header; with a semicolon\nCREATE TABLE t (a text DEFAULT ';')",
+                "/* block; */ INSERT INTO t VALUES ('it''s; fine')",
+                "CREATE FUNCTION f() RETURNS int AS $body$ SELECT 1; $body$ LANGUAGE sql",
+            ]
+        );
+
+        let script = "INSERT INTO t VALUES ('a\\';b');\n\
+            DELIMITER ;;\n\
+            CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END;;\n\
+            DELIMITER ;\n\
+            SELECT 3;";
+        assert_eq!(
+            split_statements(&Dialect::Mysql, script),
+            vec![
+                "INSERT INTO t VALUES ('a\\';b')",
+                "CREATE PROCEDURE p() BEGIN SELECT 1; SELECT 2; END",
+                "SELECT 3",
+            ]
+        );
+
+        let script = "CREATE TRIGGER tr AFTER INSERT ON t BEGIN\n  \
+            UPDATE t SET a = 1; DELETE FROM u;\nEND;\nSELECT 1;";
+        assert_eq!(
+            split_statements(&Dialect::Sqlite, script),
+            vec![
+                "CREATE TRIGGER tr AFTER INSERT ON t BEGIN\n  UPDATE t SET a = 1; DELETE FROM u;\nEND",
+                "SELECT 1",
+            ]
+        );
+    }
+}
@@ -0,0 +1,297 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use tokio::sync::mpsc::Receiver;
+
+use super::{ImportReport, LoadOptions, Loader, Record};
+use crate::{
+    database::QueryType,
+    engine::{
+        exec,
+        types::{
+            config::Dialect,
+            connection::InitiatedConnection,
+            inserts::Inserts,
+            kind::Kind,
+            result::{field, ResultSet},
+        },
+    },
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferSource {
+    Table(String),
+    /// A `SELECT` statement
+    Query(String),
+}
+
+/// The statement reading one page of a table in primary key order, starting
+/// after the key of the last row read, given as literals.
+pub fn page_query(
+    dialect: &Dialect,
+    table: &str,
+    key: &[String],
+    after: Option<&[String]>,
+    limit: usize,
+) -> String {
+    let key: Vec<String> = key.iter().map(|c| dialect.quote_ident(c)).collect();
+    let filter = match after {
+        Some(after) if key.len() == 1 => format!(" WHERE {} > {}", key[0], after[0]),
+        Some(after) => format!(" WHERE ({}) > ({})", key.join(", "), after.join(", ")),
+        None => String::new(),
+    };
+    format!(
+        "SELECT * FROM {}{} ORDER BY {} LIMIT {}",
+        dialect.quote_table(table),
+        filter,
+        key.join(", "),
+        limit
+    )
+}
+
+/// Reads a table or query a page at a time. Tables with a primary key are read
+/// in key order, each page after the last key read, so rows that move between
+/// pages are neither skipped nor read twice. Queries and tables without one are
+/// run once and their rows streamed in pages, as pages of them without an order
+/// could overlap. ClickHouse sorting keys need not be unique, its tables are
+/// streamed too.
+pub struct Pages<'a> {
+    conn: &'a InitiatedConnection,
+    source: &'a TransferSource,
+    key: Vec<String>,
+    page_size: usize,
+    inserts: Inserts<'a>,
+    after: Option<Vec<String>>,
+    stream: Option<Receiver<Result<ResultSet>>>,
+    done: bool,
+}
+
+impl<'a> Pages<'a> {
+    pub async fn new(
+        conn: &'a InitiatedConnection,
+        source: &'a TransferSource,
+        page_size: usize,
+    ) -> Result<Self> {
+        let dialect = &conn.config.dialect;
+        let (table, key) = match source {
+            TransferSource::Table(table) if dialect != &Dialect::ClickHouse => {
+                let key = conn
+                    .get_primary_key(table)
+                    .await?
+                    .iter()
+                    .filter_map(|row| Some(field(row, "column_name")?.as_str()?.to_string()))
+                    .collect();
+                (table.as_str(), key)
+            }
+            TransferSource::Table(table) => (table.as_str(), vec![]),
+            TransferSource::Query(_) => ("", vec![]),
+        };
+        let page_size = page_size.max(1);
+        let stream = match source {
+            TransferSource::Table(table) if key.is_empty() => {
+                let query = format!("SELECT * FROM {}", dialect.quote_table(table));
+                Some(exec::stream_query(conn, query, page_size))
+            }
+            TransferSource::Table(_) => None,
+            TransferSource::Query(query) => {
+                let query = query.trim().trim_end_matches(';').to_string();
+                Some(exec::stream_query(conn, query, page_size))
+            }
+        };
+        Ok(Self {
+            conn,
+            source,
+            key,
+            page_size,
+            inserts: Inserts::new(dialect, table, vec![], vec![], false)?,
+            after: None,
+            stream,
+            done: false,
+        })
+    }
+
+    /// The next page, `None` once every row was read. The first page is there
+    /// even without rows, with the columns.
+    pub async fn next(&mut self) -> Result<Option<ResultSet>> {
+        if let Some(stream) = &mut self.stream {
+            return stream.recv().await.transpose();
+        }
+        let TransferSource::Table(table) = self.source else {
+            return Ok(None);
+        };
+        if self.done {
+            return Ok(None);
+        }
+        let dialect = &self.conn.config.dialect;
+        let query = page_query(
+            dialect,
+            table,
+            &self.key,
+            self.after.as_deref(),
+            self.page_size,
+        );
+        let page = self.conn.execute_query(&query, QueryType::Select).await?;
+        self.done = page.rows.len() < self.page_size;
+        if let Some(last) = page.rows.last().filter(|_| !self.done) {
+            let after = self
+                .key
+                .iter()
+                .map(|k| {
+                    let i = page.result_columns.iter().position(|c| &c.name == k)?;
+                    let column = &page.result_columns[i];
+                    Some(self.inserts.literal(last.get(i)?, &column.type_name))
+                })
+                .collect::<Option<Vec<String>>>()
+                .ok_or_else(|| anyhow!("The rows have no primary key to page by"))?;
+            self.after = Some(after);
+        }
+        Ok(Some(page))
+    }
+}
+
+/// Copies the rows of a table or query on one connection into a table on
+/// another, possibly of another dialect. Rows are read a page at a time and
+/// converted to the target columns' kinds, creating the target table from the
+/// source's column types when asked.
+pub async fn transfer(
+    source_conn: &InitiatedConnection,
+    source: &TransferSource,
+    target_conn: &InitiatedConnection,
+    table: &str,
+    options: &LoadOptions,
+    progress: impl Fn(&ImportReport),
+) -> Result<ImportReport> {
+    let mut pages = Pages::new(source_conn, source, options.batch_size).await?;
+    let Some(mut page) = pages.next().await? else {
+        bail!("The source has no columns");
+    };
+    if page.result_columns.is_empty() {
+        bail!("The source has no columns");
+    }
+    let columns: Vec<String> = page.result_columns.iter().map(|c| c.name.clone()).collect();
+    // SQLite has no type for expressions, their kind is told by the values
+    let kinds: Vec<Kind> = page
+        .result_columns
+        .iter()
+        .enumerate()
+        .map(|(i, c)| match c.type_name.as_str() {
+            "" | "NULL" => Kind::infer(page.rows.iter().filter_map(|r| r.get(i))),
+            _ => c.kind,
+        })
+        .collect();
+
+    let mut loader = Loader::new(target_conn, table, &columns, &kinds, options).await?;
+    let mut offset = 0;
+    loop {
+        let count = page.rows.len();
+        for (i, values) in page.rows.into_iter().enumerate() {
+            let row = offset + i + 1;
+            loader.push(Ok(Record { row, values })).await;
+            if loader.stopped() {
+                break;
+            }
+        }
+        progress(&loader.report);
+        if loader.stopped() {
+            break;
+        }
+        offset += count;
+        page = match pages.next().await? {
+            Some(page) => page,
+            None => break,
+        };
+    }
+    let report = loader.finish().await;
+    progress(&report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::{page_query, transfer, Pages, TransferSource};
+    use crate::{
+        engine::types::{
+            config::{ConnectionConfig, ConnectionPool, Credentials, Dialect, Metadata, Mode},
+            connection::InitiatedConnection,
+        },
+        import::LoadOptions,
+    };
+
+    async fn sqlite() -> Result<InitiatedConnection> {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await?;
+        let credentials = Credentials::from([("path".to_string(), ":memory:".to_string())]);
+        let config = ConnectionConfig::new(
+            Dialect::Sqlite,
+            Mode::File,
+            credentials,
+            "memory",
+            "blue",
+            Metadata::default(),
+        )?;
+        let conn = InitiatedConnection {
+            config,
+            pool: ConnectionPool::Sqlite(pool),
+            schema: String::new(),
+        };
+        conn.execute_tx(vec![
+            "CREATE TABLE t (a INTEGER)",
+            "INSERT INTO t VALUES (1), (2), (3), (4), (5)",
+            "CREATE TABLE e (id INTEGER PRIMARY KEY, b TEXT)",
+        ])
+        .await?;
+        Ok(conn)
+    }
+
+    async fn page_sizes(conn: &InitiatedConnection, source: TransferSource) -> Result<Vec<usize>> {
+        let mut pages = Pages::new(conn, &source, 2).await?;
+        let mut sizes = vec![];
+        while let Some(page) = pages.next().await? {
+            assert_eq!(page.result_columns[0].name, "a");
+            sizes.push(page.rows.len());
+        }
+        Ok(sizes)
+    }
+
+    #[tokio::test]
+    async fn test_pages() -> Result<()> {
+        let conn = sqlite().await?;
+        let query = TransferSource::Query("SELECT a FROM t ORDER BY a;".to_string());
+        assert_eq!(page_sizes(&conn, query).await?, [2, 2, 1]);
+        let table = TransferSource::Table("t".to_string());
+        assert_eq!(page_sizes(&conn, table).await?, [2, 2, 1]);
+        let empty = TransferSource::Query("SELECT a FROM t WHERE a > 9".to_string());
+        assert_eq!(page_sizes(&conn, empty).await?, [0]);
+
+        // an empty table is copied, creating the target from its columns
+        let options = LoadOptions {
+            create_table: true,
+            ..LoadOptions::default()
+        };
+        let source = TransferSource::Table("e".to_string());
+        let report = transfer(&conn, &source, &conn, "copy", &options, |_| {}).await?;
+        assert_eq!(report.rows_imported, 0);
+        assert!(report.created_table.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_page_query() {
+        let key = ["id".to_string()];
+        assert_eq!(
+            page_query(&Dialect::Mysql, "app.users", &key, None, 100),
+            "SELECT * FROM `app`.`users` ORDER BY `id` LIMIT 100"
+        );
+        let key = ["a".to_string(), "b".to_string()];
+        let after = ["1".to_string(), "'x'".to_string()];
+        assert_eq!(
+            page_query(&Dialect::Postgresql, "t", &key, Some(&after), 100),
+            "SELECT * FROM \"t\" WHERE (\"a\", \"b\") > (1, 'x') ORDER BY \"a\", \"b\" LIMIT 100"
+        );
+    }
+}
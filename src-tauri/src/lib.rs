@@ -1,6 +1,8 @@
 pub mod database;
 pub mod engine;
+pub mod export;
 pub mod handlers;
+pub mod import;
 pub mod query;
 pub mod queues;
 pub mod state;
@@ -7,12 +7,22 @@ use tokio_util::sync::CancellationToken;
 
 pub enum Events {
     QueryFinished,
+    ConnectionStatus,
+    ImportProgress,
+    TransferProgress,
+    DumpProgress,
+    RestoreProgress,
 }
 
 impl Events {
     pub fn as_str(&self) -> &'static str {
         match self {
             Events::QueryFinished => "query_finished",
+            Events::ConnectionStatus => "connection_status",
+            Events::ImportProgress => "import_progress",
+            Events::TransferProgress => "transfer_progress",
+            Events::DumpProgress => "dump_progress",
+            Events::RestoreProgress => "restore_progress",
         }
     }
 }
@@ -30,6 +30,7 @@ pub trait ServiceAccess {
     fn db(&self) -> &SqlitePool;
 
     fn acquire_connection(&self, conn_id: String) -> InitiatedConnection;
+    fn find_connection(&self, conn_id: &str) -> Option<InitiatedConnection>;
     fn update_connection(&self, conn: InitiatedConnection) -> Result<()>;
     fn disconnect(&mut self, conn_id: &str) -> Result<()>;
     fn connect(&mut self, conn: &InitiatedConnection) -> Result<String>;
@@ -58,6 +59,12 @@ impl ServiceAccess for AppHandle {
         connection.clone()
     }
 
+    fn find_connection(&self, conn_id: &str) -> Option<InitiatedConnection> {
+        let app_state: State<AppState> = self.state();
+        let binding = app_state.connections.lock();
+        binding.ok().and_then(|c| c.get(conn_id).cloned())
+    }
+
     fn connect(&mut self, conn: &InitiatedConnection) -> Result<String> {
         let app_state: State<AppState> = self.state();
         let mut binding = app_state.connections.lock();
@@ -1,6 +1,14 @@
-use anyhow::Result;
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use anyhow::{anyhow, bail, Result};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use magic_crypt::{new_magic_crypt, MagicCrypt256, MagicCryptTrait};
 use md5::{Digest, Md5};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
 use tauri::AppHandle;
 
 use super::fs::read_key;
@@ -19,6 +27,59 @@ pub fn get_app_key(app: AppHandle) -> Result<MagicCrypt256> {
     Ok(new_magic_crypt!(key, 256))
 }
 
+/// Data encrypted with a passphrase, base64 encoded with the salt and nonce
+/// needed to decrypt it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SealedData {
+    pub salt: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+fn get_passphrase_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>> {
+    if passphrase.is_empty() {
+        bail!("Passphrase cannot be empty");
+    }
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Could not derive the key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypts with AES-256-GCM under an Argon2id key derived from the passphrase
+/// and a random salt. Decrypting fails if the data was tampered with.
+pub fn encrypt_with_passphrase(data: &str, passphrase: &str) -> Result<SealedData> {
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&get_passphrase_key(passphrase, &salt)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, data.as_bytes())
+        .map_err(|_| anyhow!("Could not encrypt the data"))?;
+    Ok(SealedData {
+        salt: STANDARD.encode(salt),
+        nonce: STANDARD.encode(nonce),
+        ciphertext: STANDARD.encode(ciphertext),
+    })
+}
+
+pub fn decrypt_with_passphrase(sealed: &SealedData, passphrase: &str) -> Result<String> {
+    let salt = STANDARD.decode(&sealed.salt)?;
+    let nonce = STANDARD.decode(&sealed.nonce)?;
+    if nonce.len() != 12 {
+        bail!("Invalid nonce");
+    }
+    let cipher = Aes256Gcm::new(&get_passphrase_key(passphrase, &salt)?);
+    let data = cipher
+        .decrypt(
+            Nonce::from_slice(&nonce),
+            STANDARD.decode(&sealed.ciphertext)?.as_slice(),
+        )
+        .map_err(|_| anyhow!("Wrong passphrase or corrupted data"))?;
+    Ok(String::from_utf8(data)?)
+}
+
 pub fn md5_hash(data: &str) -> String {
     let mut hasher = Md5::new();
     hasher.update(data);
@@ -29,8 +90,11 @@ pub fn md5_hash(data: &str) -> String {
 #[cfg(test)]
 mod test {
     use anyhow::Result;
+    use base64::{engine::general_purpose::STANDARD, Engine};
 
-    use crate::utils::crypto::{decrypt_data, encrypt_data};
+    use crate::utils::crypto::{
+        decrypt_data, decrypt_with_passphrase, encrypt_data, encrypt_with_passphrase,
+    };
     use magic_crypt::new_magic_crypt;
 
     #[test]
@@ -43,4 +107,25 @@ mod test {
         assert_eq!(data, decrypted);
         Ok(())
     }
+
+    #[test]
+    fn test_encrypt_with_passphrase() -> Result<()> {
+        let data = "{\"password\":\"secret\"}";
+        let sealed = encrypt_with_passphrase(data, "correct horse")?;
+        assert_eq!(decrypt_with_passphrase(&sealed, "correct horse")?, data);
+        assert!(decrypt_with_passphrase(&sealed, "wrong horse").is_err());
+        assert!(encrypt_with_passphrase(data, "").is_err());
+
+        // the same data and passphrase never give the same ciphertext
+        let other = encrypt_with_passphrase(data, "correct horse")?;
+        assert_ne!(sealed.salt, other.salt);
+        assert_ne!(sealed.ciphertext, other.ciphertext);
+
+        let mut tampered = sealed.clone();
+        let mut ciphertext = STANDARD.decode(&tampered.ciphertext)?;
+        ciphertext[0] ^= 1;
+        tampered.ciphertext = STANDARD.encode(ciphertext);
+        assert!(decrypt_with_passphrase(&tampered, "correct horse").is_err());
+        Ok(())
+    }
 }
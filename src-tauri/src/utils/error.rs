@@ -17,6 +17,8 @@ pub enum Error {
     General(#[from] anyhow::Error),
     #[error("Query results expired, please re-run the query.")]
     QueryExpired,
+    #[error("Connection is read-only, only SELECT and SHOW statements are allowed.")]
+    ReadOnly,
     #[error("{0}")]
     TxError(String),
     #[error("Serdejson error: {0}")]
@@ -1,8 +1,11 @@
-use crate::{database::QueryType, engine::types::result::ResultSet};
+use crate::{
+    database::QueryType,
+    engine::types::result::{ResultColumn, ResultSet, TableMetadata},
+};
 use anyhow::Result;
 use fs::metadata;
 use log::error;
-use serde_json::json;
+use serde_json::{json, Value};
 use std::{fs, path::PathBuf};
 use tauri::{AppHandle, Manager};
 
@@ -90,9 +93,9 @@ pub fn write_query(
     tmp_dir: PathBuf,
 ) -> Result<String> {
     let mut rows = String::from("");
-    result_set.rows.iter().for_each(|row| {
-        rows += &(row.to_string() + "\n");
-    });
+    for row in result_set.rows.iter() {
+        rows += &(serde_json::to_string(row)? + "\n");
+    }
     let (table_name, foreign_keys, primary_key, columns) = match &result_set.table {
         None => ("".to_string(), vec![], vec![], vec![]),
         Some(t) => (
@@ -109,6 +112,8 @@ pub fn write_query(
         "end_time": result_set.end_time,
         "count": result_set.rows.len(),
         "affected_rows": result_set.affected_rows,
+        "written_bytes": result_set.written_bytes,
+        "result_columns": result_set.result_columns,
         "table": table_name,
         "foreign_keys": foreign_keys,
         "primary_key": primary_key,
@@ -127,6 +132,32 @@ pub fn write_query(
     Ok(path.to_string())
 }
 
+/// Reads the column list written next to a result file by `write_query`.
+pub fn read_result_columns(path: &str) -> Result<Vec<ResultColumn>> {
+    let metadata = fs::read_to_string(path.to_string() + ".metadata")?;
+    let metadata: Value = serde_json::from_str(&metadata)?;
+    let columns = metadata.get("result_columns").cloned().unwrap_or(json!([]));
+    Ok(serde_json::from_value(columns)?)
+}
+
+/// Reads the table a result file was read from, `None` when it is not a table
+/// query.
+pub fn read_table_metadata(path: &str) -> Result<Option<TableMetadata>> {
+    let metadata = fs::read_to_string(path.to_string() + ".metadata")?;
+    let metadata: Value = serde_json::from_str(&metadata)?;
+    let table = metadata["table"].as_str().unwrap_or_default();
+    if table.is_empty() {
+        return Ok(None);
+    }
+    let list = |key: &str| metadata[key].as_array().cloned();
+    Ok(Some(TableMetadata {
+        table: table.to_string(),
+        primary_key: list("primary_key"),
+        foreign_keys: list("foreign_keys"),
+        columns: list("columns"),
+    }))
+}
+
 pub fn copy_file(src: &str, dest: &str) -> Result<()> {
     let res = fs::copy(src, dest);
     if let Err(res) = res {
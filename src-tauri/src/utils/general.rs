@@ -43,6 +43,14 @@ async fn port_forward(
     Ok(())
 }
 
+/// Returns the cancellation token registered for a connection, creating one if the
+/// connection doesn't have one yet. Disconnecting cancels it.
+pub async fn get_connection_token(handle: &AppHandle, conn_id: &str) -> CancellationToken {
+    let state: State<'_, AppState> = handle.state();
+    let mut binding = state.cancel_tokens.lock().await;
+    binding.entry(conn_id.to_string()).or_default().clone()
+}
+
 pub async fn request_port_forward(
     handle: AppHandle,
     conn_id: String,
@@ -3,3 +3,4 @@ pub mod fs;
 pub mod init;
 pub mod error;
 pub mod general;
+pub mod secrets;
@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Result};
+use std::time::Duration;
+use tokio::process::Command;
+
+use crate::engine::types::config::Credentials;
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns true if any credential value contains a `${...}` secret reference.
+pub fn has_references(credentials: &Credentials) -> bool {
+    credentials.values().any(|v| v.contains("${"))
+}
+
+/// Looks up environment variables for `${env:NAME}` references.
+pub type EnvLookup = dyn Fn(&str) -> Option<String> + Sync;
+
+fn process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Resolves `${env:NAME}`, `${file:/path}` and `${cmd:command}` references in every
+/// credential value. `$${` escapes a literal `${`. Any credential can carry a
+/// command, including imported ones, so commands only run for connections the
+/// user trusts.
+pub async fn resolve_credentials(
+    credentials: &Credentials,
+    trust_commands: bool,
+) -> Result<Credentials> {
+    let mut resolved = Credentials::new();
+    for (key, value) in credentials {
+        let value = resolve_value(value, trust_commands, &process_env)
+            .await
+            .map_err(|e| anyhow!("Failed to resolve '{}': {}", key, e))?;
+        resolved.insert(key.clone(), value);
+    }
+    Ok(resolved)
+}
+
+/// The end of the reference `rest` starts with, braces inside it must balance.
+fn reference_end(rest: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 1 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+pub async fn resolve_value(value: &str, trust_commands: bool, env: &EnvLookup) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let end = reference_end(&rest[start..])
+            .ok_or_else(|| anyhow!("Unterminated secret reference"))?;
+        let reference = &rest[start + 2..start + end];
+        out.push_str(&resolve_reference(reference, trust_commands, env).await?);
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+async fn resolve_reference(
+    reference: &str,
+    trust_commands: bool,
+    env: &EnvLookup,
+) -> Result<String> {
+    let (kind, arg) = reference
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Secret reference must look like ${{kind:value}}"))?;
+    let arg = arg.trim();
+    match kind.trim() {
+        "env" => env(arg).ok_or_else(|| anyhow!("Environment variable {} is not set", arg)),
+        "file" => tokio::fs::read_to_string(arg)
+            .await
+            .map(|s| s.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|e| anyhow!("Could not read {}: {}", arg, e)),
+        "cmd" if !trust_commands => Err(anyhow!(
+            "Secret commands only run for trusted connections, trust this one in its settings"
+        )),
+        "cmd" => run_command(arg).await,
+        kind => Err(anyhow!("Unknown secret reference kind: {}", kind)),
+    }
+}
+
+async fn run_command(command: &str) -> Result<String> {
+    #[cfg(windows)]
+    let mut cmd = {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    };
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, cmd.kill_on_drop(true).output())
+        .await
+        .map_err(|_| anyhow!("Secret command timed out"))??;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Secret command exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8(output.stdout)?
+        .trim_end_matches(['\r', '\n'])
+        .to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+
+    use super::resolve_value;
+
+    fn env(name: &str) -> Option<String> {
+        (name == "NOIR_TEST_SECRET").then(|| "s3cret".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_value() -> Result<()> {
+        assert_eq!(resolve_value("plain", false, &env).await?, "plain");
+        assert_eq!(
+            resolve_value("${env:NOIR_TEST_SECRET}", false, &env).await?,
+            "s3cret"
+        );
+        assert_eq!(
+            resolve_value("a-${env:NOIR_TEST_SECRET}-$${env:x}", false, &env).await?,
+            "a-s3cret-${env:x}"
+        );
+        assert!(resolve_value("${env:NOIR_TEST_MISSING}", false, &env)
+            .await
+            .is_err());
+        assert!(resolve_value("${vault:x}", false, &env).await.is_err());
+        assert!(resolve_value("${env:NOIR_TEST_SECRET", false, &env)
+            .await
+            .is_err());
+
+        // commands only run once the connection is trusted
+        assert!(resolve_value("${cmd:echo token}", false, &env)
+            .await
+            .is_err());
+        #[cfg(not(windows))]
+        {
+            assert_eq!(
+                resolve_value("${cmd:echo token}", true, &env).await?,
+                "token"
+            );
+            assert_eq!(
+                resolve_value("${cmd:echo '{\"a\": 1}'}-x", true, &env).await?,
+                "{\"a\": 1}-x"
+            );
+        }
+        Ok(())
+    }
+}
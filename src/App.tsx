@@ -8,7 +8,7 @@ import './index.css';
 import { onMount } from 'solid-js';
 import { useAppSelector } from 'services/Context';
 import { listen } from '@tauri-apps/api/event';
-import { Events, QueryTaskResult } from 'interfaces';
+import { ConnectionStatusEvent, Events, QueryTaskResult } from 'interfaces';
 import { error } from '@tauri-apps/plugin-log';
 import { Router } from 'Router';
 import {
@@ -29,6 +29,7 @@ function App() {
       getConnection,
       updateResultSet,
       setLoading,
+      setConnectionStatus,
     },
     app: { restoreAppStore },
     backend: { getQueryMetadata },
@@ -99,6 +100,9 @@ function App() {
     await listen<QueryTaskResult>(Events.QueryFinished, async (event) => {
       await compareAndAssign(event.payload);
     });
+    await listen<ConnectionStatusEvent>(Events.ConnectionStatus, (event) => {
+      setConnectionStatus(event.payload);
+    });
     await checkForUpdates();
   });
 
@@ -53,6 +53,8 @@ export const SslMode = {
   disable: 'disable',
   prefer: 'prefer',
   require: 'require',
+  verify_ca: 'verify-ca',
+  verify_full: 'verify-full',
 } as const;
 
 export const connectionModes = [
@@ -66,12 +68,26 @@ export const sslModes = [
   SslMode.disable,
   SslMode.prefer,
   SslMode.require,
+  SslMode.verify_ca,
+  SslMode.verify_full,
 ] as const;
 
 export type Metadata = {
   sensitive: boolean;
+  read_only?: boolean;
+  trust_commands?: boolean;
 };
 
+export const environments = [
+  'None',
+  'Development',
+  'Testing',
+  'Staging',
+  'Production',
+] as const;
+
+export type Environment = (typeof environments)[number];
+
 export type Credentials = Record<string, string | number>;
 
 export type ConnectionConfig = {
@@ -83,6 +99,11 @@ export type ConnectionConfig = {
   metadata: Metadata;
   name: string;
   color: ConnectionColor;
+  folder?: string | null;
+  tags?: string[];
+  environment?: Environment;
+  sort_order?: number;
+  last_used_at?: number | null;
 };
 
 export const connectionColors = [
@@ -153,13 +174,146 @@ export const QueryTypes = Object.keys(QueryType) as QueryType[];
 
 export type QueryType = keyof typeof QueryType;
 
+// A column of a query result, in select list order.
+export type ColumnKind =
+  | 'bool'
+  | 'integer'
+  | 'float'
+  | 'decimal'
+  | 'date'
+  | 'date_time'
+  | 'time'
+  | 'json'
+  | 'binary'
+  | 'text';
+
+export type ResultColumn = {
+  name: string;
+  type: string;
+  kind: ColumnKind;
+  nullable: boolean | null;
+  table: string | null;
+  column: string | null;
+};
+
+export type ExportFormat =
+  | 'csv'
+  | 'tsv'
+  | 'json'
+  | 'ndjson'
+  | 'sql'
+  | 'xlsx'
+  | 'parquet'
+  | 'arrow';
+
+export type JsonOptions = {
+  format?: 'array' | 'ndjson';
+  pretty?: boolean;
+  rows?: 'object' | 'array';
+};
+
+export type CsvOptions = {
+  delimiter?: string;
+  header?: boolean;
+  null?: string;
+  encoding?: 'utf8' | 'utf8_bom' | 'utf16_le';
+  line_ending?: 'crlf' | 'lf';
+};
+
+export type SqlOptions = {
+  batch_size?: number;
+  upsert?: boolean;
+  table?: string;
+};
+
+export type ParquetOptions = {
+  compression?: 'none' | 'snappy' | 'gzip' | 'zstd' | 'lz4';
+};
+
+export type TableFormat = 'markdown' | 'html' | 'ascii';
+
+export type TableOptions = {
+  format?: TableFormat;
+  first_row?: number;
+  row_count?: number;
+  columns?: number[];
+};
+
+export type CopyFormat = 'sql' | TableFormat;
+
+export type LoadOptions = {
+  mapping?: Record<string, string>;
+  create_table?: boolean;
+  dry_run?: boolean;
+  batch_size?: number;
+  max_errors?: number;
+};
+
+export type ImportOptions = {
+  format?: 'csv' | 'json' | 'ndjson' | 'parquet';
+  csv?: CsvOptions;
+} & LoadOptions;
+
+export type TransferSource = { table: string } | { query: string };
+
+export type ObjectType =
+  | 'table'
+  | 'view'
+  | 'materialized_view'
+  | 'index'
+  | 'trigger'
+  | 'function'
+  | 'procedure'
+  | 'extension'
+  | 'type';
+
+export type DumpOptions = {
+  tables?: string[];
+  schema?: boolean;
+  data?: boolean;
+  batch_size?: number;
+};
+
+export type DumpReport = {
+  table: string | null;
+  tables: number;
+  rows: number;
+  objects: number;
+};
+
+export type DumpProgress = DumpReport & { id: string };
+
+export type RestoreProgress = { id: string; statements: number; total: number };
+
+export type RowError = {
+  row: number;
+  column: string | null;
+  message: string;
+};
+
+export type ImportReport = {
+  rows_read: number;
+  rows_imported: number;
+  error_count: number;
+  errors: RowError[];
+  created_table: string | null;
+  unmapped: string[];
+  dry_run: boolean;
+  stopped: boolean;
+};
+
+export type ImportProgress = ImportReport & { id: string };
+
 export type ResultSet = {
   loading: boolean;
   id?: string;
   count?: number;
   affected_rows?: number;
+  written_bytes?: number | null;
   query_type?: QueryType;
-  rows?: Row[];
+  result_columns?: ResultColumn[];
+  // positional, in result_columns order
+  rows?: JSONValue[][];
   table?: string;
   foreign_keys?: Row[];
   primary_key?: Row[];
@@ -241,8 +395,28 @@ export type Table = {
 
 export const Events = {
   QueryFinished: 'query_finished',
+  ImportProgress: 'import_progress',
+  TransferProgress: 'transfer_progress',
+  DumpProgress: 'dump_progress',
+  RestoreProgress: 'restore_progress',
+  ConnectionStatus: 'connection_status',
 } as const;
 
+export type ConnectionInfo = {
+  server_version: string;
+  tls: { version: string; cipher: string } | null;
+};
+
+export type ConnectionStatus = 'Healthy' | 'Degraded' | 'Lost' | 'Reconnected';
+
+export type ConnectionStatusEvent = {
+  conn_id: string;
+  status: ConnectionStatus;
+  latency_ms: number | null;
+  attempt: number;
+  error: string | null;
+};
+
 export type QueryMetadataResult = Omit<ResultSet, 'rows' | 'id'>;
 
 export const loadingMessages = [
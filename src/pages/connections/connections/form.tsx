@@ -1,7 +1,7 @@
 import { z } from 'zod';
 import { validator } from '@felte/validator-zod';
 import { t } from 'i18next';
-import { createSignal, Match, Show, Switch } from 'solid-js';
+import { createSignal, For, Match, Show, Switch } from 'solid-js';
 import { createForm } from '@felte/solid';
 import { ColorCircle } from 'components/ui/color-circle';
 import {
@@ -18,6 +18,8 @@ import {
   SslMode,
   ModeType,
   ConnectionConfig,
+  environments,
+  Environment,
 } from 'interfaces';
 import { useAppSelector } from 'services/Context';
 import { invoke } from '@tauri-apps/api/core';
@@ -37,6 +39,7 @@ import {
   TextFieldLabel,
   TextFieldRoot,
 } from 'components/ui/textfield';
+import { TextArea } from 'components/ui/textarea';
 import { Alert, AlertDescription, AlertTitle } from 'components/ui/alert';
 import { BiSolidError } from 'solid-icons/bi';
 import {
@@ -60,6 +63,29 @@ const zstr = z
   .min(MIN_LENGTH_STR, messages.length)
   .max(MAX_LENGTH_STR, messages.length);
 
+const zcount = z
+  .string()
+  .regex(/^\d*$/, t('add_connection_form.number_validation'))
+  .optional();
+
+// pool sizing and session setup, shared by every dialect but ClickHouse
+const PoolSchema = z.object({
+  pool_min: zcount,
+  pool_max: zcount,
+  pool_idle_timeout_secs: zcount,
+  pool_max_lifetime_secs: zcount,
+  pool_acquire_timeout_secs: zcount,
+  init_sql: z.string().optional(),
+});
+
+const poolFields = [
+  'pool_min',
+  'pool_max',
+  'pool_idle_timeout_secs',
+  'pool_max_lifetime_secs',
+  'pool_acquire_timeout_secs',
+] as const;
+
 const CredentialsSchema = z.union([
   z.object({
     host: zstr,
@@ -104,7 +130,7 @@ const CredentialsSchema = z.union([
   z.object({
     path: zstr,
   }),
-]);
+]).and(PoolSchema);
 
 const schema = z.object({
   name: zstr,
@@ -112,8 +138,13 @@ const schema = z.object({
   mode: z.enum(connectionModes).default(connectionModes[0]),
   credentials: CredentialsSchema,
   color: z.enum(connectionColors),
+  folder: z.string().max(MAX_LENGTH_STR).nullish(),
+  tags: z.array(z.string()).default([]),
+  environment: z.enum(environments).default('None'),
   metadata: z.object({
     sensitive: z.boolean().default(false),
+    read_only: z.boolean().default(false),
+    trust_commands: z.boolean().default(false),
   }),
 });
 
@@ -126,6 +157,8 @@ const defaultValues = {
   name: '',
   dialect: Dialect.Postgresql,
   color: connectionColors[0],
+  environment: environments[0],
+  tags: [],
   mode: AvailableModes[Dialect.Postgresql][0],
   credentials: {
     port: 5432,
@@ -171,6 +204,7 @@ const AddConnectionForm = (props: AddConnectionFormProps) => {
   const [testing, setTesting] = createSignal(false);
   const [error, setError] = createSignal('');
   const [showCerts, setShowCerts] = createSignal(false);
+  const [showPool, setShowPool] = createSignal(false);
 
   const testConnection = async () => {
     try {
@@ -561,6 +595,60 @@ const AddConnectionForm = (props: AddConnectionFormProps) => {
               <ColorCircle color={data('color')} />
             </div>
           </div>
+          <div class="col-span-4">
+            <TextFieldRoot class="w-full" name="folder">
+              <TextFieldLabel>
+                {t('add_connection_form.labels.folder')}
+              </TextFieldLabel>
+              <TextField name="folder" size="sm" class="h-8" />
+              <TextFieldErrorMessage>{errors('folder')}</TextFieldErrorMessage>
+            </TextFieldRoot>
+          </div>
+          <div class="col-span-4">
+            <TextFieldRoot
+              class="w-full"
+              value={(data('tags') ?? []).join(', ')}
+              onChange={(value) =>
+                setFields(
+                  'tags',
+                  value.split(',').map((tag) => tag.trim())
+                )
+              }
+            >
+              <TextFieldLabel>
+                {t('add_connection_form.labels.tags')}
+              </TextFieldLabel>
+              <TextField size="sm" class="h-8" placeholder="billing, eu" />
+            </TextFieldRoot>
+          </div>
+          <div class="col-span-4">
+            <Label>{t('add_connection_form.labels.environment')}</Label>
+            <Select
+              class="w-full"
+              options={environments.map(String)}
+              value={data('environment')}
+              onChange={(value) => {
+                const environment = value as Environment;
+                setFields('environment', environment);
+                // mirrors the policies the backend applies to production
+                if (environment === 'Production') {
+                  setFields('metadata.sensitive', true);
+                  setFields('metadata.read_only', true);
+                }
+              }}
+              name="environment"
+              itemComponent={(props) => (
+                <SelectItem item={props.item}>{props.item.rawValue}</SelectItem>
+              )}
+            >
+              <SelectTrigger class="h-8 w-full">
+                <SelectValue>
+                  {(state) => state.selectedOption() as string}
+                </SelectValue>
+              </SelectTrigger>
+              <SelectContent />
+            </Select>
+          </div>
           <div class="col-span-12 py-1 flex iterms-center gap-2">
             <Show
               when={
@@ -581,6 +669,20 @@ const AddConnectionForm = (props: AddConnectionFormProps) => {
                 </div>
               </Checkbox>
             </Show>
+            <Show when={data('dialect') !== Dialect.ClickHouse}>
+              <Checkbox
+                checked={showPool()}
+                onChange={(e) => setShowPool(e)}
+                class="flex items-center gap-2"
+              >
+                <CheckboxControl class="rounded-md border-accent" />
+                <div class="grid gap-1.5 leading-none">
+                  <CheckboxLabel class="text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70">
+                    {t('add_connection_form.labels.show_pool')}
+                  </CheckboxLabel>
+                </div>
+              </Checkbox>
+            </Show>
             <Checkbox
               checked={data('metadata.sensitive')}
               name="metadata.sensitive"
@@ -604,7 +706,84 @@ const AddConnectionForm = (props: AddConnectionFormProps) => {
                 </TooltipContent>
               </Tooltip>
             </Checkbox>
+            <Checkbox
+              checked={data('metadata.read_only')}
+              name="metadata.read_only"
+              onChange={(e) => setFields('metadata.read_only', e, true)}
+              class="flex items-center gap-2"
+            >
+              <Tooltip>
+                <TooltipTrigger as="div" class="flex items-center gap-2">
+                  <CheckboxControl class="rounded-md border-accent" />
+                  <div class="grid gap-1.5 leading-none">
+                    <CheckboxLabel class="text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70">
+                      Read only
+                    </CheckboxLabel>
+                  </div>
+                </TooltipTrigger>
+                <TooltipContent class="max-w-lg">
+                  Rejects statements that write and opens every session read
+                  only, so the database refuses writes as well.
+                </TooltipContent>
+              </Tooltip>
+            </Checkbox>
+            <Checkbox
+              checked={data('metadata.trust_commands')}
+              name="metadata.trust_commands"
+              onChange={(e) => setFields('metadata.trust_commands', e, true)}
+              class="flex items-center gap-2"
+            >
+              <Tooltip>
+                <TooltipTrigger as="div" class="flex items-center gap-2">
+                  <CheckboxControl class="rounded-md border-accent" />
+                  <div class="grid gap-1.5 leading-none">
+                    <CheckboxLabel class="text-sm font-medium leading-none peer-disabled:cursor-not-allowed peer-disabled:opacity-70">
+                      Trust secret commands
+                    </CheckboxLabel>
+                  </div>
+                </TooltipTrigger>
+                <TooltipContent class="max-w-lg">
+                  Lets {'${cmd:...}'} references in the credentials run a shell
+                  command on every connect. Only enable this for connections
+                  you created yourself, imported connections start untrusted.
+                </TooltipContent>
+              </Tooltip>
+            </Checkbox>
           </div>
+          <Show when={data('dialect') !== Dialect.ClickHouse && showPool()}>
+            <For each={poolFields}>
+              {(field) => (
+                <div class="col-span-4">
+                  <TextFieldRoot class="w-full" name={`credentials.${field}`}>
+                    <TextFieldLabel>
+                      {t(`add_connection_form.labels.${field}`)}
+                    </TextFieldLabel>
+                    <TextField
+                      name={`credentials.${field}`}
+                      size="sm"
+                      class="h-8"
+                      inputMode="numeric"
+                    />
+                    <TextFieldErrorMessage>
+                      {errors(`credentials.${field}`)}
+                    </TextFieldErrorMessage>
+                  </TextFieldRoot>
+                </div>
+              )}
+            </For>
+            <div class="col-span-12">
+              <TextFieldRoot class="w-full" name="credentials.init_sql">
+                <TextFieldLabel>
+                  {t('add_connection_form.labels.init_sql')}
+                </TextFieldLabel>
+                <TextArea
+                  name="credentials.init_sql"
+                  class="font-mono text-xs"
+                  placeholder="SET time_zone = '+00:00';"
+                />
+              </TextFieldRoot>
+            </div>
+          </Show>
           <Show
             when={
               data('mode') === Mode.Host &&
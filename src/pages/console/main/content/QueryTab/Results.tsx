@@ -13,11 +13,19 @@ import {
 } from 'ag-grid-community';
 import AgGridSolid, { AgGridSolidRef } from 'ag-grid-solid';
 import { useAppSelector } from 'services/Context';
-import { loadingMessages, Row } from 'interfaces';
+import {
+  CopyFormat,
+  CsvOptions,
+  ExportFormat,
+  JsonOptions,
+  loadingMessages,
+  Row,
+} from 'interfaces';
+import { writeText } from '@tauri-apps/plugin-clipboard-manager';
 import { Pagination } from './components/Pagination';
 import { NoResults } from './components/NoResults';
 import { Loader } from 'components/ui/loader';
-import { getAnyCase } from 'utils/utils';
+import { getAnyCase, rowsToObjects } from 'utils/utils';
 import { save } from '@tauri-apps/plugin-dialog';
 import { invoke } from '@tauri-apps/api/core';
 import { deleteFrom, insert, update } from 'sql-bricks';
@@ -57,6 +65,11 @@ export const Results = (props: {
       pageSize,
       downloadCsv,
       downloadJSON,
+      downloadSql,
+      downloadXlsx,
+      downloadParquet,
+      downloadArrow,
+      downloadTable,
       selectAllFrom,
     },
     app: { cmdOrCtrl },
@@ -135,6 +148,12 @@ export const Results = (props: {
         const columns = result_set?.columns ?? [];
         const foreign_keys = result_set?.foreign_keys ?? [];
         const primary_key = result_set?.primary_key ?? [];
+        const result_columns = result_set?.result_columns ?? [];
+        // results of execute_query carry their rows inline
+        const inlineRows = rowsToObjects(
+          result_set?.rows ?? [],
+          result_columns
+        );
         const start_time = result_set?.start_time ?? 0;
         const end_time = result_set?.end_time ?? 0;
         setTable({
@@ -149,20 +168,21 @@ export const Results = (props: {
         });
         let colDef = getColumnDefs({
           columns,
+          result_columns,
           foreign_keys,
           primary_key,
           openModal,
           editable: !!props.editable,
           setChanges,
-          row: result_set?.rows?.[0] ?? {},
+          row: inlineRows[0] ?? {},
           openDrawerForm,
         });
-        if (result_set?.rows?.length) {
+        if (inlineRows.length) {
           return {
-            rows: result_set.rows,
+            rows: inlineRows,
             columns,
             colDef,
-            count: result_set.count,
+            count: result_set?.count,
             start_time,
             end_time,
           };
@@ -172,11 +192,13 @@ export const Results = (props: {
         }
         const rows = await getQueryResults(
           result_set.path!,
+          result_columns,
           pageVal,
           pageSizeVal
         );
         colDef = getColumnDefs({
           columns,
+          result_columns,
           foreign_keys,
           primary_key,
           openModal,
@@ -239,27 +261,82 @@ export const Results = (props: {
   };
 
 
-  const onBtnExport = async (t: 'csv' | 'json') => {
+  const onBtnExport = async (t: ExportFormat) => {
     if (!data()?.path) return;
     const filename = (props.table ?? '') + new Date().toISOString() + '.' + t;
     const filePath = (await save({ defaultPath: filename })) ?? '';
     const dataPath = data()?.path;
     if (!filePath || !dataPath) return;
-    if (t === 'json') {
-      await downloadJSON(dataPath, filePath).catch((error) => {
+    if (t === 'xlsx') {
+      // every result set of the script goes in its own sheet
+      const sources = getContentData('Query').result_sets.flatMap((rs) =>
+        'path' in rs && rs.path ? [rs.path] : []
+      );
+      await downloadXlsx(sources, filePath).catch((error) => {
+        toast.error('Could not download Excel', {
+          description: (error as Error).message || (error as string),
+        });
+      });
+    } else if (t === 'parquet' || t === 'arrow') {
+      const download = t === 'parquet' ? downloadParquet : downloadArrow;
+      await download(dataPath, filePath)
+        .then((invalid) => {
+          if (invalid) {
+            toast.warning(`${invalid} values were written as empty`, {
+              description: `They do not fit the type of their ${t} column`,
+            });
+          }
+        })
+        .catch((error) => {
+          toast.error(`Could not download ${t}`, {
+            description: (error as Error).message || (error as string),
+          });
+        });
+    } else if (t === 'sql') {
+      await downloadSql(getConnection().id, dataPath, filePath).catch(
+        (error) => {
+          toast.error('Could not download SQL', {
+            description: (error as Error).message || (error as string),
+          });
+        }
+      );
+    } else if (t === 'json' || t === 'ndjson') {
+      const options: JsonOptions = { format: t === 'json' ? 'array' : 'ndjson' };
+      await downloadJSON(dataPath, filePath, options).catch((error) => {
         toast.error('Could not download JSON', {
           description: (error as Error).message || (error as string),
         });
       });
     } else {
-      await downloadCsv(dataPath, filePath).catch((error) => {
-        toast.error('Could not download JSON', {
+      const options: CsvOptions =
+        t === 'tsv'
+          ? { delimiter: '\t', encoding: 'utf8_bom' }
+          : { encoding: 'utf8_bom' };
+      await downloadCsv(dataPath, filePath, options).catch((error) => {
+        toast.error(`Could not download ${t.toUpperCase()}`, {
           description: (error as Error).message || (error as string),
         });
       });
     }
   };
 
+  const onBtnCopy = async (format: CopyFormat) => {
+    const dataPath = data()?.path;
+    if (!dataPath) return;
+    try {
+      const text =
+        format === 'sql'
+          ? await downloadSql(getConnection().id, dataPath, null)
+          : await downloadTable(dataPath, null, { format });
+      await writeText(text ?? '');
+      toast.success(t('console.table.copied'));
+    } catch (error) {
+      toast.error('Could not copy results', {
+        description: (error as Error).message || (error as string),
+      });
+    }
+  };
+
   const applyChanges = async () => {
     try {
       const _table = table.name;
@@ -488,6 +565,7 @@ export const Results = (props: {
             },
             onPageSizeChange,
             onBtnExport,
+            onBtnCopy,
             openDrawerForm: props.editable ? openDrawerForm : undefined,
           }}
         />
@@ -1,8 +1,15 @@
 import { GridApi } from 'ag-grid-community';
-import { Row } from 'interfaces';
-import { For } from 'solid-js';
-import { getAnyCase, parseObjRecursive } from 'utils/utils';
+import { ColumnKind, Row } from 'interfaces';
+import { For, Show } from 'solid-js';
+import {
+  binaryImageUrl,
+  getAnyCase,
+  isBinaryImage,
+  parseObjRecursive,
+} from 'utils/utils';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
+import { invoke } from '@tauri-apps/api/core';
+import { toast } from 'solid-sonner';
 import { t } from 'utils/i18n';
 import { Changes } from './utils';
 import { SetStoreFunction } from 'solid-js/store';
@@ -12,6 +19,12 @@ import {
   ContextMenuItem,
   ContextMenuTrigger,
 } from 'components/ui/context-menu';
+import {
+  HoverCard,
+  HoverCardContent,
+  HoverCardTrigger,
+} from 'components/ui/hover-card';
+import { FiImage } from 'solid-icons/fi';
 
 const rowsActions = [
   {
@@ -29,6 +42,11 @@ const rowsActions = [
     editable: false,
     label: t('console.table.row_actions.copy_cell'),
   },
+  {
+    action: 'copy-geojson',
+    editable: false,
+    label: t('console.table.row_actions.copy_geojson'),
+  },
   {
     action: 'edit-row',
     editable: true,
@@ -76,6 +94,9 @@ export type PopupCellRendererProps = {
   primary_key: Row[];
   setChanges: SetStoreFunction<Changes>;
   openDrawerForm: (s: Pick<DrawerState, 'mode' | 'rowIndex' | 'data'>) => void;
+  kind?: ColumnKind;
+  // PostGIS and MySQL geometries, shown as WKT
+  geometry?: boolean;
 };
 
 const PopupCellRenderer = (props: PopupCellRendererProps) => {
@@ -94,6 +115,19 @@ const PopupCellRenderer = (props: PopupCellRendererProps) => {
       await writeText(String(props.value));
       return;
     }
+    if (option === 'copy-geojson') {
+      try {
+        const geojson = await invoke('geometry_to_geojson', {
+          wkt: String(props.value),
+        });
+        await writeText(JSON.stringify(geojson));
+      } catch (error) {
+        toast.error('Could not convert the geometry', {
+          description: (error as Error).message || (error as string),
+        });
+      }
+      return;
+    }
 
     if (option === 'add-row') {
       props.openDrawerForm({
@@ -106,7 +140,10 @@ const PopupCellRenderer = (props: PopupCellRendererProps) => {
     if (option === 'delete-row') {
       const condition = props.primary_key.reduce((acc, c) => {
         const col = getAnyCase(c, 'column_name');
-        return { ...acc, [col]: props.data[col] };
+        return {
+          ...acc,
+          [col]: props.data[col],
+        };
       }, {});
       const key = Object.values(condition).join('_');
       props.setChanges('delete', key, {
@@ -136,12 +173,30 @@ const PopupCellRenderer = (props: PopupCellRendererProps) => {
     }
   };
 
+  const cell = () => props.data[props.column.getColId()];
+
   return (
     <ContextMenu>
-      <ContextMenuTrigger>{props.value}</ContextMenuTrigger>
+      <ContextMenuTrigger>
+        <Show when={isBinaryImage(props.kind, cell())}>
+          <HoverCard>
+            <HoverCardTrigger as="span" class="mr-1 align-middle">
+              <FiImage class="inline" />
+            </HoverCardTrigger>
+            <HoverCardContent class="w-auto p-2">
+              <img
+                src={binaryImageUrl(props.kind, cell())}
+                class="max-h-64 max-w-64"
+              />
+            </HoverCardContent>
+          </HoverCard>
+        </Show>
+        {props.value}
+      </ContextMenuTrigger>
       <ContextMenuContent>
         <For
           each={rowsActions.filter((r) => {
+            if (r.action === 'copy-geojson' && !props.geometry) return false;
             return !r.editable || r.editable === props.editable;
           })}
         >
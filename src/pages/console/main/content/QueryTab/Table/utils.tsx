@@ -2,8 +2,14 @@ import PopupCellRenderer, {
   DrawerState,
   PopupCellRendererProps,
 } from './PopupCellRenderer';
-import { getAnyCase } from 'utils/utils';
-import { Row } from 'interfaces';
+import {
+  binaryValue,
+  compareNumbers,
+  getAnyCase,
+  isNumericKind,
+  uniqueNames,
+} from 'utils/utils';
+import { ColumnKind, ResultColumn, Row } from 'interfaces';
 import { Show } from 'solid-js';
 import { ColDef } from 'ag-grid-community';
 import { IoKey as Key } from 'solid-icons/io';
@@ -12,6 +18,11 @@ import { SetStoreFunction } from 'solid-js/store';
 import { Tooltip } from '@kobalte/core/tooltip';
 import { TooltipTrigger, TooltipContent } from 'components/ui/tooltip';
 
+const formatCellValue = (kind: ColumnKind | undefined, val: unknown) => {
+  if (typeof val === 'boolean') return String(val);
+  return binaryValue(kind, val) ?? val;
+};
+
 export type UpdageChange = {
   [rowIndex: string]: {
     condition: Row;
@@ -77,6 +88,7 @@ const headerComponent = (
 
 export const getColumnDefs = ({
   columns,
+  result_columns,
   foreign_keys,
   primary_key,
   openModal,
@@ -86,6 +98,7 @@ export const getColumnDefs = ({
   openDrawerForm,
 }: {
   columns: Row[];
+  result_columns: ResultColumn[];
   foreign_keys: Row[];
   primary_key: Row[];
   openModal: (code: string) => void;
@@ -94,12 +107,25 @@ export const getColumnDefs = ({
   row: Row;
   openDrawerForm: (s: Pick<DrawerState, 'mode' | 'data' | 'rowIndex'>) => void;
 }): ColDef[] => {
-  const cellRenderer = (p: PopupCellRendererProps) => (
-    <PopupCellRenderer
-      {...p}
-      {...{ setChanges, editable, openModal, primary_key, openDrawerForm }}
-    />
-  );
+  const names = uniqueNames(result_columns);
+  // the kind of the result column tells how to sort and show its values
+  const kindDef = (field: string): ColDef => {
+    const column = result_columns[names.indexOf(field)];
+    const kind = column?.kind;
+    const geometry = /^(geometry|geography)\b/i.test(column?.type ?? '');
+    return {
+      cellRenderer: (p: PopupCellRendererProps) => (
+        <PopupCellRenderer
+          {...p}
+          {...{ setChanges, editable, openModal, primary_key, openDrawerForm }}
+          kind={kind}
+          geometry={geometry}
+        />
+      ),
+      comparator: isNumericKind(kind) ? compareNumbers : undefined,
+      valueGetter: (r) => formatCellValue(kind, r.data[field]),
+    };
+  };
 
   if (columns.length) {
     return columns.map((col, _i) => {
@@ -115,7 +141,7 @@ export const getColumnDefs = ({
       const visible_type = getAnyCase(col, 'column_type') || '';
 
       return {
-        cellRenderer,
+        ...kindDef(field),
         cellDataType: 'text',
         editable,
         headerComponent: () =>
@@ -130,25 +156,17 @@ export const getColumnDefs = ({
               : undefined,
           }),
         field,
-        valueGetter: (r) => {
-          const val = r.data[field];
-          return typeof val === 'boolean' ? String(val) : val;
-        },
         headerName: field,
       };
     });
   }
   return Object.keys(row).map((field, _i) => {
     return {
+      ...kindDef(field),
       editable,
       cellDataType: 'text',
-      cellRenderer,
       headerComponent: () => headerComponent(field, {}),
       field,
-      valueGetter: (r) => {
-        const val = r.data[field];
-        return typeof val === 'boolean' ? String(val) : val;
-      },
       headerName: field,
     };
   });
@@ -1,11 +1,11 @@
 import { createShortcut } from '@solid-primitives/keyboard';
-import { Accessor, createEffect, Match, Show, Switch } from 'solid-js';
+import { Accessor, createEffect, For, Match, Show, Switch } from 'solid-js';
 import {
   FaSolidChevronLeft as ChevronLeft,
   FaSolidChevronRight as ChevronRight,
 } from 'solid-icons/fa';
 import { createStore } from 'solid-js/store';
-import { QueryType, ResultSet } from 'interfaces';
+import { CopyFormat, ExportFormat, QueryType, ResultSet } from 'interfaces';
 import { Alert } from 'components/ui/alert';
 import { useAppSelector } from 'services/Context';
 import { t } from 'utils/i18n';
@@ -21,6 +21,11 @@ import {
 import { Loader } from 'components/ui/loader';
 import { Tooltip, TooltipContent, TooltipTrigger } from 'components/ui/tooltip';
 import { Kbd } from 'components/ui/kbd';
+import {
+  Popover,
+  PopoverContent,
+  PopoverTrigger,
+} from 'components/ui/popover';
 import { CgInfo } from 'solid-icons/cg';
 import { TooltipTriggerProps } from '@kobalte/core/tooltip';
 
@@ -31,7 +36,8 @@ type PaginationProps = {
   onPageSizeChange: () => void;
   onPrevPage: () => void;
   onNextPage: () => void;
-  onBtnExport: (t: 'csv' | 'json') => void;
+  onBtnExport: (t: ExportFormat) => void;
+  onBtnCopy: (t: CopyFormat) => void;
   applyChanges: () => void;
   undoChanges: () => void;
   openDrawerForm?: (s: Pick<DrawerState, 'mode' | 'rowIndex' | 'data'>) => void;
@@ -46,6 +52,16 @@ type PaginationProps = {
 
 const PAGE_SIZE_OPTIONS = [10, 25, 50, 100];
 
+const MORE_EXPORTS: ExportFormat[] = [
+  'tsv',
+  'ndjson',
+  'xlsx',
+  'parquet',
+  'arrow',
+  'sql',
+];
+const COPY_FORMATS: CopyFormat[] = ['sql', 'markdown', 'html', 'ascii'];
+
 export const Pagination = (props: PaginationProps) => {
   const {
     connections: { selectNextQuery, selectPrevQuery, queryIdx, getContentData },
@@ -125,6 +141,12 @@ export const Pagination = (props: PaginationProps) => {
                   resultSet?.affected_rows +
                     ' ' +
                     t('console.table.affected_rows')}
+                <Show when={resultSet?.written_bytes}>
+                  {' · ' +
+                    t('console.table.written_bytes', {
+                      bytes: resultSet?.written_bytes,
+                    })}
+                </Show>
               </Alert>
             </Show>
           </div>
@@ -234,6 +256,39 @@ export const Pagination = (props: PaginationProps) => {
             >
               {t('console.table.json')}
             </Button>
+            <Popover>
+              <PopoverTrigger as={Button} variant="outline" size="sm">
+                {t('console.table.more_exports')}
+              </PopoverTrigger>
+              <PopoverContent class="w-56 p-2 pt-8">
+                <div class="flex flex-col">
+                  <For each={MORE_EXPORTS}>
+                    {(format) => (
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        class="justify-start"
+                        onClick={() => props.onBtnExport(format)}
+                      >
+                        {t(`console.table.${format}`)}
+                      </Button>
+                    )}
+                  </For>
+                  <For each={COPY_FORMATS}>
+                    {(format) => (
+                      <Button
+                        variant="ghost"
+                        size="sm"
+                        class="justify-start"
+                        onClick={() => props.onBtnCopy(format)}
+                      >
+                        {t(`console.table.copy_${format}`)}
+                      </Button>
+                    )}
+                  </For>
+                </div>
+              </PopoverContent>
+            </Popover>
           </Show>
           <Select
             class="w-20"
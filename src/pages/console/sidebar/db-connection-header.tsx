@@ -10,20 +10,65 @@ import {
   HoverCardTrigger,
 } from 'components/ui/hover-card';
 import { useAppSelector } from 'services/Context';
-import { Match, Show, Switch } from 'solid-js';
+import { createResource, createSignal, Match, Show, Switch } from 'solid-js';
 import { cn } from 'utils/cn';
 import { useCommandPalette } from 'services/palette/context';
 import { Kbd } from 'components/ui/kbd';
+import { Button } from 'components/ui/button';
+import { invoke } from '@tauri-apps/api/core';
+import { toast } from 'solid-sonner';
+import { ConnectionInfo, ConnectionStatus } from 'interfaces';
+
+const DEGRADED_LATENCY_MS = 1000;
+
+const statusColors: Record<ConnectionStatus, string> = {
+  Healthy: 'bg-green-500',
+  Reconnected: 'bg-green-500',
+  Degraded: 'bg-amber-500',
+  Lost: 'bg-red-500',
+};
 
 export function DbConnectionHeader() {
   const {
-    connections: { getConnection },
+    connections: { getConnection, setConnectionStatus },
   } = useAppSelector();
   const { setOpen } = useCommandPalette();
   const conn = getConnection();
+  const [pinging, setPinging] = createSignal(false);
+  const [info] = createResource(async () => {
+    try {
+      return await invoke<ConnectionInfo>('get_connection_info', {
+        connId: conn.id,
+      });
+    } catch {
+      return undefined;
+    }
+  });
 
   const host = conn.connection.credentials.host;
 
+  const ping = async () => {
+    try {
+      setPinging(true);
+      const latency = await invoke<number>('ping_connection', {
+        connId: conn.id,
+      });
+      setConnectionStatus({
+        conn_id: conn.id,
+        status: latency > DEGRADED_LATENCY_MS ? 'Degraded' : 'Healthy',
+        latency_ms: latency,
+        attempt: 0,
+        error: null,
+      });
+    } catch (error) {
+      toast.error('Could not reach the database', {
+        description: (error as Error).message || (error as string),
+      });
+    } finally {
+      setPinging(false);
+    }
+  };
+
   return (
     <HoverCard>
       <HoverCardTrigger>
@@ -47,7 +92,20 @@ export function DbConnectionHeader() {
                   <DialectIcon dialect={conn.connection.dialect} class="size-4" />
                 </div>
                 <div class="flex flex-col gap-0.5 leading-none">
-                  <span class="font-semibold">{conn.connection.name}</span>
+                  <span class="font-semibold flex items-center gap-1.5">
+                    {conn.connection.name}
+                    <Show when={conn.status}>
+                      {(status) => (
+                        <span
+                          title={status().error ?? status().status}
+                          class={cn(
+                            'size-2 rounded-full',
+                            statusColors[status().status]
+                          )}
+                        />
+                      )}
+                    </Show>
+                  </span>
                   <span class="text-xs opacity-60 overflow-hidden text-ellipsis truncate max-w-[20ch]">
                     {host}
                   </span>
@@ -107,6 +165,57 @@ export function DbConnectionHeader() {
               <td class="font-bold">Sensitive</td>
               <td>{conn.connection.metadata.sensitive ? 'Yes' : 'No'} (Require confirmation on sensitive queries)</td>
             </tr>
+            <Show when={info()}>
+              {(info) => (
+                <>
+                  <tr>
+                    <td class="font-bold">Server</td>
+                    <td>{info().server_version}</td>
+                  </tr>
+                  <tr>
+                    <td class="font-bold">Encryption</td>
+                    <td classList={{ 'text-destructive': !info().tls }}>
+                      {info().tls
+                        ? `${info().tls?.version} (${info().tls?.cipher})`
+                        : 'Not encrypted'}
+                    </td>
+                  </tr>
+                </>
+              )}
+            </Show>
+            <tr>
+              <td class="font-bold">Status</td>
+              <td>
+                <div class="flex items-center gap-2">
+                  <span>
+                    {conn.status?.status ?? 'Unknown'}
+                    <Show when={conn.status?.latency_ms != null}>
+                      {` (${conn.status?.latency_ms} ms)`}
+                    </Show>
+                    <Show when={conn.status?.status === 'Lost' && conn.status.attempt}>
+                      {`, reconnect attempt ${conn.status?.attempt}`}
+                    </Show>
+                  </span>
+                  <Button
+                    size="sm"
+                    variant="secondary"
+                    class="h-6 px-2 text-xs"
+                    disabled={pinging()}
+                    onClick={ping}
+                  >
+                    Ping
+                  </Button>
+                </div>
+              </td>
+            </tr>
+            <Show when={conn.status?.error}>
+              <tr>
+                <td class="font-bold">Last error</td>
+                <td class="max-w-[40ch] truncate" title={conn.status?.error ?? ''}>
+                  {conn.status?.error}
+                </td>
+              </tr>
+            </Show>
           </tbody>
         </table>
       </HoverCardContent>
@@ -1,6 +1,12 @@
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { Button } from 'components/ui/button';
-import { BsTerminalFill as Terminal } from 'solid-icons/bs';
+import {
+  BsDownload as Download,
+  BsTerminalFill as Terminal,
+  BsUpload as Upload,
+} from 'solid-icons/bs';
 import { IoRefresh as Refresh } from 'solid-icons/io';
 import {
   Select,
@@ -9,7 +15,12 @@ import {
   SelectTrigger,
   SelectValue,
 } from 'components/ui/select';
-import { ResultSet } from 'interfaces';
+import {
+  DumpProgress,
+  Events,
+  ResultSet,
+  RestoreProgress,
+} from 'interfaces';
 import { newContentTab } from 'services/Connections';
 import { useAppSelector } from 'services/Context';
 import { createEffect, createSignal, Match, Show, Switch } from 'solid-js';
@@ -46,6 +57,7 @@ export const SchemaSelect = () => {
       updateSchemaDefinition,
       refreshEntities,
     },
+    backend: { dumpSchema, restoreScript },
   } = useAppSelector();
   const conn = getConnection();
   const [loading, setLoading] = createSignal(false);
@@ -108,6 +120,80 @@ export const SchemaSelect = () => {
     setLoading(false);
   };
 
+  const dump = async () => {
+    const conn = getConnection();
+    const schema = conn.selectedSchema;
+    const destination = await save({
+      title: t('sidebar.dump_schema'),
+      defaultPath: `${schema.split('/').pop()}.sql`,
+      filters: [{ name: 'SQL', extensions: ['sql'] }],
+    });
+    if (!destination) return;
+    const id = randomId();
+    const toastId = toast.loading(t('sidebar.dumping', { schema }));
+    const unlisten = await listen<DumpProgress>(
+      Events.DumpProgress,
+      ({ payload }) => {
+        if (payload.id !== id || !payload.table) return;
+        toast.loading(
+          t('sidebar.dump_progress', {
+            table: payload.table,
+            rows: payload.rows,
+          }),
+          { id: toastId }
+        );
+      }
+    );
+    try {
+      const report = await dumpSchema(conn.id, id, destination);
+      toast.success(
+        t('sidebar.dumped', { tables: report.tables, rows: report.rows }),
+        { id: toastId, description: destination }
+      );
+    } catch (error) {
+      toast.error('Could not dump schema', {
+        id: toastId,
+        description: (error as Error).message || (error as string),
+      });
+    } finally {
+      unlisten();
+    }
+  };
+
+  const restore = async () => {
+    const conn = getConnection();
+    const schema = conn.selectedSchema;
+    const path = (await open({
+      multiple: false,
+      title: t('sidebar.restore_script'),
+      filters: [{ name: 'SQL', extensions: ['sql'] }],
+    })) as string;
+    if (!path) return;
+    const id = randomId();
+    const toastId = toast.loading(t('sidebar.restoring', { schema }));
+    const unlisten = await listen<RestoreProgress>(
+      Events.RestoreProgress,
+      ({ payload }) => {
+        if (payload.id !== id) return;
+        toast.loading(t('sidebar.restore_progress', payload), {
+          id: toastId,
+        });
+      }
+    );
+    try {
+      const total = await restoreScript(conn.id, id, path);
+      toast.success(t('sidebar.restored', { total }), { id: toastId });
+      await refreshEntities();
+    } catch (error) {
+      toast.error('Could not restore', {
+        id: toastId,
+        description: (error as Error).message || (error as string),
+      });
+    } finally {
+      unlisten();
+    }
+  };
+
   const dropDatabase = async (schema: string) => {
     try {
       const selectedSchema = getConnection().selectedSchema;
@@ -204,6 +290,28 @@ export const SchemaSelect = () => {
           <TooltipContent>{t('sidebar.show_process_list')}</TooltipContent>
         </Tooltip>
       </Show>
+      <Tooltip>
+        <TooltipTrigger
+          onClick={dump}
+          variant="ghost"
+          class="p-1 size-4 flex items-center"
+          as={Button}
+        >
+          <Download class="size-4" />
+        </TooltipTrigger>
+        <TooltipContent>{t('sidebar.dump_schema')}</TooltipContent>
+      </Tooltip>
+      <Tooltip>
+        <TooltipTrigger
+          onClick={restore}
+          variant="ghost"
+          class="p-1 size-4 flex items-center"
+          as={Button}
+        >
+          <Upload class="size-4" />
+        </TooltipTrigger>
+        <TooltipContent>{t('sidebar.restore_script')}</TooltipContent>
+      </Tooltip>
       <Tooltip>
         <TooltipTrigger
           onClick={refresh}
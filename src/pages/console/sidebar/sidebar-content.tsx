@@ -5,7 +5,7 @@ import { RiEditorFunctions as Function } from 'solid-icons/ri';
 import { BsShare as ShareNodes } from 'solid-icons/bs';
 import { VsChromeClose as Close } from 'solid-icons/vs';
 import { invoke } from '@tauri-apps/api/core';
-import { ResultSet, Row, Table } from 'interfaces';
+import { ObjectType, ResultSet, Row, Table } from 'interfaces';
 import { newContentTab } from 'services/Connections';
 import { getAnyCase, randomId } from 'utils/utils';
 // @ts-ignore
@@ -53,6 +53,7 @@ export const DbSidebarContent = () => {
       getSchemaEntity,
       refreshEntities,
     },
+    backend: { getCreateStatement },
   } = useAppSelector();
   const [itemCollapseState, setItemCollapseState] = createStore<
     Record<string, boolean>
@@ -103,9 +104,20 @@ export const DbSidebarContent = () => {
     }
   };
 
-  const showCreateStatement = (name: string, stmt: string) => {
-    const data = { query: stmt, result_sets: [], id: randomId() };
-    addContentTab(newContentTab(name, 'Query', data));
+  const showCreateStatement = async (objectType: ObjectType, name: string) => {
+    try {
+      const query = await getCreateStatement(
+        getConnection().id,
+        objectType,
+        name
+      );
+      const data = { query, result_sets: [], id: randomId() };
+      addContentTab(newContentTab(name, 'Query', data));
+    } catch (error) {
+      toast.error('Could not show create statement', {
+        description: (error as Error).message || (error as string),
+      });
+    }
   };
 
   const showTrigger = async (trigger: string) => {
@@ -169,10 +181,7 @@ export const DbSidebarContent = () => {
           name: (row: Row) => getAnyCase(row, 'routine_name'),
           show: (row: Row) => showRoutine(getAnyCase(row, 'routine_name')),
           statement: (row: Row) =>
-            showCreateStatement(
-              getAnyCase(row, 'routine_name'),
-              getAnyCase(row, 'routine_definition')
-            ),
+            showCreateStatement('procedure', getAnyCase(row, 'routine_name')),
         },
         {
           title: 'Triggers',
@@ -181,10 +190,7 @@ export const DbSidebarContent = () => {
           icon: () => <ShareNodes class="size-4 text-yellow-500" />,
           show: (row: Row) => showTrigger(getAnyCase(row, 'trigger_name')),
           statement: (row: Row) =>
-            showCreateStatement(
-              getAnyCase(row, 'trigger_name'),
-              getAnyCase(row, 'action_statement')
-            ),
+            showCreateStatement('trigger', getAnyCase(row, 'trigger_name')),
           name: (row: Row) =>
             getAnyCase(row, 'trigger_name') +
             (getAnyCase(row, 'event_object_table')
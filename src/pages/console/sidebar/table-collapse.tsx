@@ -1,5 +1,8 @@
 import { createSignal, For, Show } from 'solid-js';
+import { createStore, reconcile } from 'solid-js/store';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { open, save } from '@tauri-apps/plugin-dialog';
 import { t } from 'utils/i18n';
 import { useAppSelector } from 'services/Context';
 import { TbTable as Table } from 'solid-icons/tb';
@@ -8,8 +11,8 @@ import {
   newContentTab,
   TableStructureContentTabData,
 } from 'services/Connections';
-import { Column, ResultSet } from 'interfaces';
-import { getAnyCase } from 'utils/utils';
+import { Column, Events, ImportProgress, ResultSet } from 'interfaces';
+import { getAnyCase, randomId } from 'utils/utils';
 import {
   Collapsible,
   CollapsibleContent,
@@ -44,6 +47,18 @@ import {
   DialogFooter,
 } from 'components/ui/dialog';
 import { Button } from 'components/ui/button';
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from 'components/ui/select';
+import {
+  Checkbox,
+  CheckboxControl,
+  CheckboxLabel,
+} from 'components/ui/checkbox';
 
 type TableColumnsCollapseProps = {
   entity: 'views' | 'tables';
@@ -61,15 +76,35 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
   >('');
   const [renameOpen, setRenameOpen] = createSignal(false);
   const [newName, setNewName] = createSignal('');
+  const [copyOpen, setCopyOpen] = createSignal(false);
+  const [copyTarget, setCopyTarget] = createSignal('');
+  const [copyTable, setCopyTable] = createSignal('');
+  const [createTable, setCreateTable] = createSignal(true);
+  const [importOpen, setImportOpen] = createSignal(false);
+  const [importPath, setImportPath] = createSignal('');
+  // target column for each source column of the file, '' to skip it
+  const [mapping, setMapping] = createStore<Record<string, string>>({});
+  const [importCreate, setImportCreate] = createSignal(false);
+  const [dryRun, setDryRun] = createSignal(false);
+  const [maxErrors, setMaxErrors] = createSignal(0);
 
   const {
     connections: {
+      store,
       insertColumnName,
       addContentTab,
       getConnection,
       updateDataContentTab,
     },
-    backend: { selectAllFrom },
+    backend: {
+      selectAllFrom,
+      importColumns,
+      importFile,
+      transferData,
+      cancelTask,
+      dumpSchema,
+      getCreateStatement,
+    },
   } = useAppSelector();
 
   const addTableStructureTab = async (table: string) => {
@@ -89,6 +124,23 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
     }
   };
 
+  const showCreateStatement = async () => {
+    try {
+      const objectType = props.entity === 'views' ? 'view' : 'table';
+      const query = await getCreateStatement(
+        getConnection().id,
+        objectType,
+        table
+      );
+      const data = { query, result_sets: [], id: randomId() };
+      addContentTab(newContentTab(table, 'Query', data));
+    } catch (error) {
+      toast.error('Could not show create statement', {
+        description: (error as Error).message || (error as string),
+      });
+    }
+  };
+
   const listData = async () => {
     try {
       const conn = getConnection();
@@ -173,6 +225,201 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
     }
   };
 
+  const importData = async () => {
+    const path = (await open({
+      multiple: false,
+      title: t('sidebar.import_data'),
+      filters: [
+        {
+          name: 'Data',
+          extensions: [
+            'csv',
+            'tsv',
+            'txt',
+            'json',
+            'ndjson',
+            'jsonl',
+            'parquet',
+          ],
+        },
+      ],
+    })) as string;
+    if (!path) return;
+    try {
+      const columns = await importColumns(path);
+      const targets = props.columns.map((c) => c.name);
+      const match = (name: string) =>
+        targets.find((c) => c.toLowerCase() === name.toLowerCase()) ?? '';
+      setMapping(
+        reconcile(Object.fromEntries(columns.map((c) => [c, match(c)])))
+      );
+      setImportPath(path);
+      setImportOpen(true);
+    } catch (error) {
+      toast.error('Could not read the file', {
+        description: (error as Error).message || (error as string),
+      });
+    }
+  };
+
+  const runImport = async () => {
+    setImportOpen(false);
+    const id = randomId();
+    const cancel = {
+      label: t('sidebar.cancel'),
+      onClick: () => cancelTask([id]),
+    };
+    const toastId = toast.loading(t('sidebar.importing', { table }), {
+      action: cancel,
+    });
+    const unlisten = await listen<ImportProgress>(
+      Events.ImportProgress,
+      ({ payload }) => {
+        if (payload.id !== id) return;
+        const rows = dryRun() ? payload.rows_read : payload.rows_imported;
+        const key = dryRun()
+          ? 'sidebar.check_progress'
+          : 'sidebar.import_progress';
+        toast.loading(t(key, { rows, table }), { id: toastId, action: cancel });
+      }
+    );
+    try {
+      const report = await importFile(
+        getConnection().id,
+        id,
+        importPath(),
+        table,
+        {
+          mapping: Object.fromEntries(
+            Object.entries(mapping).filter(([, target]) => target)
+          ),
+          create_table: importCreate(),
+          dry_run: dryRun(),
+          max_errors: maxErrors(),
+        }
+      );
+      const rows = report.dry_run ? report.rows_read : report.rows_imported;
+      const errors = report.error_count;
+      const description = report.errors
+        .slice(0, 3)
+        .map((e) => `#${e.row} ${e.column ?? ''} ${e.message}`)
+        .join('\n');
+      if (report.dry_run) {
+        const message = t('sidebar.checked', { rows, table, errors });
+        const show = errors ? toast.error : toast.success;
+        show(message, { id: toastId, action: undefined, description });
+      } else if (errors) {
+        toast.error(t('sidebar.import_errors', { rows, table, errors }), {
+          id: toastId,
+          action: undefined,
+          description,
+        });
+      } else {
+        toast.success(t('sidebar.imported', { rows, table }), {
+          id: toastId,
+          action: undefined,
+        });
+      }
+    } catch (error) {
+      toast.error('Could not import data', {
+        id: toastId,
+        action: undefined,
+        description: (error as Error).message || (error as string),
+      });
+    } finally {
+      unlisten();
+    }
+  };
+
+  const copyToConnection = async () => {
+    const target = store.connections.find((c) => c.id === copyTarget());
+    const name = copyTable().trim();
+    if (!target || !name) return;
+    setCopyOpen(false);
+    const id = randomId();
+    const cancel = {
+      label: t('sidebar.cancel'),
+      onClick: () => cancelTask([id]),
+    };
+    const toastId = toast.loading(t('sidebar.copying', { table }), {
+      action: cancel,
+    });
+    const unlisten = await listen<ImportProgress>(
+      Events.TransferProgress,
+      ({ payload }) => {
+        if (payload.id !== id) return;
+        const rows = payload.rows_imported;
+        toast.loading(t('sidebar.copy_progress', { rows, table: name }), {
+          id: toastId,
+          action: cancel,
+        });
+      }
+    );
+    try {
+      const report = await transferData(
+        getConnection().id,
+        { table },
+        target.id,
+        name,
+        id,
+        { create_table: createTable() }
+      );
+      const rows = report.rows_imported;
+      if (report.error_count) {
+        const errors = report.error_count;
+        toast.error(t('sidebar.copy_errors', { rows, table: name, errors }), {
+          id: toastId,
+          action: undefined,
+          description: report.errors
+            .slice(0, 3)
+            .map((e) => `#${e.row} ${e.column ?? ''} ${e.message}`)
+            .join('\n'),
+        });
+      } else {
+        toast.success(t('sidebar.copied', { rows, table: name }), {
+          id: toastId,
+          action: undefined,
+        });
+      }
+      if (target.id === getConnection().id) await props.refresh();
+    } catch (error) {
+      toast.error('Could not copy data', {
+        id: toastId,
+        action: undefined,
+        description: (error as Error).message || (error as string),
+      });
+    } finally {
+      unlisten();
+    }
+  };
+
+  const dumpTable = async () => {
+    const destination = await save({
+      title: t('sidebar.dump_table'),
+      defaultPath: `${table}.sql`,
+      filters: [{ name: 'SQL', extensions: ['sql'] }],
+    });
+    if (!destination) return;
+    const toastId = toast.loading(t('sidebar.dumping', { schema: table }));
+    try {
+      const report = await dumpSchema(
+        getConnection().id,
+        randomId(),
+        destination,
+        { tables: [table] }
+      );
+      toast.success(
+        t('sidebar.dumped', { tables: report.tables, rows: report.rows }),
+        { id: toastId, description: destination }
+      );
+    } catch (error) {
+      toast.error('Could not dump table', {
+        id: toastId,
+        description: (error as Error).message || (error as string),
+      });
+    }
+  };
+
   const acceptAction = () => {
     if (dialogAction() === 'drop') {
       dropTable();
@@ -250,6 +497,9 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
             >
               {t('sidebar.show_table_structure')}
             </ContextMenuItem>
+            <ContextMenuItem onClick={showCreateStatement}>
+              {t('sidebar.show_create_statement')}
+            </ContextMenuItem>
             <ContextMenuItem onClick={listData}>
               {t('sidebar.view_data')}
             </ContextMenuItem>
@@ -262,6 +512,21 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
               Rename
             </ContextMenuItem>
             <Show when={props.entity === 'tables'}>
+              <ContextMenuItem onClick={importData}>
+                {t('sidebar.import_data')}
+              </ContextMenuItem>
+              <ContextMenuItem
+                onSelect={() => {
+                  setCopyTarget(getConnection().id);
+                  setCopyTable(table);
+                  setCopyOpen(true);
+                }}
+              >
+                {t('sidebar.copy_to_connection')}
+              </ContextMenuItem>
+              <ContextMenuItem onClick={dumpTable}>
+                {t('sidebar.dump_table')}
+              </ContextMenuItem>
               <AlertDialogTrigger class="w-full">
                 <ContextMenuItem onSelect={() => setDialogAction('truncate')}>
                   {t('sidebar.truncate_table')}
@@ -332,6 +597,175 @@ export const TableColumnsCollapse = (props: TableColumnsCollapseProps) => {
           </form>
         </DialogContent>
       </Dialog>
+      <Dialog open={importOpen()} onOpenChange={setImportOpen}>
+        <DialogContent class="max-w-md">
+          <DialogHeader>
+            <DialogTitle>{t('sidebar.import_data')}</DialogTitle>
+          </DialogHeader>
+          <form
+            onSubmit={(e) => {
+              e.preventDefault();
+              runImport();
+            }}
+          >
+            <div class="flex flex-col gap-3">
+              <span class="text-xs truncate">{importPath()}</span>
+              <div class="flex flex-col gap-1 max-h-64 overflow-auto">
+                <For each={Object.keys(mapping)}>
+                  {(source) => (
+                    <div class="flex items-center justify-between gap-2">
+                      <span class="text-sm truncate">{source}</span>
+                      <Select
+                        value={mapping[source]}
+                        options={['', ...props.columns.map((c) => c.name)]}
+                        onChange={(value) => setMapping(source, value ?? '')}
+                        itemComponent={(props) => (
+                          <SelectItem item={props.item}>
+                            {props.item.rawValue || t('sidebar.import_skip')}
+                          </SelectItem>
+                        )}
+                      >
+                        <SelectTrigger class="w-48">
+                          <SelectValue<string>>
+                            {(state) =>
+                              state.selectedOption() ||
+                              t('sidebar.import_skip')
+                            }
+                          </SelectValue>
+                        </SelectTrigger>
+                        <SelectContent />
+                      </Select>
+                    </div>
+                  )}
+                </For>
+              </div>
+              <Checkbox
+                checked={importCreate()}
+                onChange={setImportCreate}
+                class="flex items-center gap-2"
+              >
+                <CheckboxControl class="rounded-md border-accent" />
+                <CheckboxLabel class="text-sm font-medium leading-none">
+                  {t('sidebar.copy_create_table')}
+                </CheckboxLabel>
+              </Checkbox>
+              <Checkbox
+                checked={dryRun()}
+                onChange={setDryRun}
+                class="flex items-center gap-2"
+              >
+                <CheckboxControl class="rounded-md border-accent" />
+                <CheckboxLabel class="text-sm font-medium leading-none">
+                  {t('sidebar.import_dry_run')}
+                </CheckboxLabel>
+              </Checkbox>
+              <TextFieldRoot class="flex items-center justify-between gap-2">
+                <span class="text-sm">{t('sidebar.import_max_errors')}</span>
+                <TextField
+                  type="number"
+                  min="0"
+                  class="w-24"
+                  value={maxErrors()}
+                  onInput={(e: InputEvent) =>
+                    setMaxErrors(
+                      Math.max(
+                        0,
+                        Number((e.target as HTMLInputElement).value) || 0
+                      )
+                    )
+                  }
+                />
+              </TextFieldRoot>
+              <DialogFooter>
+                <Button
+                  variant="outline"
+                  onClick={() => setImportOpen(false)}
+                >
+                  Cancel
+                </Button>
+                <Button
+                  type="submit"
+                  disabled={!Object.values(mapping).some((target) => target)}
+                >
+                  {dryRun()
+                    ? t('sidebar.import_check')
+                    : t('sidebar.import_data')}
+                </Button>
+              </DialogFooter>
+            </div>
+          </form>
+        </DialogContent>
+      </Dialog>
+      <Dialog open={copyOpen()} onOpenChange={setCopyOpen}>
+        <DialogContent class="max-w-sm">
+          <DialogHeader>
+            <DialogTitle>{t('sidebar.copy_to_connection')}</DialogTitle>
+          </DialogHeader>
+          <form
+            onSubmit={(e) => {
+              e.preventDefault();
+              copyToConnection();
+            }}
+          >
+            <div class="flex flex-col gap-3">
+              <Select
+                value={copyTarget()}
+                options={store.connections.map((c) => c.id)}
+                onChange={(value) => setCopyTarget(value || '')}
+                placeholder={t('sidebar.copy_target')}
+                itemComponent={(props) => (
+                  <SelectItem item={props.item}>
+                    {store.connections.find(
+                      (c) => c.id === props.item.rawValue
+                    )?.label ?? props.item.rawValue}
+                  </SelectItem>
+                )}
+              >
+                <SelectTrigger class="w-full">
+                  <SelectValue<string>>
+                    {(state) =>
+                      store.connections.find(
+                        (c) => c.id === state.selectedOption()
+                      )?.label
+                    }
+                  </SelectValue>
+                </SelectTrigger>
+                <SelectContent />
+              </Select>
+              <TextFieldRoot>
+                <TextField
+                  placeholder={t('sidebar.copy_table_name')}
+                  value={copyTable()}
+                  onInput={(e: InputEvent) =>
+                    setCopyTable((e.target as HTMLInputElement).value)
+                  }
+                />
+              </TextFieldRoot>
+              <Checkbox
+                checked={createTable()}
+                onChange={setCreateTable}
+                class="flex items-center gap-2"
+              >
+                <CheckboxControl class="rounded-md border-accent" />
+                <CheckboxLabel class="text-sm font-medium leading-none">
+                  {t('sidebar.copy_create_table')}
+                </CheckboxLabel>
+              </Checkbox>
+              <DialogFooter>
+                <Button variant="outline" onClick={() => setCopyOpen(false)}>
+                  Cancel
+                </Button>
+                <Button
+                  type="submit"
+                  disabled={!copyTarget() || !copyTable().trim()}
+                >
+                  {t('sidebar.copy_to_connection')}
+                </Button>
+              </DialogFooter>
+            </div>
+          </form>
+        </DialogContent>
+      </Dialog>
     </>
   );
 };
@@ -1,26 +1,40 @@
 import { invoke } from '@tauri-apps/api/core';
 import {
+  CsvOptions,
+  DumpOptions,
+  DumpReport,
+  ImportOptions,
+  ImportReport,
+  JsonOptions,
+  LoadOptions,
+  ObjectType,
+  ParquetOptions,
   QueryMetadataResult,
   QueryTaskEnqueueResult,
   RawQueryResult,
+  ResultColumn,
   Row,
+  SqlOptions,
+  TableOptions,
+  TransferSource,
 } from 'interfaces';
 import { createSignal } from 'solid-js';
 import { select } from 'sql-bricks';
-import { getAnyCase } from 'utils/utils';
+import { getAnyCase, rowsToObjects } from 'utils/utils';
 
 export const BackendService = () => {
   const [pageSize, setPageSize] = createSignal<number>(50);
 
   const getQueryResults = async (
     path: string,
+    columns: ResultColumn[],
     page = 0,
     page_size = pageSize()
   ): Promise<Row[]> => {
     const res = await invoke<string[]>('query_results', {
       params: { path, page, page_size },
     });
-    return res.map((r) => JSON.parse(r));
+    return rowsToObjects(res.map((r) => JSON.parse(r)), columns);
   };
 
   const getQueryMetadata = async (path: string) => {
@@ -61,11 +75,94 @@ export const BackendService = () => {
     return result_sets;
   };
 
-  const downloadJSON = async (source: string, destination: string) =>
-    invoke<string>('download_json', { source, destination });
+  const downloadJSON = async (
+    source: string,
+    destination: string,
+    options?: JsonOptions
+  ) => invoke<string>('download_json', { source, destination, options });
 
-  const downloadCsv = async (source: string, destination: string) =>
-    invoke<string>('download_csv', { source, destination });
+  const downloadCsv = async (
+    source: string,
+    destination: string,
+    options?: CsvOptions
+  ) => invoke<string>('download_csv', { source, destination, options });
+
+  const downloadXlsx = async (sources: string[], destination: string) =>
+    invoke<string>('download_xlsx', { sources, destination });
+
+  const downloadParquet = async (
+    source: string,
+    destination: string,
+    options?: ParquetOptions
+  ) => invoke<number>('download_parquet', { source, destination, options });
+
+  const downloadArrow = async (source: string, destination: string) =>
+    invoke<number>('download_arrow', { source, destination });
+
+  const downloadSql = async (
+    connId: string,
+    source: string,
+    destination: string | null,
+    options?: SqlOptions
+  ) =>
+    invoke<string | null>('download_sql', {
+      connId,
+      source,
+      destination,
+      options,
+    });
+
+  const downloadTable = async (
+    source: string,
+    destination: string | null,
+    options?: TableOptions
+  ) =>
+    invoke<string | null>('download_table', { source, destination, options });
+
+  const importColumns = async (path: string, options?: ImportOptions) =>
+    invoke<string[]>('import_columns', { path, options });
+
+  const importFile = async (
+    connId: string,
+    id: string,
+    path: string,
+    table: string,
+    options?: ImportOptions
+  ) =>
+    invoke<ImportReport>('import_file', { connId, id, path, table, options });
+
+  const transferData = async (
+    sourceConnId: string,
+    source: TransferSource,
+    targetConnId: string,
+    table: string,
+    id: string,
+    options?: LoadOptions
+  ) =>
+    invoke<ImportReport>('transfer_data', {
+      sourceConnId,
+      source,
+      targetConnId,
+      table,
+      id,
+      options,
+    });
+
+  const dumpSchema = async (
+    connId: string,
+    id: string,
+    destination: string,
+    options?: DumpOptions
+  ) => invoke<DumpReport>('dump_schema', { connId, id, destination, options });
+
+  const restoreScript = async (connId: string, id: string, path: string) =>
+    invoke<number>('restore_script', { connId, id, path });
+
+  const getCreateStatement = async (
+    connId: string,
+    objectType: ObjectType,
+    name: string
+  ) => invoke<string>('get_create_statement', { connId, objectType, name });
 
   const cancelTask = (ids: string[]) =>
     invoke<void>('cancel_task_token', { ids });
@@ -78,6 +175,17 @@ export const BackendService = () => {
     getQueryMetadata,
     downloadCsv,
     downloadJSON,
+    downloadSql,
+    downloadXlsx,
+    downloadParquet,
+    downloadArrow,
+    downloadTable,
+    importColumns,
+    importFile,
+    dumpSchema,
+    getCreateStatement,
+    restoreScript,
     selectAllFrom,
+    transferData,
   };
 };
@@ -2,6 +2,7 @@ import { createStore, produce } from 'solid-js/store';
 import { editor, Position } from 'monaco-editor';
 import {
   ConnectionConfig,
+  ConnectionStatusEvent,
   Credentials,
   DialectType,
   ModeType,
@@ -132,6 +133,7 @@ export type ConnectionTab = {
   tabs: ContentTabType[];
   idx: number;
   prevIdx?: number;
+  status?: ConnectionStatusEvent;
 };
 
 // const CONNECTIONS_KEY = '_conn_tabs';
@@ -376,6 +378,12 @@ export const ConnectionsService = () => {
     updateStore();
   };
 
+  const setConnectionStatus = (event: ConnectionStatusEvent) => {
+    const idx = store.connections.findIndex((c) => c.id === event.conn_id);
+    if (idx < 0) return;
+    setStore('connections', idx, 'status', event);
+  };
+
   const updateSchemaDefinition = (data: Schema) => {
     const conn = getConnection();
     const schema = conn.selectedSchema;
@@ -533,6 +541,7 @@ export const ConnectionsService = () => {
     removeContentTab,
     updateConnection,
     updateConnectionTab,
+    setConnectionStatus,
     selectPrevQuery,
     selectNextQuery,
     queryIdx,
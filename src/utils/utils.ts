@@ -1,4 +1,12 @@
-import { Dialect, DialectType, Row, Table } from 'interfaces';
+import {
+  ColumnKind,
+  Dialect,
+  DialectType,
+  JSONValue,
+  ResultColumn,
+  Row,
+  Table,
+} from 'interfaces';
 import { check } from '@tauri-apps/plugin-updater';
 import { relaunch } from '@tauri-apps/plugin-process';
 import { toast } from 'solid-sonner';
@@ -89,6 +97,98 @@ export const columnsToTables = (
   };
 };
 
+// Decimals and 64-bit integers arrive as strings of their digits, numbers above
+// 2^53 can't be held by a JS number, and binary data as 0x prefixed hex. The kind
+// of the result column tells them apart from text.
+export const isNumericKind = (kind?: ColumnKind) =>
+  kind === 'integer' || kind === 'float' || kind === 'decimal';
+
+const isMissing = (val: unknown) => val === null || val === undefined;
+
+// Orders numbers and the digits of exact numbers by value rather than as text.
+export const compareNumbers = (a: unknown, b: unknown) => {
+  if (isMissing(a) || isMissing(b))
+    return Number(isMissing(b)) - Number(isMissing(a));
+  const [x, y] = [String(a), String(b)];
+  if (/^-?\d+$/.test(x) && /^-?\d+$/.test(y)) {
+    const [m, n] = [BigInt(x), BigInt(y)];
+    return m === n ? 0 : m < n ? -1 : 1;
+  }
+  return Number(x) - Number(y);
+};
+
+const binaryHex = (kind: ColumnKind | undefined, val: unknown) => {
+  if (kind !== 'binary' || typeof val !== 'string') return;
+  if (val.startsWith('0x')) return val.slice(2);
+};
+
+// Formats are told by the first bytes only, so long values are never scanned.
+const SIGNATURES: [string, string][] = [
+  ['89504e470d0a1a0a', 'png'],
+  ['ffd8ff', 'jpeg'],
+  ['474946383761', 'gif'],
+  ['474946383961', 'gif'],
+  ['255044462d', 'pdf'],
+  ['1f8b', 'gzip'],
+  ['504b0304', 'zip'],
+  ['53514c69746520666f726d6174203300', 'sqlite'],
+  ['424d', 'bmp'],
+  ['7b', 'json'],
+  ['5b', 'json'],
+];
+
+const binaryFormat = (hex: string) => {
+  if (hex.startsWith('52494646') && hex.slice(16, 24) === '57454250')
+    return 'webp';
+  return SIGNATURES.find(([magic]) => hex.startsWith(magic))?.[1];
+};
+
+// Long binary values are cut short for display, with their format and size.
+export const binaryValue = (kind: ColumnKind | undefined, val: unknown) => {
+  const hex = binaryHex(kind, val);
+  if (hex === undefined) return;
+  if (hex.length <= 64) return `0x${hex}`;
+  const format = binaryFormat(hex);
+  const label = format ? `${format}, ` : '';
+  return `0x${hex.slice(0, 64)}… (${label}${hex.length / 2} bytes)`;
+};
+
+const IMAGE_FORMATS = ['png', 'jpeg', 'gif', 'webp', 'bmp'];
+
+// Whether a binary value holds an image, from its first bytes only.
+export const isBinaryImage = (kind: ColumnKind | undefined, val: unknown) => {
+  const hex = binaryHex(kind, val);
+  return !!hex && IMAGE_FORMATS.includes(binaryFormat(hex) ?? '');
+};
+
+// A data url for binary values holding an image, built only when previewed.
+export const binaryImageUrl = (kind: ColumnKind | undefined, val: unknown) => {
+  const hex = binaryHex(kind, val);
+  const format = hex && binaryFormat(hex);
+  if (!hex || !format || !IMAGE_FORMATS.includes(format)) return;
+  const bytes = hex.match(/../g) ?? [];
+  const binary = bytes.map((b) => String.fromCharCode(parseInt(b, 16)));
+  return `data:image/${format};base64,${btoa(binary.join(''))}`;
+};
+
+// Keys for positional rows, repeated column names get a `_2`, `_3`... suffix.
+export const uniqueNames = (columns: ResultColumn[]) =>
+  columns.reduce<string[]>((names, { name }) => {
+    let key = name;
+    for (let n = 2; names.includes(key); n++) key = `${name}_${n}`;
+    return [...names, key];
+  }, []);
+
+export const rowsToObjects = (
+  rows: JSONValue[][],
+  columns: ResultColumn[]
+): Row[] => {
+  const names = uniqueNames(columns);
+  return rows.map((row) =>
+    Object.fromEntries(names.map((name, i) => [name, row[i]]))
+  );
+};
+
 export const parseObjRecursive = (
   obj: unknown
 ): Record<string, unknown | unknown[]> | null | unknown => {